@@ -49,11 +49,7 @@ fn main() {
 
     // Print tsv headers
     println!(
-        "{}\t{}\t{}\t{}",
-        "Compression level",
-        "Compression ratio",
-        "Compression speed",
-        "Decompression speed"
+        "Compression level\tCompression ratio\tCompression speed\tDecompression speed"
     );
 
     for level in args.begin..args.end {
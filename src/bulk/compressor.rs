@@ -1,5 +1,7 @@
 use crate::map_error_code;
 
+#[cfg(feature = "experimental")]
+use std::alloc::GlobalAlloc;
 use std::io;
 use zstd_safe;
 
@@ -34,9 +36,44 @@ impl Compressor<'static> {
 
         Ok(compressor)
     }
+
+    /// Creates a new zstd compressor, configured with the given parameters.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_cctx_params(
+        params: &zstd_safe::CCtxParams,
+    ) -> io::Result<Self> {
+        let mut compressor = Self::default();
+
+        compressor.set_cctx_params(params)?;
+
+        Ok(compressor)
+    }
+
+    /// Creates a new zstd compressor, using the threads of a shared pool.
+    #[cfg(all(feature = "experimental", feature = "zstdmt"))]
+    #[cfg_attr(
+        feature = "doc-cfg",
+        doc(cfg(all(feature = "experimental", feature = "zstdmt")))
+    )]
+    pub fn with_thread_pool(
+        level: i32,
+        pool: &crate::ThreadPool,
+    ) -> io::Result<Self> {
+        let mut compressor = Self::new(level)?;
+
+        compressor.set_thread_pool(pool)?;
+
+        Ok(compressor)
+    }
 }
 
 impl<'a> Compressor<'a> {
+    #[cfg(feature = "experimental")]
+    fn from_context(context: zstd_safe::CCtx<'a>) -> Self {
+        Compressor { context }
+    }
+
     /// Creates a new compressor using an existing `EncoderDictionary`.
     ///
     /// The compression level will be the one specified when creating the dictionary.
@@ -56,6 +93,85 @@ impl<'a> Compressor<'a> {
         Ok(compressor)
     }
 
+    /// Creates a new compressor, using a dictionary loaded and interpreted
+    /// as specified.
+    ///
+    /// Note that using a dictionary means that decompression will need to use
+    /// the same dictionary.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_dictionary_advanced(
+        level: i32,
+        dictionary: &'a [u8],
+        load_method: zstd_safe::DictLoadMethod,
+        content_type: zstd_safe::DictContentType,
+    ) -> io::Result<Self> {
+        let mut compressor = Self::default();
+
+        compressor.set_dictionary_advanced(
+            level,
+            dictionary,
+            load_method,
+            content_type,
+        )?;
+
+        Ok(compressor)
+    }
+
+    /// Creates a new compressor using `allocator` for all its memory.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_allocator<A: GlobalAlloc + Sync>(
+        level: i32,
+        allocator: &'a A,
+    ) -> io::Result<Self> {
+        let context = zstd_safe::CCtx::try_create_with_allocator(allocator)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::OutOfMemory,
+                    "failed to allocate zstd context",
+                )
+            })?;
+        let mut compressor = Compressor::from_context(context);
+
+        compressor
+            .set_parameter(zstd_safe::CParameter::CompressionLevel(level))?;
+
+        Ok(compressor)
+    }
+
+    /// Creates a new compressor living in the given workspace.
+    ///
+    /// The compressor will never allocate memory on its own: use
+    /// [`compress_to_buffer`](Self::compress_to_buffer) to also avoid
+    /// allocating the output.
+    ///
+    /// [`zstd_safe::estimate_cctx_size`] gives the size needed by
+    /// `workspace` to compress with `level` (or any lower level).
+    ///
+    /// Such a compressor cannot use [`set_dictionary`](Self::set_dictionary)
+    /// with a non-empty dictionary, but can use prepared dictionaries.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn in_workspace(
+        workspace: &'a mut [u8],
+        level: i32,
+    ) -> io::Result<Self> {
+        let context =
+            zstd_safe::CCtx::init_static(workspace).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "workspace is too small",
+                )
+            })?;
+        let mut compressor = Compressor::from_context(context);
+
+        compressor
+            .set_parameter(zstd_safe::CParameter::CompressionLevel(level))?;
+
+        Ok(compressor)
+    }
+
     /// Changes the compression level used by this compressor.
     ///
     /// *This will clear any dictionary previously registered.*
@@ -108,6 +224,67 @@ impl<'a> Compressor<'a> {
         Ok(())
     }
 
+    /// Changes the compression level and the dictionary, loaded and
+    /// interpreted as specified.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn set_dictionary_advanced(
+        &mut self,
+        level: i32,
+        dictionary: &'a [u8],
+        load_method: zstd_safe::DictLoadMethod,
+        content_type: zstd_safe::DictContentType,
+    ) -> io::Result<()> {
+        self.context
+            .set_parameter(zstd_safe::CParameter::CompressionLevel(level))
+            .map_err(map_error_code)?;
+
+        self.context
+            .load_dictionary_advanced(dictionary, load_method, content_type)
+            .map_err(map_error_code)?;
+
+        Ok(())
+    }
+
+    /// Compresses using the threads of a shared pool.
+    ///
+    /// This also sets the number of workers to the size of the pool.
+    ///
+    /// Fails if a different pool was already set.
+    #[cfg(all(feature = "experimental", feature = "zstdmt"))]
+    #[cfg_attr(
+        feature = "doc-cfg",
+        doc(cfg(all(feature = "experimental", feature = "zstdmt")))
+    )]
+    pub fn set_thread_pool(
+        &mut self,
+        pool: &crate::ThreadPool,
+    ) -> io::Result<()> {
+        pool.attach(&mut self.context)
+    }
+
+    /// Uses the given sequence producer to find matches.
+    ///
+    /// If the producer fails or panics on a block, the built-in match finder
+    /// is used instead.
+    ///
+    /// See [`zstd_safe::CCtx::register_sequence_producer`].
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn set_sequence_producer<P>(
+        &mut self,
+        producer: &'a mut P,
+    ) -> io::Result<()>
+    where
+        P: zstd_safe::SequenceProducer + Send,
+    {
+        self.context
+            .register_sequence_producer(producer)
+            .map_err(map_error_code)?;
+
+        Ok(())
+    }
+
     /// Compress a single block of data to the given destination buffer.
     ///
     /// Returns the number of bytes written, or an error if something happened
@@ -154,7 +331,63 @@ impl<'a> Compressor<'a> {
         Ok(())
     }
 
+    /// Sets all compression parameters at once.
+    ///
+    /// Nothing is changed if any of them is invalid.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn set_compression_parameters(
+        &mut self,
+        parameters: &zstd_safe::CompressionParameters,
+    ) -> io::Result<()> {
+        self.context
+            .set_compression_parameters(parameters)
+            .map_err(map_error_code)?;
+        Ok(())
+    }
+
+    /// Applies all the parameters from the given set.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn set_cctx_params(
+        &mut self,
+        params: &zstd_safe::CCtxParams,
+    ) -> io::Result<()> {
+        self.context
+            .set_parameters_using_cctx_params(params)
+            .map_err(map_error_code)?;
+        Ok(())
+    }
+
+    /// Sets all compression and frame parameters at once.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn set_parameters(
+        &mut self,
+        parameters: &zstd_safe::Parameters,
+    ) -> io::Result<()> {
+        self.context
+            .set_parameters(parameters)
+            .map_err(map_error_code)?;
+        Ok(())
+    }
+
+    /// Returns the raw value of a compression parameter.
+    ///
+    /// Only the kind of `parameter` is used: the value it holds is ignored.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn get_parameter(
+        &self,
+        parameter: zstd_safe::CParameter,
+    ) -> io::Result<i32> {
+        self.context
+            .get_parameter(parameter)
+            .map_err(map_error_code)
+    }
+
     crate::encoder_parameters!();
+    crate::encoder_getters!();
 }
 
 fn _assert_traits() {
@@ -1,5 +1,8 @@
+use crate::error::{Error, Position};
 use crate::map_error_code;
 
+#[cfg(feature = "experimental")]
+use std::alloc::GlobalAlloc;
 #[cfg(feature = "experimental")]
 use std::convert::TryInto;
 use std::io;
@@ -47,6 +50,69 @@ impl<'a> Decompressor<'a> {
         Ok(decompressor)
     }
 
+    /// Creates a new decompressor, using a dictionary loaded and interpreted
+    /// as specified.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_dictionary_advanced(
+        dictionary: &'a [u8],
+        load_method: zstd_safe::DictLoadMethod,
+        content_type: zstd_safe::DictContentType,
+    ) -> io::Result<Self> {
+        let mut decompressor = Self::default();
+
+        decompressor.set_dictionary_advanced(
+            dictionary,
+            load_method,
+            content_type,
+        )?;
+
+        Ok(decompressor)
+    }
+
+    /// Creates a new decompressor using `allocator` for all its memory.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_allocator<A: GlobalAlloc + Sync>(
+        allocator: &'a A,
+    ) -> io::Result<Self> {
+        let context = zstd_safe::DCtx::try_create_with_allocator(allocator)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::OutOfMemory,
+                    "failed to allocate zstd context",
+                )
+            })?;
+
+        Ok(Decompressor { context })
+    }
+
+    /// Creates a new decompressor living in the given workspace.
+    ///
+    /// The decompressor will never allocate memory on its own: use
+    /// [`decompress_to_buffer`](Self::decompress_to_buffer) to also avoid
+    /// allocating the output.
+    ///
+    /// [`zstd_safe::estimate_dctx_size`] gives the size needed by
+    /// `workspace`.
+    ///
+    /// Such a decompressor cannot use [`set_dictionary`](Self::set_dictionary)
+    /// with a non-empty dictionary, but can use prepared dictionaries. It does
+    /// not support legacy formats either.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn in_workspace(workspace: &'a mut [u8]) -> io::Result<Self> {
+        let context =
+            zstd_safe::DCtx::init_static(workspace).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "workspace is too small",
+                )
+            })?;
+
+        Ok(Decompressor { context })
+    }
+
     /// Changes the dictionary used by this decompressor.
     ///
     /// Will affect future compression jobs.
@@ -79,6 +145,23 @@ impl<'a> Decompressor<'a> {
         Ok(())
     }
 
+    /// Changes the dictionary used by this decompressor, loaded and
+    /// interpreted as specified.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn set_dictionary_advanced(
+        &mut self,
+        dictionary: &'a [u8],
+        load_method: zstd_safe::DictLoadMethod,
+        content_type: zstd_safe::DictContentType,
+    ) -> io::Result<()> {
+        self.context
+            .load_dictionary_advanced(dictionary, load_method, content_type)
+            .map_err(map_error_code)?;
+
+        Ok(())
+    }
+
     /// Deompress a single block of data to the given destination buffer.
     ///
     /// Returns the number of bytes written, or an error if something happened
@@ -88,9 +171,62 @@ impl<'a> Decompressor<'a> {
         source: &[u8],
         destination: &mut C,
     ) -> io::Result<usize> {
+        match self.context.decompress(destination, source) {
+            Ok(written) => Ok(written),
+            Err(code) => Err(self.locate_error(source, code).into()),
+        }
+    }
+
+    /// Finds where in `source` the error `code` happens.
+    ///
+    /// The one-shot API doesn't report how far it went, so this replays the
+    /// decompression in streaming mode, discarding the output. The position
+    /// is only attached if the replay fails with the same error.
+    fn locate_error(
+        &mut self,
+        source: &[u8],
+        code: zstd_safe::ErrorCode,
+    ) -> Error {
+        let error = Error::new(code);
+
+        // Keeps parameters and dictionary.
+        if self
+            .context
+            .reset(zstd_safe::ResetDirective::SessionOnly)
+            .is_err()
+        {
+            return error;
+        }
+
+        let mut position = Position::default();
+        let mut scratch = vec![0u8; zstd_safe::DCtx::out_size()];
+        let mut input = zstd_safe::InBuffer::around(source);
+        let located = loop {
+            let mut output = zstd_safe::OutBuffer::around(&mut scratch[..]);
+            let before = input.pos();
+            match self.context.decompress_stream(&mut output, &mut input) {
+                Ok(hint) => {
+                    let consumed = input.pos() - before;
+                    position.advance(consumed, output.pos());
+                    if hint == 0 {
+                        position.frame_index += 1;
+                    }
+                    if consumed == 0 && output.pos() == 0 {
+                        // Ran out of input without hitting the error.
+                        break error;
+                    }
+                }
+                Err(replay_code) if replay_code == code => {
+                    break error.at(position)
+                }
+                Err(_) => break error,
+            }
+        };
+
         self.context
-            .decompress(destination, source)
-            .map_err(map_error_code)
+            .reset(zstd_safe::ResetDirective::SessionOnly)
+            .ok();
+        located
     }
 
     /// Decompress a block of data, and return the result in a `Vec<u8>`.
@@ -120,7 +256,22 @@ impl<'a> Decompressor<'a> {
         Ok(())
     }
 
+    /// Returns the raw value of a decompression parameter.
+    ///
+    /// Only the kind of `parameter` is used: the value it holds is ignored.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn get_parameter(
+        &self,
+        parameter: zstd_safe::DParameter,
+    ) -> io::Result<i32> {
+        self.context
+            .get_parameter(parameter)
+            .map_err(map_error_code)
+    }
+
     crate::decoder_parameters!();
+    crate::decoder_getters!();
 
     /// Get an upper bound on the decompressed size of data, if available
     ///
@@ -40,3 +40,292 @@ fn has_content_size() {
         Some(TEXT.len() as u64)
     );
 }
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_sequence_producer() {
+    use super::Compressor;
+    use zstd_safe::{Sequence, SequenceProducer};
+
+    // Emits the whole block as literals, or gives up.
+    struct Literals {
+        calls: usize,
+        fail: bool,
+        short_match: bool,
+    }
+
+    impl SequenceProducer for Literals {
+        fn produce_sequences(
+            &mut self,
+            sequences: &mut [Sequence],
+            src: &[u8],
+            _dict: &[u8],
+            _compression_level: i32,
+            _window_size: usize,
+        ) -> Option<usize> {
+            self.calls += 1;
+            if self.fail {
+                panic!("producer failure");
+            }
+            if self.short_match {
+                sequences[0] = Sequence {
+                    literal_length: 1,
+                    offset: 1,
+                    match_length: 1,
+                    ..Sequence::default()
+                };
+                sequences[1] = Sequence {
+                    literal_length: src.len() as u32 - 2,
+                    ..Sequence::default()
+                };
+                return Some(2);
+            }
+            sequences[0] = Sequence {
+                literal_length: src.len() as u32,
+                ..Sequence::default()
+            };
+            Some(1)
+        }
+    }
+
+    let default_size = compress(TEXT.as_bytes(), 1).unwrap().len();
+
+    let mut producer = Literals {
+        calls: 0,
+        fail: false,
+        short_match: false,
+    };
+    let mut compressor = Compressor::new(1).unwrap();
+    compressor.set_sequence_producer(&mut producer).unwrap();
+    let compressed = compressor.compress(TEXT.as_bytes()).unwrap();
+    drop(compressor);
+
+    assert!(producer.calls > 0);
+    // Without any match, only entropy coding helps.
+    assert!(compressed.len() > default_size);
+    assert_eq!(
+        decompress(&compressed, TEXT.len()).unwrap(),
+        TEXT.as_bytes()
+    );
+
+    // Panics fall back to the built-in match finder.
+    let mut producer = Literals {
+        calls: 0,
+        fail: true,
+        short_match: false,
+    };
+    let mut compressor = Compressor::new(1).unwrap();
+    compressor.set_sequence_producer(&mut producer).unwrap();
+    let compressed = compressor.compress(TEXT.as_bytes()).unwrap();
+    drop(compressor);
+
+    assert!(producer.calls > 0);
+    assert_eq!(compressed.len(), default_size);
+    assert_eq!(
+        decompress(&compressed, TEXT.len()).unwrap(),
+        TEXT.as_bytes()
+    );
+
+    // Invalid sequences fall back too, even without validation.
+    let mut producer = Literals {
+        calls: 0,
+        fail: false,
+        short_match: true,
+    };
+    let mut compressor = Compressor::new(1).unwrap();
+    compressor.set_sequence_producer(&mut producer).unwrap();
+    compressor
+        .set_parameter(zstd_safe::CParameter::ValidateSequences(false))
+        .unwrap();
+    let compressed = compressor.compress(TEXT.as_bytes()).unwrap();
+    drop(compressor);
+
+    assert!(producer.calls > 0);
+    assert_eq!(compressed.len(), default_size);
+    assert_eq!(
+        decompress(&compressed, TEXT.len()).unwrap(),
+        TEXT.as_bytes()
+    );
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_in_workspace() {
+    use super::{Compressor, Decompressor};
+
+    let mut workspace = vec![0u8; zstd_safe::estimate_cctx_size(3)];
+    let mut compressor = Compressor::in_workspace(&mut workspace, 3).unwrap();
+    let mut compressed = vec![0u8; zstd_safe::compress_bound(TEXT.len())];
+    let written = compressor
+        .compress_to_buffer(TEXT.as_bytes(), &mut compressed[..])
+        .unwrap();
+    compressed.truncate(written);
+
+    let mut workspace = vec![0u8; zstd_safe::estimate_dctx_size()];
+    let mut decompressor = Decompressor::in_workspace(&mut workspace).unwrap();
+    let mut decompressed = vec![0u8; TEXT.len()];
+    let read = decompressor
+        .decompress_to_buffer(&compressed, &mut decompressed[..])
+        .unwrap();
+    assert_eq!(&decompressed[..read], TEXT.as_bytes());
+
+    assert!(Decompressor::in_workspace(&mut [0u8; 16]).is_err());
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_with_allocator() {
+    use super::{Compressor, Decompressor};
+    use std::alloc::System;
+
+    // zstd-safe checks that allocations go through the allocator.
+    let mut compressor = Compressor::with_allocator(3, &System).unwrap();
+    let compressed = compressor.compress(TEXT.as_bytes()).unwrap();
+
+    let mut decompressor = Decompressor::with_allocator(&System).unwrap();
+    let decompressed =
+        decompressor.decompress(&compressed, TEXT.len()).unwrap();
+    assert_eq!(decompressed, TEXT.as_bytes());
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_compression_parameters() {
+    use super::Compressor;
+    use zstd_safe::{CompressionParameters, Strategy};
+
+    let mut params = CompressionParameters::new(3, Some(TEXT.len() as u64), 0);
+    params.strategy = Strategy::ZSTD_btultra2;
+
+    let mut compressor = Compressor::new(3).unwrap();
+    compressor.set_compression_parameters(&params).unwrap();
+    let compressed = compressor.compress(TEXT.as_bytes()).unwrap();
+    assert_eq!(
+        decompress(&compressed, TEXT.len()).unwrap(),
+        TEXT.as_bytes()
+    );
+
+    params.min_match = 0;
+    assert!(compressor.set_compression_parameters(&params).is_err());
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_get_parameters() {
+    use super::{Compressor, Decompressor};
+
+    let mut compressor = Compressor::new(7).unwrap();
+    compressor.include_checksum(true).unwrap();
+    compressor.window_log(20).unwrap();
+    assert_eq!(compressor.get_compression_level().unwrap(), 7);
+    assert_eq!(compressor.get_window_log().unwrap(), 20);
+    assert!(compressor.get_checksum_flag().unwrap());
+    assert!(compressor.get_content_size_flag().unwrap());
+
+    let mut decompressor = Decompressor::new().unwrap();
+    decompressor.window_log_max(24).unwrap();
+    assert_eq!(decompressor.get_window_log_max().unwrap(), 24);
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_dict_content_type() {
+    use super::{Compressor, Decompressor};
+    use crate::dict::{
+        DecoderDictionary, DictContentType, DictLoadMethod, EncoderDictionary,
+    };
+
+    // Raw content that happens to start with the dictionary magic number.
+    let mut dict = zstd_safe::MAGIC_DICTIONARY.to_le_bytes().to_vec();
+    dict.extend_from_slice(&TEXT.as_bytes()[..512]);
+    let sample = &TEXT.as_bytes()[512..];
+
+    assert!(EncoderDictionary::new_advanced(
+        &dict,
+        3,
+        DictLoadMethod::ByRef,
+        DictContentType::FullDict
+    )
+    .is_err());
+    let encoder_dict = EncoderDictionary::new_advanced(
+        &dict,
+        3,
+        DictLoadMethod::ByRef,
+        DictContentType::RawContent,
+    )
+    .unwrap();
+    let decoder_dict = DecoderDictionary::new_advanced(
+        &dict,
+        DictLoadMethod::ByRef,
+        DictContentType::RawContent,
+    )
+    .unwrap();
+
+    let compressed = Compressor::with_prepared_dictionary(&encoder_dict)
+        .unwrap()
+        .compress(sample)
+        .unwrap();
+    let decompressed = Decompressor::with_dictionary_advanced(
+        &dict,
+        DictLoadMethod::ByCopy,
+        DictContentType::RawContent,
+    )
+    .unwrap()
+    .decompress(&compressed, sample.len())
+    .unwrap();
+    assert_eq!(decompressed, sample);
+
+    let compressed = Compressor::with_dictionary_advanced(
+        3,
+        &dict,
+        DictLoadMethod::ByCopy,
+        DictContentType::RawContent,
+    )
+    .unwrap()
+    .compress(sample)
+    .unwrap();
+    let decompressed = Decompressor::with_prepared_dictionary(&decoder_dict)
+        .unwrap()
+        .decompress(&compressed, sample.len())
+        .unwrap();
+    assert_eq!(decompressed, sample);
+}
+
+#[cfg(all(feature = "experimental", feature = "zstdmt"))]
+#[test]
+fn test_thread_pool() {
+    use super::Compressor;
+
+    let pool = crate::ThreadPool::new(2).unwrap();
+    let mut compressor = Compressor::with_thread_pool(3, &pool).unwrap();
+    drop(pool);
+    assert_eq!(compressor.get_nb_workers().unwrap(), 2);
+
+    let compressed = compressor.compress(TEXT.as_bytes()).unwrap();
+    assert_eq!(
+        decompress(&compressed, TEXT.len()).unwrap(),
+        TEXT.as_bytes()
+    );
+
+    // zstd would keep using the first pool.
+    let other = crate::ThreadPool::new(2).unwrap();
+    assert!(compressor.set_thread_pool(&other).is_err());
+    drop(other);
+    let compressed = compressor.compress(TEXT.as_bytes()).unwrap();
+    assert_eq!(
+        decompress(&compressed, TEXT.len()).unwrap(),
+        TEXT.as_bytes()
+    );
+
+    // The pool follows the context when it is moved out.
+    let mut context = zstd_safe::CCtx::create();
+    std::mem::swap(compressor.context_mut(), &mut context);
+    drop(compressor);
+    let mut compressed =
+        Vec::with_capacity(zstd_safe::compress_bound(TEXT.len()));
+    context.compress2(&mut compressed, TEXT.as_bytes()).unwrap();
+    assert_eq!(
+        decompress(&compressed, TEXT.len()).unwrap(),
+        TEXT.as_bytes()
+    );
+}
@@ -15,9 +15,21 @@
 //! [`Decoder::with_dictionary`]: ../struct.Decoder.html#method.with_dictionary
 
 #[cfg(feature = "zdict_builder")]
-use std::io::{self, Read};
+use std::convert::TryInto;
+#[cfg(any(feature = "zdict_builder", feature = "experimental"))]
+use std::io;
+#[cfg(feature = "zdict_builder")]
+use std::io::Read;
+
+use std::num::NonZeroU32;
 
 pub use zstd_safe::{CDict, DDict};
+#[cfg(feature = "experimental")]
+pub use zstd_safe::{DictContentType, DictLoadMethod};
+
+#[cfg(feature = "zdict_builder")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "zdict_builder")))]
+pub use zstd_safe::DictParameters;
 
 /// Prepared dictionary for compression
 ///
@@ -25,6 +37,7 @@ pub use zstd_safe::{CDict, DDict};
 /// to a separate buffer (if it has another lifetime).
 pub struct EncoderDictionary<'a> {
     cdict: CDict<'a>,
+    level: i32,
 }
 
 impl EncoderDictionary<'static> {
@@ -34,6 +47,7 @@ impl EncoderDictionary<'static> {
     pub fn copy(dictionary: &[u8], level: i32) -> Self {
         Self {
             cdict: zstd_safe::create_cdict(dictionary, level),
+            level,
         }
     }
 }
@@ -49,13 +63,54 @@ impl<'a> EncoderDictionary<'a> {
     pub fn new(dictionary: &'a [u8], level: i32) -> Self {
         Self {
             cdict: zstd_safe::CDict::create_by_reference(dictionary, level),
+            level,
         }
     }
 
+    /// Creates a prepared dictionary for compression, loaded and interpreted
+    /// as specified.
+    ///
+    /// Fails if the dictionary is invalid for the given content type, for
+    /// example a raw buffer used with `DictContentType::FullDict`.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn new_advanced(
+        dictionary: &'a [u8],
+        level: i32,
+        load_method: zstd_safe::DictLoadMethod,
+        content_type: zstd_safe::DictContentType,
+    ) -> io::Result<Self> {
+        let cdict = zstd_safe::CDict::try_create_advanced(
+            dictionary,
+            load_method,
+            content_type,
+            level,
+        )
+        .ok_or_else(invalid_dictionary)?;
+        Ok(Self { cdict, level })
+    }
+
     /// Returns reference to `CDict` inner object
     pub fn as_cdict(&self) -> &CDict<'a> {
         &self.cdict
     }
+
+    /// Returns the dictionary ID, or `None` for a raw content dictionary.
+    pub fn get_dict_id(&self) -> Option<NonZeroU32> {
+        self.cdict.get_dict_id()
+    }
+
+    /// Returns the memory used by this dictionary, in bytes.
+    pub fn sizeof(&self) -> usize {
+        self.cdict.sizeof()
+    }
+
+    /// Returns the compression level this dictionary was prepared for.
+    ///
+    /// A level of `0` means zstd's default.
+    pub fn get_compression_level(&self) -> i32 {
+        self.level
+    }
 }
 
 /// Prepared dictionary for decompression
@@ -86,10 +141,131 @@ impl<'a> DecoderDictionary<'a> {
         }
     }
 
+    /// Creates a prepared dictionary for decompression, loaded and
+    /// interpreted as specified.
+    ///
+    /// Fails if the dictionary is invalid for the given content type, for
+    /// example a raw buffer used with `DictContentType::FullDict`.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn new_advanced(
+        dictionary: &'a [u8],
+        load_method: zstd_safe::DictLoadMethod,
+        content_type: zstd_safe::DictContentType,
+    ) -> io::Result<Self> {
+        let ddict = zstd_safe::DDict::try_create_advanced(
+            dictionary,
+            load_method,
+            content_type,
+        )
+        .ok_or_else(invalid_dictionary)?;
+        Ok(Self { ddict })
+    }
+
     /// Returns reference to `DDict` inner object
     pub fn as_ddict(&self) -> &DDict<'a> {
         &self.ddict
     }
+
+    /// Returns the dictionary ID, or `None` for a raw content dictionary.
+    pub fn get_dict_id(&self) -> Option<NonZeroU32> {
+        self.ddict.get_dict_id()
+    }
+
+    /// Returns the memory used by this dictionary, in bytes.
+    pub fn sizeof(&self) -> usize {
+        self.ddict.sizeof()
+    }
+}
+
+#[cfg(feature = "experimental")]
+fn invalid_dictionary() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "failed to create dictionary")
+}
+
+/// Metadata about a dictionary, as found in its header.
+///
+/// Dictionaries come in two flavours:
+/// * Full dictionaries, as produced by the training functions, start with
+///   the `MAGIC_DICTIONARY` magic number, followed by the dictionary ID and
+///   entropy tables, and end with the content.
+/// * Raw content dictionaries are any other buffer, used entirely as
+///   content. They have no ID.
+#[cfg(feature = "zdict_builder")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "zdict_builder")))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DictionaryInfo {
+    magic: Option<u32>,
+    dict_id: Option<NonZeroU32>,
+    header_size: usize,
+    content_size: usize,
+}
+
+#[cfg(feature = "zdict_builder")]
+impl DictionaryInfo {
+    /// Reads the header of the given dictionary.
+    ///
+    /// Fails if the dictionary starts with the `MAGIC_DICTIONARY` magic
+    /// number, but its header or entropy tables are invalid.
+    pub fn parse(dictionary: &[u8]) -> io::Result<Self> {
+        use crate::map_error_code;
+
+        let magic = dictionary
+            .get(..4)
+            .map(|magic| u32::from_le_bytes(magic.try_into().unwrap()));
+
+        if magic != Some(zstd_safe::MAGIC_DICTIONARY) {
+            return Ok(DictionaryInfo {
+                magic,
+                dict_id: None,
+                header_size: 0,
+                content_size: dictionary.len(),
+            });
+        }
+
+        let header_size = zstd_safe::get_dict_header_size(dictionary)
+            .map_err(map_error_code)?;
+        Ok(DictionaryInfo {
+            magic,
+            dict_id: zstd_safe::get_dict_id(dictionary),
+            header_size,
+            content_size: dictionary.len() - header_size,
+        })
+    }
+
+    /// Returns the first 4 bytes of the dictionary, as a little-endian
+    /// number.
+    ///
+    /// This is `MAGIC_DICTIONARY` for full dictionaries, and `None` if the
+    /// dictionary is shorter than 4 bytes.
+    pub fn magic(&self) -> Option<u32> {
+        self.magic
+    }
+
+    /// Returns `true` if this is a raw content dictionary.
+    pub fn is_raw_content(&self) -> bool {
+        self.magic != Some(zstd_safe::MAGIC_DICTIONARY)
+    }
+
+    /// Returns the dictionary ID.
+    ///
+    /// This is `None` for raw content dictionaries, and for full
+    /// dictionaries with an ID of `0`.
+    pub fn dict_id(&self) -> Option<NonZeroU32> {
+        self.dict_id
+    }
+
+    /// Returns the size of the header and entropy tables.
+    ///
+    /// This is `0` for raw content dictionaries.
+    pub fn header_size(&self) -> usize {
+        self.header_size
+    }
+
+    /// Returns the size of the content, that is everything after the header.
+    pub fn content_size(&self) -> usize {
+        self.content_size
+    }
 }
 
 /// Train a dictionary from a big continuous chunk of data, with all samples
@@ -100,7 +276,7 @@ impl<'a> DecoderDictionary<'a> {
 ///
 /// * `sample_data` is the concatenation of all sample data.
 /// * `sample_sizes` is the size of each sample in `sample_data`.
-///     The sum of all `sample_sizes` should equal the length of `sample_data`.
+///   The sum of all `sample_sizes` should equal the length of `sample_data`.
 /// * `max_size` is the maximum size of the dictionary to generate.
 ///
 /// The result is the dictionary data. You can, for example, feed it to [`CDict::create`].
@@ -113,18 +289,103 @@ pub fn from_continuous(
 ) -> io::Result<Vec<u8>> {
     use crate::map_error_code;
 
-    // Complain if the lengths don't add up to the entire data.
+    check_sample_sizes(sample_data, sample_sizes)?;
+
+    let mut result = Vec::with_capacity(max_size);
+    zstd_safe::train_from_buffer(&mut result, sample_data, sample_sizes)
+        .map_err(map_error_code)?;
+    Ok(result)
+}
+
+/// Turns raw content into a dictionary.
+///
+/// This adds a header, with the dictionary ID from `parameters`, and entropy
+/// tables computed from `samples`. The content is kept as is: make sure the
+/// most useful parts are at the end, as they are the cheapest to reference.
+///
+/// * `content` is the raw dictionary content, for example hand-curated
+///   strings that frequently appear in the data.
+/// * `samples` is a list of samples representative of the data to compress.
+/// * `parameters` sets the dictionary ID and the compression level the
+///   dictionary is tuned for. A `dict_id` of `0` derives one from a hash of
+///   the content.
+///
+/// The result can be given to [`EncoderDictionary::copy`] and
+/// [`DecoderDictionary::copy`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # let content = b"";
+/// # let samples: Vec<Vec<u8>> = Vec::new();
+/// // Use the schema version as dictionary ID.
+/// let dictionary = zstd::dict::finalize(
+///     content,
+///     &samples,
+///     zstd::dict::DictParameters {
+///         dict_id: 42,
+///         compression_level: 19,
+///         ..Default::default()
+///     },
+/// ).unwrap();
+/// ```
+#[cfg(feature = "zdict_builder")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "zdict_builder")))]
+pub fn finalize<S: AsRef<[u8]>>(
+    content: &[u8],
+    samples: &[S],
+    parameters: DictParameters,
+) -> io::Result<Vec<u8>> {
+    use crate::map_error_code;
+
+    // The header and entropy tables take at most this many bytes. See
+    // `HBUFFSIZE` in `zdict.c`.
+    const HEADER_CAPACITY: usize = 256;
+
+    let (data, sizes) = concat_samples(samples);
+
+    let mut result = Vec::with_capacity(content.len() + HEADER_CAPACITY);
+    zstd_safe::finalize_dictionary(
+        &mut result,
+        content,
+        &data,
+        &sizes,
+        &parameters,
+    )
+    .map_err(map_error_code)?;
+    Ok(result)
+}
+
+/// Complains if the lengths don't add up to the entire data.
+#[cfg(feature = "zdict_builder")]
+fn check_sample_sizes(
+    sample_data: &[u8],
+    sample_sizes: &[usize],
+) -> io::Result<()> {
     if sample_sizes.iter().sum::<usize>() != sample_data.len() {
         return Err(io::Error::new(
             io::ErrorKind::Other,
             "sample sizes don't add up".to_string(),
         ));
     }
+    Ok(())
+}
 
-    let mut result = Vec::with_capacity(max_size);
-    zstd_safe::train_from_buffer(&mut result, sample_data, sample_sizes)
-        .map_err(map_error_code)?;
-    Ok(result)
+/// Copies every sample to a big chunk of memory.
+///
+/// Returns this chunk, and the size of each sample.
+#[cfg(feature = "zdict_builder")]
+fn concat_samples<S: AsRef<[u8]>>(samples: &[S]) -> (Vec<u8>, Vec<usize>) {
+    // Pre-allocate the entire required size.
+    let total_length: usize =
+        samples.iter().map(|sample| sample.as_ref().len()).sum();
+
+    let mut data = Vec::with_capacity(total_length);
+    data.extend(samples.iter().flat_map(|s| s.as_ref()).cloned());
+
+    let sizes: Vec<_> = samples.iter().map(|s| s.as_ref().len()).collect();
+
+    (data, sizes)
 }
 
 /// Train a dictionary from multiple samples.
@@ -147,16 +408,7 @@ pub fn from_samples<S: AsRef<[u8]>>(
     samples: &[S],
     max_size: usize,
 ) -> io::Result<Vec<u8>> {
-    // Pre-allocate the entire required size.
-    let total_length: usize =
-        samples.iter().map(|sample| sample.as_ref().len()).sum();
-
-    let mut data = Vec::with_capacity(total_length);
-
-    // Copy every sample to a big chunk of memory
-    data.extend(samples.iter().flat_map(|s| s.as_ref()).cloned());
-
-    let sizes: Vec<_> = samples.iter().map(|s| s.as_ref().len()).collect();
+    let (data, sizes) = concat_samples(samples);
 
     from_continuous(&data, &sizes, max_size)
 }
@@ -222,7 +474,7 @@ where
 /// Train a dict from a list of files.
 ///
 /// * `filenames` is an iterator of files to load. Each file will be treated as an individual
-///     sample.
+///   sample.
 /// * `max_size` is the maximum size of the dictionary to generate.
 ///
 /// The result is the dictionary data. You can, for example, feed it to [`CDict::create`].
@@ -241,6 +493,303 @@ where
     )
 }
 
+/// Algorithm used by a [`Trainer`] to build a dictionary.
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "experimental", feature = "zdict_builder")))
+)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    /// The COVER algorithm. Slow, but usually gives the best dictionaries.
+    Cover,
+
+    /// A faster variant of COVER. This is what [`from_continuous`] uses.
+    FastCover,
+
+    /// The algorithm used before COVER was introduced.
+    Legacy,
+}
+
+/// Trains a dictionary with a specific algorithm and parameters.
+///
+/// Parameters left unset are picked by zstd. For COVER and FastCover, if
+/// either `k` or `d` is unset, several values are tried on a part of the
+/// samples and the ones giving the best compression are kept.
+///
+/// Training returns the parameters that were picked, as a new `Trainer`
+/// that can train again on similar samples without the search step.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # fn main() -> std::io::Result<()> {
+/// # let samples: Vec<Vec<u8>> = Vec::new();
+/// use zstd::dict::{Algorithm, Trainer};
+///
+/// let (dictionary, picked) = Trainer::new(Algorithm::FastCover)
+///     .d(8)
+///     .steps(40)
+///     .threads(4)
+///     .compression_level(19)
+///     .train_from_samples(&samples, 16 * 1024)?;
+/// println!("k={:?} d={:?}", picked.get_k(), picked.get_d());
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "experimental", feature = "zdict_builder")))
+)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Trainer {
+    algorithm: Algorithm,
+    k: Option<u32>,
+    d: Option<u32>,
+    steps: Option<u32>,
+    split_point: Option<f64>,
+    accel: Option<u32>,
+    threads: u32,
+    compression_level: i32,
+}
+
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+impl Default for Trainer {
+    fn default() -> Self {
+        Trainer::new(Algorithm::FastCover)
+    }
+}
+
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+impl Trainer {
+    /// Creates a new trainer using the given algorithm.
+    pub fn new(algorithm: Algorithm) -> Self {
+        Trainer {
+            algorithm,
+            k: None,
+            d: None,
+            steps: None,
+            split_point: None,
+            accel: None,
+            threads: 1,
+            compression_level: 0,
+        }
+    }
+
+    /// Sets the segment size.
+    ///
+    /// Only used by COVER and FastCover.
+    pub fn k(mut self, k: u32) -> Self {
+        self.k = Some(k);
+        self
+    }
+
+    /// Sets the dmer size, usually between 6 and 16.
+    ///
+    /// Only used by COVER and FastCover.
+    pub fn d(mut self, d: u32) -> Self {
+        self.d = Some(d);
+        self
+    }
+
+    /// Sets the number of values of `k` tried when searching for it.
+    pub fn steps(mut self, steps: u32) -> Self {
+        self.steps = Some(steps);
+        self
+    }
+
+    /// Sets the fraction of samples used for training when searching for
+    /// parameters, the rest being used to evaluate the result.
+    ///
+    /// Must be in `(0.0, 1.0]`.
+    pub fn split_point(mut self, split_point: f64) -> Self {
+        self.split_point = Some(split_point);
+        self
+    }
+
+    /// Sets the acceleration level of FastCover, from 1 to 10.
+    ///
+    /// Higher is faster, but gives worse dictionaries.
+    pub fn accel(mut self, accel: u32) -> Self {
+        self.accel = Some(accel);
+        self
+    }
+
+    /// Sets the number of threads used when searching for parameters.
+    pub fn threads(mut self, threads: u32) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Sets the compression level the dictionary will be used with.
+    ///
+    /// A level of `0` uses zstd's default (currently `3`).
+    pub fn compression_level(mut self, level: i32) -> Self {
+        self.compression_level = level;
+        self
+    }
+
+    /// Returns the algorithm used by this trainer.
+    pub fn get_algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Returns the segment size, if set.
+    pub fn get_k(&self) -> Option<u32> {
+        self.k
+    }
+
+    /// Returns the dmer size, if set.
+    pub fn get_d(&self) -> Option<u32> {
+        self.d
+    }
+
+    /// Returns the number of steps, if set.
+    pub fn get_steps(&self) -> Option<u32> {
+        self.steps
+    }
+
+    /// Returns the split point, if set.
+    pub fn get_split_point(&self) -> Option<f64> {
+        self.split_point
+    }
+
+    /// Returns the FastCover acceleration level, if set.
+    pub fn get_accel(&self) -> Option<u32> {
+        self.accel
+    }
+
+    /// Trains a dictionary from a big continuous chunk of data.
+    ///
+    /// See [`from_continuous`] for the meaning of the arguments.
+    ///
+    /// Returns the dictionary, and the parameters that were used.
+    pub fn train_from_continuous(
+        &self,
+        sample_data: &[u8],
+        sample_sizes: &[usize],
+        max_size: usize,
+    ) -> io::Result<(Vec<u8>, Trainer)> {
+        use crate::map_error_code;
+
+        check_sample_sizes(sample_data, sample_sizes)?;
+
+        let dict = zstd_safe::DictParameters {
+            compression_level: self.compression_level,
+            ..Default::default()
+        };
+        let search = self.k.is_none() || self.d.is_none();
+
+        let mut result = Vec::with_capacity(max_size);
+        let picked = match self.algorithm {
+            Algorithm::Cover => {
+                let mut parameters = zstd_safe::CoverParameters {
+                    k: self.k.unwrap_or(0),
+                    d: self.d.unwrap_or(0),
+                    steps: self.steps.unwrap_or(0),
+                    nb_threads: self.threads,
+                    split_point: self.split_point.unwrap_or(0.0),
+                    dict,
+                    ..Default::default()
+                };
+                if search {
+                    zstd_safe::optimize_train_from_buffer_cover(
+                        &mut result,
+                        sample_data,
+                        sample_sizes,
+                        &mut parameters,
+                    )
+                } else {
+                    zstd_safe::train_from_buffer_cover(
+                        &mut result,
+                        sample_data,
+                        sample_sizes,
+                        &parameters,
+                    )
+                }
+                .map_err(map_error_code)?;
+                Trainer {
+                    k: Some(parameters.k),
+                    d: Some(parameters.d),
+                    steps: non_zero(parameters.steps).or(self.steps),
+                    ..*self
+                }
+            }
+            Algorithm::FastCover => {
+                let mut parameters = zstd_safe::FastCoverParameters {
+                    k: self.k.unwrap_or(0),
+                    d: self.d.unwrap_or(0),
+                    steps: self.steps.unwrap_or(0),
+                    nb_threads: self.threads,
+                    split_point: self.split_point.unwrap_or(0.0),
+                    accel: self.accel.unwrap_or(0),
+                    dict,
+                    ..Default::default()
+                };
+                if search {
+                    zstd_safe::optimize_train_from_buffer_fast_cover(
+                        &mut result,
+                        sample_data,
+                        sample_sizes,
+                        &mut parameters,
+                    )
+                } else {
+                    zstd_safe::train_from_buffer_fast_cover(
+                        &mut result,
+                        sample_data,
+                        sample_sizes,
+                        &parameters,
+                    )
+                }
+                .map_err(map_error_code)?;
+                Trainer {
+                    k: Some(parameters.k),
+                    d: Some(parameters.d),
+                    steps: non_zero(parameters.steps).or(self.steps),
+                    accel: non_zero(parameters.accel).or(self.accel),
+                    ..*self
+                }
+            }
+            Algorithm::Legacy => {
+                let parameters = zstd_safe::LegacyParameters {
+                    dict,
+                    ..Default::default()
+                };
+                zstd_safe::train_from_buffer_legacy(
+                    &mut result,
+                    sample_data,
+                    sample_sizes,
+                    &parameters,
+                )
+                .map_err(map_error_code)?;
+                *self
+            }
+        };
+
+        Ok((result, picked))
+    }
+
+    /// Trains a dictionary from multiple samples.
+    ///
+    /// The samples will internally be copied to a single continuous buffer.
+    ///
+    /// Returns the dictionary, and the parameters that were used.
+    pub fn train_from_samples<S: AsRef<[u8]>>(
+        &self,
+        samples: &[S],
+        max_size: usize,
+    ) -> io::Result<(Vec<u8>, Trainer)> {
+        let (data, sizes) = concat_samples(samples);
+        self.train_from_continuous(&data, &sizes, max_size)
+    }
+}
+
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+fn non_zero(value: u32) -> Option<u32> {
+    Some(value).filter(|&value| value != 0)
+}
+
 #[cfg(test)]
 #[cfg(feature = "zdict_builder")]
 mod tests {
@@ -293,4 +842,144 @@ mod tests {
             assert_eq!(&content, &result);
         }
     }
+
+    #[cfg(feature = "experimental")]
+    #[test]
+    fn test_trainer() {
+        use super::{Algorithm, Trainer};
+
+        let samples: Vec<_> = (0..2000)
+            .map(|i| {
+                format!(
+                    r#"{{"id":{},"name":"user-{}","active":{},"tags":["a","b"]}}"#,
+                    i,
+                    i * 7 % 100,
+                    i % 3 == 0
+                )
+            })
+            .collect();
+
+        let (dict, picked) = Trainer::new(Algorithm::FastCover)
+            .d(8)
+            .steps(4)
+            .compression_level(3)
+            .train_from_samples(&samples, 4096)
+            .unwrap();
+        assert!(!dict.is_empty() && dict.len() <= 4096);
+        assert_eq!(picked.get_d(), Some(8));
+        assert!(picked.get_k().is_some());
+
+        // Training again with the picked parameters skips the search.
+        let (again, _) = picked.train_from_samples(&samples, 4096).unwrap();
+        assert!(!again.is_empty());
+
+        let (cover, picked) = Trainer::new(Algorithm::Cover)
+            .k(64)
+            .d(8)
+            .train_from_samples(&samples, 4096)
+            .unwrap();
+        assert_eq!(picked.get_k(), Some(64));
+
+        let sample = samples[42].as_bytes();
+        let compressed = crate::bulk::Compressor::with_dictionary(3, &cover)
+            .unwrap()
+            .compress(sample)
+            .unwrap();
+        let decompressed = crate::bulk::Decompressor::with_dictionary(&cover)
+            .unwrap()
+            .decompress(&compressed, sample.len())
+            .unwrap();
+        assert_eq!(decompressed, sample);
+
+        Trainer::new(Algorithm::Legacy)
+            .train_from_samples(&samples, 4096)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_finalize() {
+        let samples: Vec<_> = (0..1000)
+            .map(|i| format!(r#"{{"schema":3,"id":{},"ok":true}}"#, i))
+            .collect();
+        let content = br#"{"schema":3,"id":"ok":true}"#.repeat(16);
+
+        let dict = super::finalize(
+            &content,
+            &samples,
+            super::DictParameters {
+                dict_id: 0x1234,
+                compression_level: 3,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(dict.ends_with(&content));
+        assert_eq!(zstd_safe::get_dict_id(&dict).unwrap().get(), 0x1234);
+
+        let encoder_dict = super::EncoderDictionary::copy(&dict, 3);
+        let decoder_dict = super::DecoderDictionary::copy(&dict);
+        let sample = samples[7].as_bytes();
+        let compressed =
+            crate::bulk::Compressor::with_prepared_dictionary(&encoder_dict)
+                .unwrap()
+                .compress(sample)
+                .unwrap();
+        assert_eq!(
+            zstd_safe::get_dict_id_from_frame(&compressed)
+                .unwrap()
+                .get(),
+            0x1234
+        );
+        let decompressed =
+            crate::bulk::Decompressor::with_prepared_dictionary(&decoder_dict)
+                .unwrap()
+                .decompress(&compressed, sample.len())
+                .unwrap();
+        assert_eq!(decompressed, sample);
+    }
+
+    #[test]
+    fn test_dictionary_info() {
+        use super::DictionaryInfo;
+
+        let samples: Vec<_> = (0..1000)
+            .map(|i| format!(r#"{{"schema":4,"id":{}}}"#, i))
+            .collect();
+        let content = br#"{"schema":4,"id":"#.repeat(16);
+        let dict = super::finalize(
+            &content,
+            &samples,
+            super::DictParameters {
+                dict_id: 77,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let info = DictionaryInfo::parse(&dict).unwrap();
+        assert_eq!(info.magic(), Some(zstd_safe::MAGIC_DICTIONARY));
+        assert!(!info.is_raw_content());
+        assert_eq!(info.dict_id().unwrap().get(), 77);
+        assert_eq!(info.content_size(), content.len());
+        assert_eq!(info.header_size() + info.content_size(), dict.len());
+
+        let info = DictionaryInfo::parse(&content).unwrap();
+        assert!(info.is_raw_content());
+        assert_eq!(info.dict_id(), None);
+        assert_eq!(info.header_size(), 0);
+        assert_eq!(info.content_size(), content.len());
+
+        // A full dictionary magic with a broken header.
+        let mut broken = dict[..12].to_vec();
+        broken.extend_from_slice(&content);
+        assert!(DictionaryInfo::parse(&broken).is_err());
+
+        let encoder_dict = super::EncoderDictionary::copy(&dict, 5);
+        assert_eq!(encoder_dict.get_dict_id().unwrap().get(), 77);
+        assert_eq!(encoder_dict.get_compression_level(), 5);
+        assert!(encoder_dict.sizeof() > dict.len());
+        let decoder_dict = super::DecoderDictionary::copy(&dict);
+        assert_eq!(decoder_dict.get_dict_id().unwrap().get(), 77);
+        assert!(decoder_dict.sizeof() > dict.len());
+    }
 }
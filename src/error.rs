@@ -0,0 +1,184 @@
+//! Error type for zstd operations.
+use std::fmt;
+use std::io;
+
+pub use zstd_safe::ErrorKind;
+
+/// An error returned by the zstd library.
+///
+/// Functions of this crate return `io::Error`. When the failure comes from
+/// zstd itself, the `io::Error` wraps one of these, which can be recovered
+/// with `io::Error::get_ref` and `downcast_ref`, or with [`Error::from_io`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    code: zstd_safe::ErrorCode,
+    position: Option<Position>,
+}
+
+/// Location in the streams where a decompression error was detected.
+///
+/// zstd reports errors for whole blocks, so the damaged region starts at or
+/// shortly after these offsets.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Position {
+    /// Number of compressed bytes successfully consumed before the error.
+    pub input_offset: u64,
+    /// Number of decompressed bytes produced before the error.
+    pub output_offset: u64,
+    /// Index of the frame being decoded, starting at 0.
+    ///
+    /// Skippable frames are counted.
+    pub frame_index: u64,
+}
+
+impl Position {
+    /// Moves the position forward by the given amounts of input and output.
+    pub(crate) fn advance(&mut self, consumed: usize, produced: usize) {
+        self.input_offset += consumed as u64;
+        self.output_offset += produced as u64;
+    }
+}
+
+impl Error {
+    pub(crate) fn new(code: zstd_safe::ErrorCode) -> Self {
+        Error {
+            code,
+            position: None,
+        }
+    }
+
+    /// Returns the same error, located at the given position.
+    pub(crate) fn at(self, position: Position) -> Self {
+        Error {
+            position: Some(position),
+            ..self
+        }
+    }
+
+    /// Returns the zstd error wrapped in `error`, if any.
+    pub fn from_io(error: &io::Error) -> Option<&Self> {
+        error.get_ref()?.downcast_ref()
+    }
+
+    /// Returns the kind of error.
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::from_code(self.code).unwrap_or(ErrorKind::Unknown)
+    }
+
+    /// Returns the raw error code returned by zstd.
+    pub fn code(&self) -> zstd_safe::ErrorCode {
+        self.code
+    }
+
+    /// Returns where in the input and output this error was detected.
+    ///
+    /// This is only available for errors returned while decompressing.
+    pub fn position(&self) -> Option<Position> {
+        self.position
+    }
+
+    /// Returns the `io::ErrorKind` best matching this error.
+    fn io_kind(&self) -> io::ErrorKind {
+        match self.kind() {
+            ErrorKind::PrefixUnknown
+            | ErrorKind::FrameParameterUnsupported
+            | ErrorKind::CorruptionDetected
+            | ErrorKind::ChecksumWrong
+            | ErrorKind::LiteralsHeaderWrong
+            | ErrorKind::DictionaryCorrupted
+            | ErrorKind::DictionaryWrong => io::ErrorKind::InvalidData,
+            ErrorKind::VersionUnsupported
+            | ErrorKind::FrameParameterWindowTooLarge
+            | ErrorKind::ParameterUnsupported
+            | ErrorKind::ParameterCombinationUnsupported => {
+                io::ErrorKind::Unsupported
+            }
+            ErrorKind::ParameterOutOfBound
+            | ErrorKind::WorkSpaceTooSmall
+            | ErrorKind::DstSizeTooSmall
+            | ErrorKind::SrcSizeWrong => io::ErrorKind::InvalidInput,
+            ErrorKind::MemoryAllocation => io::ErrorKind::OutOfMemory,
+            _ => io::ErrorKind::Other,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(zstd_safe::get_error_name(self.code))?;
+        if let Some(position) = self.position {
+            write!(
+                f,
+                " (input offset {}, output offset {}, frame {})",
+                position.input_offset,
+                position.output_offset,
+                position.frame_index
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for io::Error {
+    fn from(error: Error) -> Self {
+        io::Error::new(error.io_kind(), error)
+    }
+}
+
+/// Attaches `position` to `error` if it comes from zstd and isn't located yet.
+pub(crate) fn locate(error: io::Error, position: Position) -> io::Error {
+    match Error::from_io(&error) {
+        Some(zstd_error) if zstd_error.position.is_none() => {
+            zstd_error.at(position).into()
+        }
+        _ => error,
+    }
+}
+
+/// Shifts the position of `error`, relative to a frame, by where that frame
+/// starts in the whole stream.
+pub(crate) fn relocate(error: io::Error, start: Position) -> io::Error {
+    match Error::from_io(&error) {
+        Some(zstd_error) => {
+            let relative = zstd_error.position.unwrap_or_default();
+            zstd_error
+                .at(Position {
+                    input_offset: start.input_offset + relative.input_offset,
+                    output_offset: start.output_offset
+                        + relative.output_offset,
+                    frame_index: start.frame_index + relative.frame_index,
+                })
+                .into()
+        }
+        None => error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, ErrorKind};
+    use std::io;
+
+    #[test]
+    fn test_error_kind() {
+        let error =
+            crate::decode_all(&b"\x28\xb5\x2f\xfd garbage"[..]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        let zstd_error = Error::from_io(&error).unwrap();
+        assert!(matches!(
+            zstd_error.kind(),
+            ErrorKind::CorruptionDetected
+                | ErrorKind::FrameParameterUnsupported
+        ));
+
+        let compressed = crate::bulk::compress(&[0u8; 1000], 1).unwrap();
+        let error = crate::bulk::decompress(&compressed, 10).unwrap_err();
+        let zstd_error =
+            error.get_ref().unwrap().downcast_ref::<Error>().unwrap();
+        assert_eq!(zstd_error.kind(), ErrorKind::DstSizeTooSmall);
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(error.to_string(), "Destination buffer is too small");
+    }
+}
@@ -0,0 +1,636 @@
+//! Inspect zstd frames without decompressing them.
+//!
+//! Only available with the `experimental` feature.
+use std::io::{self, Read};
+use std::num::NonZeroU32;
+
+use crate::error::{locate, Position};
+use crate::map_error_code;
+
+pub use zstd_safe::{FrameFormat, FrameHeader, FrameType};
+
+/// Outcome of [`read_header`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HeaderStatus {
+    /// The header was entirely parsed.
+    Complete(FrameHeader),
+
+    /// The given data is a valid prefix, but too short to hold the header.
+    Incomplete {
+        /// Number of bytes still missing to parse the header.
+        needed: usize,
+    },
+}
+
+/// Parses the header of the frame at the beginning of `src`.
+///
+/// `src` does not need to contain the entire frame: if it is too short to
+/// hold the header, `HeaderStatus::Incomplete` is returned with the number
+/// of additional bytes needed.
+///
+/// Returns an error if `src` does not start with a zstd frame.
+pub fn read_header(src: &[u8]) -> io::Result<HeaderStatus> {
+    read_header_with_format(src, FrameFormat::One)
+}
+
+/// Parses the header of the frame at the beginning of `src`.
+///
+/// Same as [`read_header`], but can parse frames written without the magic
+/// number, when given `FrameFormat::Magicless`.
+pub fn read_header_with_format(
+    src: &[u8],
+    format: FrameFormat,
+) -> io::Result<HeaderStatus> {
+    match zstd_safe::get_frame_header_advanced(src, format) {
+        Ok(header) => Ok(HeaderStatus::Complete(header)),
+        Err(zstd_safe::FrameHeaderError::Incomplete(size)) => {
+            Ok(HeaderStatus::Incomplete {
+                needed: size - src.len(),
+            })
+        }
+        Err(zstd_safe::FrameHeaderError::Invalid(code)) => {
+            Err(map_error_code(code))
+        }
+    }
+}
+
+/// Kind of a frame listed by [`Frames`] or [`ReadFrames`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrameKind {
+    /// A regular zstd frame.
+    Zstd,
+
+    /// A skippable frame, holding user data.
+    Skippable {
+        /// Magic variant of the frame, between 0 and 15.
+        variant: u8,
+    },
+
+    /// A frame written by zstd versions before 0.8.
+    Legacy {
+        /// The `0.x` version of the format, between 1 and 7.
+        version: u8,
+    },
+}
+
+/// Metadata about a frame, as listed by [`Frames`] or [`ReadFrames`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FrameInfo {
+    /// Kind of frame.
+    pub kind: FrameKind,
+
+    /// Position of the frame in the input, in bytes.
+    pub offset: u64,
+
+    /// Size of the entire frame, in bytes.
+    pub compressed_size: u64,
+
+    /// Decompressed size of the frame, if it is known.
+    ///
+    /// For skippable frames, this is the size of the user data.
+    pub content_size: Option<u64>,
+
+    /// Size of the window needed to decompress this frame.
+    ///
+    /// `None` for skippable and legacy frames.
+    pub window_size: Option<u64>,
+
+    /// Dictionary ID required to decompress this frame, if any.
+    pub dict_id: Option<NonZeroU32>,
+
+    /// Whether a checksum follows the last block of this frame.
+    pub checksum: bool,
+
+    /// Number of blocks in this frame.
+    ///
+    /// `None` for legacy frames.
+    pub blocks: Option<u64>,
+}
+
+/// Magic number of the 0.1 format; later legacy versions use
+/// `LEGACY_MAGIC + version`.
+const LEGACY_V01_MAGIC: u32 = 0x1EB5_2FFD;
+const LEGACY_MAGIC: u32 = 0xFD2F_B520;
+
+/// Size of the header of each block.
+const BLOCK_HEADER_SIZE: usize = 3;
+
+/// Identifies the kind of frame starting with `magic`.
+fn frame_kind(magic: u32) -> Option<FrameKind> {
+    match magic {
+        zstd_safe::MAGICNUMBER => Some(FrameKind::Zstd),
+        _ if magic & zstd_safe::MAGIC_SKIPPABLE_MASK
+            == zstd_safe::MAGIC_SKIPPABLE_START =>
+        {
+            Some(FrameKind::Skippable {
+                variant: (magic & !zstd_safe::MAGIC_SKIPPABLE_MASK) as u8,
+            })
+        }
+        LEGACY_V01_MAGIC => Some(FrameKind::Legacy { version: 1 }),
+        _ if (LEGACY_MAGIC + 2..=LEGACY_MAGIC + 7).contains(&magic) => {
+            Some(FrameKind::Legacy {
+                version: (magic - LEGACY_MAGIC) as u8,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Parses a block header.
+///
+/// Returns whether this is the last block, and the size of its content.
+fn parse_block_header(
+    header: [u8; BLOCK_HEADER_SIZE],
+) -> io::Result<(bool, u64)> {
+    let header = u32::from_le_bytes([header[0], header[1], header[2], 0]);
+    let last = header & 1 == 1;
+    let size = u64::from(header >> 3);
+    match (header >> 1) & 3 {
+        // Raw and compressed blocks.
+        0 | 2 => Ok((last, size)),
+        // RLE blocks only store the repeated byte.
+        1 => Ok((last, 1)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "reserved block type",
+        )),
+    }
+}
+
+/// Builds a `FrameInfo` from the header of a zstd or skippable frame.
+fn header_info(
+    header: &FrameHeader,
+    kind: FrameKind,
+    offset: u64,
+    compressed_size: u64,
+    blocks: u64,
+) -> FrameInfo {
+    let skippable = header.frame_type == FrameType::Skippable;
+    FrameInfo {
+        kind,
+        offset,
+        compressed_size,
+        content_size: header.content_size,
+        window_size: if skippable {
+            None
+        } else {
+            Some(header.window_size)
+        },
+        dict_id: if skippable { None } else { header.dict_id },
+        checksum: header.checksum_flag,
+        blocks: Some(blocks),
+    }
+}
+
+/// Describes the frame at the beginning of `src`.
+fn slice_frame_info(src: &[u8], offset: u64) -> io::Result<FrameInfo> {
+    let compressed_size =
+        zstd_safe::find_frame_compressed_size(src).map_err(map_error_code)?;
+    let frame = &src[..compressed_size];
+    let magic = u32::from_le_bytes([frame[0], frame[1], frame[2], frame[3]]);
+
+    let kind = match frame_kind(magic) {
+        Some(FrameKind::Legacy { version }) => {
+            return Ok(FrameInfo {
+                kind: FrameKind::Legacy { version },
+                offset,
+                compressed_size: compressed_size as u64,
+                content_size: zstd_safe::get_frame_content_size(frame)
+                    .ok()
+                    .flatten(),
+                window_size: None,
+                dict_id: None,
+                checksum: false,
+                blocks: None,
+            });
+        }
+        Some(kind) => kind,
+        None => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unknown frame magic number",
+            ))
+        }
+    };
+
+    let mut header = match read_header(frame)? {
+        HeaderStatus::Complete(header) => header,
+        HeaderStatus::Incomplete { .. } => {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "incomplete frame header",
+            ))
+        }
+    };
+
+    let mut blocks = 0;
+    if kind == FrameKind::Zstd {
+        header.content_size = zstd_safe::get_frame_content_size(frame)
+            .map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidData, "invalid frame")
+            })?;
+
+        // `find_frame_compressed_size` already checked the block headers.
+        let mut pos = header.header_size as usize;
+        while let Some(block) = frame.get(pos..pos + BLOCK_HEADER_SIZE) {
+            let (last, size) =
+                parse_block_header([block[0], block[1], block[2]])?;
+            blocks += 1;
+            pos += BLOCK_HEADER_SIZE + size as usize;
+            if last {
+                break;
+            }
+        }
+    }
+
+    Ok(header_info(
+        &header,
+        kind,
+        offset,
+        compressed_size as u64,
+        blocks,
+    ))
+}
+
+/// Iterator over the frames of a buffer.
+///
+/// Yields a [`FrameInfo`] for each frame, similar to what `zstd -l` reports,
+/// without decompressing anything.
+///
+/// If the buffer does not end with a complete frame, the iterator yields an
+/// error and stops. [`Frames::offset`] then gives the exact position of the
+/// trailing bytes; zstd errors also carry it in their
+/// [`position`](crate::Error::position).
+///
+/// Legacy frames are only recognized with the `legacy` feature.
+#[derive(Clone, Debug)]
+pub struct Frames<'a> {
+    src: &'a [u8],
+    offset: usize,
+    index: u64,
+    failed: bool,
+}
+
+impl<'a> Frames<'a> {
+    /// Lists the frames in `src`.
+    pub fn new(src: &'a [u8]) -> Self {
+        Frames {
+            src,
+            offset: 0,
+            index: 0,
+            failed: false,
+        }
+    }
+
+    /// Returns the offset of the next frame to be listed.
+    ///
+    /// After an error, this is where the invalid data starts.
+    pub fn offset(&self) -> u64 {
+        self.offset as u64
+    }
+}
+
+impl Iterator for Frames<'_> {
+    type Item = io::Result<FrameInfo>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.offset == self.src.len() {
+            return None;
+        }
+
+        let offset = self.offset as u64;
+        match slice_frame_info(&self.src[self.offset..], offset) {
+            Ok(info) => {
+                self.offset += info.compressed_size as usize;
+                self.index += 1;
+                Some(Ok(info))
+            }
+            Err(e) => {
+                self.failed = true;
+                Some(Err(locate_frame(e, offset, self.index)))
+            }
+        }
+    }
+}
+
+/// Iterator over the frames read from a stream.
+///
+/// Same as [`Frames`], but reads the frames from a `Read`. The content of
+/// blocks and skippable frames is read and discarded, so memory usage does
+/// not depend on the size of the frames.
+///
+/// Legacy frames cannot be listed this way: they are reported as an
+/// `Unsupported` error.
+#[derive(Debug)]
+pub struct ReadFrames<R> {
+    reader: R,
+    offset: u64,
+    index: u64,
+    failed: bool,
+}
+
+impl<R: Read> ReadFrames<R> {
+    /// Lists the frames read from `reader`.
+    pub fn new(reader: R) -> Self {
+        ReadFrames {
+            reader,
+            offset: 0,
+            index: 0,
+            failed: false,
+        }
+    }
+
+    /// Returns the offset of the next frame to be listed.
+    ///
+    /// After an error, this is where the invalid data starts.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads and discards `n` bytes.
+    fn skip(&mut self, n: u64) -> io::Result<()> {
+        let skipped =
+            io::copy(&mut (&mut self.reader).take(n), &mut io::sink())?;
+        if skipped < n {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "incomplete frame",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Reads the frame starting with `magic`.
+    fn read_frame(&mut self, magic: [u8; 4]) -> io::Result<FrameInfo> {
+        let kind = match frame_kind(u32::from_le_bytes(magic)) {
+            Some(FrameKind::Legacy { .. }) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "cannot list legacy frames from a reader",
+                ))
+            }
+            Some(kind) => kind,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "unknown frame magic number",
+                ))
+            }
+        };
+
+        let mut buffer = magic.to_vec();
+        let header = loop {
+            match read_header(&buffer)? {
+                HeaderStatus::Complete(header) => break header,
+                HeaderStatus::Incomplete { needed } => {
+                    let len = buffer.len();
+                    buffer.resize(len + needed, 0);
+                    self.reader.read_exact(&mut buffer[len..])?;
+                }
+            }
+        };
+        let mut compressed_size = buffer.len() as u64;
+        let mut blocks = 0;
+
+        if kind == FrameKind::Zstd {
+            loop {
+                let mut block = [0u8; BLOCK_HEADER_SIZE];
+                self.reader.read_exact(&mut block)?;
+                let (last, size) = parse_block_header(block)?;
+                self.skip(size)?;
+                blocks += 1;
+                compressed_size += (BLOCK_HEADER_SIZE as u64) + size;
+                if last {
+                    break;
+                }
+            }
+            if header.checksum_flag {
+                self.skip(4)?;
+                compressed_size += 4;
+            }
+        } else {
+            let size = header.content_size.unwrap_or(0);
+            self.skip(size)?;
+            compressed_size += size;
+        }
+
+        Ok(header_info(
+            &header,
+            kind,
+            self.offset,
+            compressed_size,
+            blocks,
+        ))
+    }
+}
+
+impl<R: Read> Iterator for ReadFrames<R> {
+    type Item = io::Result<FrameInfo>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let mut magic = [0u8; 4];
+        let mut read = 0;
+        while read < magic.len() {
+            match self.reader.read(&mut magic[read..]) {
+                Ok(0) => break,
+                Ok(n) => read += n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.failed = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+
+        let result = match read {
+            // A clean end of input, between two frames.
+            0 => return None,
+            4 => self.read_frame(magic),
+            _ => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "incomplete frame",
+            )),
+        };
+
+        match result {
+            Ok(info) => {
+                self.offset += info.compressed_size;
+                self.index += 1;
+                Some(Ok(info))
+            }
+            Err(e) => {
+                self.failed = true;
+                Some(Err(locate_frame(e, self.offset, self.index)))
+            }
+        }
+    }
+}
+
+/// Locates an error at the start of the `index`-th frame.
+fn locate_frame(error: io::Error, offset: u64, index: u64) -> io::Error {
+    locate(
+        error,
+        Position {
+            input_offset: offset,
+            output_offset: 0,
+            frame_index: index,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        read_header, FrameInfo, FrameKind, FrameType, Frames, HeaderStatus,
+        ReadFrames,
+    };
+    use std::io;
+
+    #[test]
+    fn test_read_header() {
+        let compressed = crate::bulk::compress(b"foobar", 1).unwrap();
+
+        let header = match read_header(&compressed).unwrap() {
+            HeaderStatus::Complete(header) => header,
+            other => panic!("unexpected header status: {:?}", other),
+        };
+        assert_eq!(header.frame_type, FrameType::Frame);
+        assert_eq!(header.content_size, Some(6));
+
+        match read_header(&compressed[..3]).unwrap() {
+            HeaderStatus::Incomplete { needed } => assert!(needed > 0),
+            other => panic!("unexpected header status: {:?}", other),
+        }
+
+        assert!(read_header(b"not a zstd frame").is_err());
+    }
+
+    const TEXT: &[u8] = include_bytes!("../assets/example.txt");
+
+    /// Returns a skippable frame, a frame with a checksum and several blocks,
+    /// a frame with a known content size, then some trailing garbage.
+    fn sample() -> (Vec<u8>, usize) {
+        let mut data = Vec::new();
+        data.extend_from_slice(
+            &(zstd_safe::MAGIC_SKIPPABLE_START + 3).to_le_bytes(),
+        );
+        data.extend_from_slice(&5u32.to_le_bytes());
+        data.extend_from_slice(b"hello");
+
+        let big = TEXT.repeat(300 * 1024 / TEXT.len() + 1);
+        let mut encoder = crate::stream::Encoder::new(Vec::new(), 1).unwrap();
+        encoder.include_checksum(true).unwrap();
+        io::Write::write_all(&mut encoder, &big).unwrap();
+        data.extend_from_slice(&encoder.finish().unwrap());
+
+        data.extend_from_slice(&crate::bulk::compress(TEXT, 1).unwrap());
+
+        let garbage = data.len();
+        data.extend_from_slice(b"garbage");
+        (data, garbage)
+    }
+
+    fn check_frames(frames: &[FrameInfo], garbage: usize) {
+        assert_eq!(frames.len(), 3);
+
+        assert_eq!(frames[0].kind, FrameKind::Skippable { variant: 3 });
+        assert_eq!(frames[0].offset, 0);
+        assert_eq!(frames[0].compressed_size, 13);
+        assert_eq!(frames[0].content_size, Some(5));
+        assert_eq!(frames[0].blocks, Some(0));
+
+        assert_eq!(frames[1].kind, FrameKind::Zstd);
+        assert_eq!(frames[1].offset, 13);
+        assert_eq!(frames[1].content_size, None);
+        assert!(frames[1].checksum);
+        assert!(frames[1].blocks.unwrap() >= 3);
+
+        assert_eq!(frames[2].kind, FrameKind::Zstd);
+        assert_eq!(
+            frames[2].offset,
+            frames[1].offset + frames[1].compressed_size
+        );
+        assert_eq!(frames[2].content_size, Some(TEXT.len() as u64));
+        assert!(!frames[2].checksum);
+        assert_eq!(frames[2].dict_id, None);
+        assert!(frames[2].window_size.is_some());
+        assert_eq!(
+            frames[2].offset + frames[2].compressed_size,
+            garbage as u64
+        );
+    }
+
+    #[test]
+    fn test_frames() {
+        let (data, garbage) = sample();
+
+        let mut frames = Frames::new(&data);
+        let infos: Vec<FrameInfo> =
+            frames.by_ref().take(3).map(Result::unwrap).collect();
+        check_frames(&infos, garbage);
+
+        let err = frames.next().unwrap().unwrap_err();
+        let position = crate::Error::from_io(&err).unwrap().position();
+        assert_eq!(position.unwrap().input_offset, garbage as u64);
+        assert_eq!(position.unwrap().frame_index, 3);
+        assert_eq!(frames.offset(), garbage as u64);
+        assert!(frames.next().is_none());
+
+        // A truncated frame is reported at its start.
+        let mut frames = Frames::new(&data[..garbage - 1]);
+        assert!(frames.by_ref().take(2).all(|frame| frame.is_ok()));
+        assert!(frames.next().unwrap().is_err());
+        assert_eq!(frames.offset(), infos[2].offset);
+    }
+
+    #[test]
+    fn test_read_frames() {
+        let (data, garbage) = sample();
+
+        let mut frames = ReadFrames::new(&data[..]);
+        let infos: Vec<FrameInfo> =
+            frames.by_ref().take(3).map(Result::unwrap).collect();
+        check_frames(&infos, garbage);
+        assert_eq!(
+            infos,
+            Frames::new(&data[..garbage])
+                .map(Result::unwrap)
+                .collect::<Vec<_>>()
+        );
+
+        let err = frames.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(frames.offset(), garbage as u64);
+        assert!(frames.next().is_none());
+
+        let mut frames = ReadFrames::new(&data[..garbage - 1]);
+        assert!(frames.by_ref().take(2).all(|frame| frame.is_ok()));
+        let err = frames.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(frames.offset(), infos[2].offset);
+    }
+
+    #[cfg(feature = "legacy")]
+    #[test]
+    fn test_legacy_frames() {
+        for version in 5..=7 {
+            let data =
+                std::fs::read(format!("assets/example.txt.v{}.zst", version))
+                    .unwrap();
+            let frames: Vec<FrameInfo> =
+                Frames::new(&data).map(Result::unwrap).collect();
+            assert_eq!(frames.len(), 1);
+            assert_eq!(frames[0].kind, FrameKind::Legacy { version });
+            assert_eq!(frames[0].compressed_size, data.len() as u64);
+        }
+    }
+}
@@ -26,10 +26,18 @@ pub use zstd_safe;
 
 pub mod bulk;
 pub mod dict;
+mod error;
+
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub mod frame;
 
 #[macro_use]
 pub mod stream;
 
+#[cfg(all(feature = "experimental", feature = "zstdmt"))]
+mod thread_pool;
+
 use std::io;
 
 /// Default compression level.
@@ -41,13 +49,21 @@ pub fn compression_level_range(
     zstd_safe::min_c_level()..=zstd_safe::max_c_level()
 }
 
+pub use crate::error::{Error, ErrorKind, Position};
+
+#[cfg(all(feature = "experimental", feature = "zstdmt"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "experimental", feature = "zstdmt")))
+)]
+pub use crate::thread_pool::ThreadPool;
+
 #[doc(no_inline)]
 pub use crate::stream::{decode_all, encode_all, Decoder, Encoder};
 
-/// Returns the error message as io::Error based on error_code.
+/// Returns the error as io::Error based on error_code.
 fn map_error_code(code: usize) -> io::Error {
-    let msg = zstd_safe::get_error_name(code);
-    io::Error::new(io::ErrorKind::Other, msg.to_string())
+    Error::new(code).into()
 }
 
 // Some helper functions to write full-cycle tests.
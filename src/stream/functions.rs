@@ -1,4 +1,4 @@
-use std::io;
+use std::io::{self, Read};
 
 use super::{Decoder, Encoder};
 
@@ -24,6 +24,78 @@ where
     Ok(())
 }
 
+/// Decompress from the given source as if using a `Decoder`.
+///
+/// Same as [`copy_decode`], but calls `progress` after each chunk of
+/// decompressed data is written, and once more when the input is complete,
+/// with the total number of bytes consumed from `source` and written to
+/// `destination` so far.
+pub fn copy_decode_with_progress<R, W, F>(
+    source: R,
+    mut destination: W,
+    mut progress: F,
+) -> io::Result<()>
+where
+    R: io::Read,
+    W: io::Write,
+    F: FnMut(u64, u64),
+{
+    let mut decoder = Decoder::new(Counting::new(source))?;
+    let mut buffer = vec![0; Decoder::<&[u8]>::recommended_output_size()];
+    let mut written = 0;
+
+    loop {
+        let n = match decoder.read(&mut buffer) {
+            Ok(n) => n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        destination.write_all(&buffer[..n])?;
+        written += n as u64;
+
+        // Input still in the buffer was not given to the decoder yet.
+        let reader = decoder.get_ref();
+        let consumed = reader.get_ref().count - reader.buffer().len() as u64;
+        progress(consumed, written);
+
+        if n == 0 {
+            return Ok(());
+        }
+    }
+}
+
+/// A reader or writer keeping track of how many bytes went through it.
+struct Counting<T> {
+    inner: T,
+    count: u64,
+}
+
+impl<T> Counting<T> {
+    fn new(inner: T) -> Self {
+        Counting { inner, count: 0 }
+    }
+}
+
+impl<R: io::Read> io::Read for Counting<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+}
+
+impl<W: io::Write> io::Write for Counting<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 /// Compress all data from the given source as if using an `Encoder`.
 ///
 /// Result will be in the zstd frame format.
@@ -55,5 +127,94 @@ where
     Ok(())
 }
 
+/// Compress all data from the given source as if using an `Encoder`.
+///
+/// Same as [`copy_encode`], but calls `progress` after each chunk of input is
+/// given to the encoder, and once more when the frame is complete, with the
+/// total number of bytes consumed from `source` and written to `destination`
+/// so far.
+pub fn copy_encode_with_progress<R, W, F>(
+    mut source: R,
+    destination: W,
+    level: i32,
+    mut progress: F,
+) -> io::Result<()>
+where
+    R: io::Read,
+    W: io::Write,
+    F: FnMut(u64, u64),
+{
+    use std::io::Write;
+
+    let mut encoder = Encoder::new(Counting::new(destination), level)?;
+    let mut buffer = vec![0; Encoder::<Vec<u8>>::recommended_input_size()];
+    let mut read = 0;
+
+    loop {
+        let n = match source.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        encoder.write_all(&buffer[..n])?;
+        read += n as u64;
+        progress(read, encoder.get_ref().count);
+    }
+
+    let destination = encoder.finish()?;
+    progress(read, destination.count);
+    Ok(())
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::{copy_decode_with_progress, copy_encode_with_progress};
+    use super::{decode_all, encode_all};
+
+    #[test]
+    fn test_copy_decode_with_progress() {
+        let data: Vec<u8> = (0..1_000_000).map(|i| (i % 251) as u8).collect();
+        let compressed = encode_all(&data[..], 1).unwrap();
+
+        let mut calls = Vec::new();
+        let mut decompressed = Vec::new();
+        copy_decode_with_progress(
+            &compressed[..],
+            &mut decompressed,
+            |read, written| calls.push((read, written)),
+        )
+        .unwrap();
+
+        assert_eq!(decompressed, data);
+        assert!(calls.len() > 1);
+        // The whole input is buffered at once, but consumed block by block.
+        assert!(calls[0].0 < compressed.len() as u64);
+        assert_eq!(
+            calls.last(),
+            Some(&(compressed.len() as u64, data.len() as u64))
+        );
+    }
+
+    #[test]
+    fn test_copy_encode_with_progress() {
+        let data = vec![7u8; 1_000_000];
+        let mut calls = Vec::new();
+        let mut compressed = Vec::new();
+        copy_encode_with_progress(
+            &data[..],
+            &mut compressed,
+            1,
+            |read, written| calls.push((read, written)),
+        )
+        .unwrap();
+
+        assert_eq!(decode_all(&compressed[..]).unwrap(), data);
+        assert!(calls.len() > 1);
+        assert!(calls.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(
+            calls.last(),
+            Some(&(data.len() as u64, compressed.len() as u64))
+        );
+    }
+}
@@ -0,0 +1,992 @@
+//! Compress and decompress streams of chunks, for the `futures` traits.
+//!
+//! [`Encoder`] and [`Decoder`] wrap a [`TryStream`] of input chunks, and
+//! are themselves a [`Stream`] of output chunks. [`EncoderSink`] and
+//! [`DecoderSink`] go the other way: they are a [`Sink`] of input chunks,
+//! forwarding output chunks to another `Sink`.
+//!
+//! Output chunks are [`Bytes`] of at most [`chunk_size`] bytes. By default,
+//! a chunk is only emitted once full, or at the end of the stream. With
+//! [`flush_on_chunk`], everything that can be produced from an input chunk
+//! is emitted as soon as that chunk is consumed.
+//!
+//! The wrapped stream or sink must be `Unpin`. Others can be used through
+//! `Box::pin`.
+//!
+//! [`chunk_size`]: Encoder::chunk_size
+//! [`flush_on_chunk`]: Encoder::flush_on_chunk
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use bytes::Bytes;
+use futures_core::{Stream, TryStream};
+use futures_sink::Sink;
+
+use crate::dict::{DecoderDictionary, EncoderDictionary};
+use crate::error::{locate, Position};
+use crate::stream::raw::{self, InBuffer, Operation, OutBuffer};
+
+/// Runs an operation, collecting its output in chunks.
+struct Chunker<D> {
+    operation: D,
+
+    buffer: Vec<u8>,
+    chunk_size: usize,
+    flush_on_chunk: bool,
+
+    // When `true`, the end of the stream was written: nothing should be
+    // added to the buffer anymore.
+    finished: bool,
+
+    finished_frame: bool,
+
+    // Bytes consumed and produced so far, used to locate errors.
+    position: Position,
+}
+
+impl<D: Operation> Chunker<D> {
+    fn new(operation: D, chunk_size: usize) -> Self {
+        Chunker {
+            operation,
+            buffer: Vec::new(),
+            chunk_size,
+            flush_on_chunk: false,
+            finished: false,
+            finished_frame: false,
+            position: Position::default(),
+        }
+    }
+
+    #[cfg(feature = "experimental")]
+    fn operation(&self) -> &D {
+        &self.operation
+    }
+
+    fn operation_mut(&mut self) -> &mut D {
+        &mut self.operation
+    }
+
+    fn is_full(&self) -> bool {
+        self.buffer.len() >= self.chunk_size
+    }
+
+    /// Returns the current chunk, if not empty.
+    fn take(&mut self) -> Option<Bytes> {
+        if self.buffer.is_empty() {
+            None
+        } else {
+            Some(Bytes::from(std::mem::take(&mut self.buffer)))
+        }
+    }
+
+    /// Runs `f` on the space left in the current chunk.
+    ///
+    /// `f` returns the operation's result and how much input it consumed.
+    fn with_buffer<F>(&mut self, f: F) -> io::Result<usize>
+    where
+        F: FnOnce(
+            &mut OutBuffer<'_, Vec<u8>>,
+            &mut D,
+        ) -> (io::Result<usize>, usize),
+    {
+        if self.buffer.capacity() == 0 {
+            self.buffer = Vec::with_capacity(self.chunk_size);
+        }
+        let before = self.buffer.len();
+        let (result, consumed) = {
+            let mut output = OutBuffer::around_pos(&mut self.buffer, before);
+            f(&mut output, &mut self.operation)
+        };
+        let produced = self.buffer.len() - before;
+        match result {
+            Ok(hint) => {
+                self.position.advance(consumed, produced);
+                Ok(hint)
+            }
+            Err(e) => {
+                let mut position = self.position;
+                position.advance(consumed, produced);
+                Err(locate(e, position))
+            }
+        }
+    }
+
+    /// Feeds some input to the operation, returning how much was consumed.
+    fn run(&mut self, input: &[u8]) -> io::Result<usize> {
+        // Support concatenated frames by re-initializing the context.
+        if self.finished_frame && !input.is_empty() {
+            self.operation.reinit()?;
+            self.finished_frame = false;
+        }
+
+        let mut src = InBuffer::around(input);
+        let hint = self.with_buffer(|dst, op| {
+            let hint = op.run(&mut src, dst);
+            (hint, src.pos())
+        })?;
+        if hint == 0 {
+            self.finished_frame = true;
+            self.position.frame_index += 1;
+        }
+        Ok(src.pos())
+    }
+
+    /// Runs the operation without input, to empty its internal buffers.
+    ///
+    /// Returns `true` if this produced anything.
+    fn drain(&mut self) -> io::Result<bool> {
+        let before = self.buffer.len();
+        self.run(&[])?;
+        Ok(self.buffer.len() > before)
+    }
+
+    /// Flushes the operation; returns `Ok(0)` once everything was flushed.
+    fn flush(&mut self) -> io::Result<usize> {
+        self.with_buffer(|dst, op| (op.flush(dst), 0))
+    }
+
+    /// Ends the stream; returns `Ok(0)` once everything was written.
+    fn finish(&mut self) -> io::Result<usize> {
+        if self.finished {
+            return Ok(0);
+        }
+
+        // Some output may still be waiting for room in the last chunk.
+        if self.drain()? {
+            return Ok(1);
+        }
+
+        let finished_frame = self.finished_frame;
+        let before = self.buffer.len();
+        let hint =
+            self.with_buffer(|dst, op| (op.finish(dst, finished_frame), 0))?;
+
+        if hint != 0 && self.buffer.len() == before && !self.is_full() {
+            // This happens if we are decoding an incomplete frame.
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "incomplete frame",
+            ));
+        }
+        self.finished = hint == 0;
+        Ok(hint)
+    }
+}
+
+enum State {
+    // Feeding input to the operation.
+    Running,
+    // Flushing the operation after an input chunk.
+    Flushing,
+    // Writing the end of the stream.
+    Finishing,
+    // Nothing left to produce.
+    Done,
+}
+
+/// Pulls chunks from `stream` through `chunker`.
+struct Input<S: TryStream> {
+    stream: S,
+    chunk: Option<S::Ok>,
+    pos: usize,
+    state: State,
+}
+
+// Input chunks are never pinned.
+impl<S: TryStream + Unpin> Unpin for Input<S> {}
+
+impl<S> Input<S>
+where
+    S: TryStream + Unpin,
+    S::Ok: AsRef<[u8]>,
+    S::Error: Into<io::Error>,
+{
+    fn new(stream: S) -> Self {
+        Input {
+            stream,
+            chunk: None,
+            pos: 0,
+            state: State::Running,
+        }
+    }
+
+    /// Produces the next output chunk.
+    ///
+    /// Errors end the stream.
+    fn poll_next<D: Operation>(
+        &mut self,
+        chunker: &mut Chunker<D>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<io::Result<Bytes>>> {
+        let result = ready!(self.poll_step(chunker, cx));
+        if !matches!(result, Some(Ok(_))) {
+            self.state = State::Done;
+        }
+        Poll::Ready(result)
+    }
+
+    fn poll_step<D: Operation>(
+        &mut self,
+        chunker: &mut Chunker<D>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<io::Result<Bytes>>> {
+        loop {
+            if chunker.is_full() {
+                return Poll::Ready(chunker.take().map(Ok));
+            }
+
+            match self.state {
+                State::Running => {
+                    if let Some(chunk) = &self.chunk {
+                        let data = &chunk.as_ref()[self.pos..];
+                        if !data.is_empty() {
+                            self.pos += chunker.run(data)?;
+                            continue;
+                        }
+                        self.chunk = None;
+                        if chunker.flush_on_chunk {
+                            self.state = State::Flushing;
+                            continue;
+                        }
+                    }
+
+                    match ready!(Pin::new(&mut self.stream).try_poll_next(cx))
+                    {
+                        Some(Ok(chunk)) => {
+                            self.chunk = Some(chunk);
+                            self.pos = 0;
+                        }
+                        Some(Err(e)) => {
+                            return Poll::Ready(Some(Err(e.into())))
+                        }
+                        None => self.state = State::Finishing,
+                    }
+                }
+                State::Flushing => {
+                    if chunker.flush()? == 0 && !chunker.is_full() {
+                        self.state = State::Running;
+                        if let Some(chunk) = chunker.take() {
+                            return Poll::Ready(Some(Ok(chunk)));
+                        }
+                    }
+                }
+                State::Finishing => {
+                    if chunker.finish()? == 0 && !chunker.is_full() {
+                        self.state = State::Done;
+                        if let Some(chunk) = chunker.take() {
+                            return Poll::Ready(Some(Ok(chunk)));
+                        }
+                    }
+                }
+                State::Done => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+/// Pushes chunks from `chunker` to `sink`.
+struct Output<Si> {
+    sink: Si,
+    chunk: Bytes,
+    pos: usize,
+    flushing: bool,
+}
+
+impl<Si> Output<Si>
+where
+    Si: Sink<Bytes> + Unpin,
+    Si::Error: Into<io::Error>,
+{
+    fn new(sink: Si) -> Self {
+        Output {
+            sink,
+            chunk: Bytes::new(),
+            pos: 0,
+            flushing: false,
+        }
+    }
+
+    /// Sends the current chunk to the sink, even if it isn't full.
+    fn poll_send<D: Operation>(
+        &mut self,
+        chunker: &mut Chunker<D>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        if chunker.buffer.is_empty() {
+            return Poll::Ready(Ok(()));
+        }
+        ready!(Pin::new(&mut self.sink).poll_ready(cx)).map_err(Into::into)?;
+        if let Some(chunk) = chunker.take() {
+            Pin::new(&mut self.sink)
+                .start_send(chunk)
+                .map_err(Into::into)?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Sends full chunks until `step` returns `Ok(0)`.
+    fn poll_until<D: Operation, F>(
+        &mut self,
+        chunker: &mut Chunker<D>,
+        cx: &mut Context<'_>,
+        mut step: F,
+    ) -> Poll<io::Result<()>>
+    where
+        F: FnMut(&mut Chunker<D>) -> io::Result<usize>,
+    {
+        loop {
+            if chunker.is_full() {
+                ready!(self.poll_send(chunker, cx))?;
+            }
+            if step(chunker)? == 0 && !chunker.is_full() {
+                return Poll::Ready(Ok(()));
+            }
+        }
+    }
+
+    /// Processes the pending input chunk.
+    fn poll_drain<D: Operation>(
+        &mut self,
+        chunker: &mut Chunker<D>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        loop {
+            if chunker.is_full() {
+                ready!(self.poll_send(chunker, cx))?;
+            }
+            if self.pos == self.chunk.len() {
+                break;
+            }
+            self.pos += chunker.run(&self.chunk[self.pos..])?;
+        }
+
+        if self.flushing {
+            ready!(self.poll_until(chunker, cx, Chunker::flush))?;
+            ready!(self.poll_send(chunker, cx))?;
+            self.flushing = false;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send<D: Operation>(&mut self, chunker: &Chunker<D>, item: Bytes) {
+        assert_eq!(self.pos, self.chunk.len(), "poll_ready wasn't called");
+        self.flushing = chunker.flush_on_chunk && !item.is_empty();
+        self.chunk = item;
+        self.pos = 0;
+    }
+
+    fn poll_flush<D: Operation>(
+        &mut self,
+        chunker: &mut Chunker<D>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        // Once finished, only the last chunk is left to send.
+        if !chunker.finished {
+            ready!(self.poll_drain(chunker, cx))?;
+            ready!(self.poll_until(chunker, cx, Chunker::flush))?;
+        }
+        ready!(self.poll_send(chunker, cx))?;
+        Pin::new(&mut self.sink).poll_flush(cx).map_err(Into::into)
+    }
+
+    fn poll_close<D: Operation>(
+        &mut self,
+        chunker: &mut Chunker<D>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        if !chunker.finished {
+            ready!(self.poll_drain(chunker, cx))?;
+            ready!(self.poll_until(chunker, cx, Chunker::finish))?;
+        }
+        ready!(self.poll_send(chunker, cx))?;
+        Pin::new(&mut self.sink).poll_close(cx).map_err(Into::into)
+    }
+}
+
+/// A stream of compressed chunks, from a stream of input chunks.
+pub struct Encoder<'a, S: TryStream> {
+    input: Input<S>,
+    chunker: Chunker<raw::Encoder<'a>>,
+}
+
+/// A stream of decompressed chunks, from a stream of compressed chunks.
+pub struct Decoder<'a, S: TryStream> {
+    input: Input<S>,
+    chunker: Chunker<raw::Decoder<'a>>,
+}
+
+/// A sink of input chunks, forwarding compressed chunks to another sink.
+///
+/// Don't forget to close it after the last chunk: this writes the end of the
+/// frame, then closes the inner sink.
+pub struct EncoderSink<'a, Si> {
+    output: Output<Si>,
+    chunker: Chunker<raw::Encoder<'a>>,
+}
+
+/// A sink of compressed chunks, forwarding decompressed chunks to another
+/// sink.
+///
+/// Closing it checks that the last frame was complete, then closes the inner
+/// sink.
+pub struct DecoderSink<'a, Si> {
+    output: Output<Si>,
+    chunker: Chunker<raw::Decoder<'a>>,
+}
+
+/// Implements the builder and accessors of an adapter.
+macro_rules! chunk_options {
+    ($field:ident.$inner:ident: $inner_type:ident) => {
+        /// Sets the maximum size of output chunks.
+        ///
+        /// Defaults to the size recommended by zstd, about 128 KiB.
+        ///
+        /// # Panics
+        ///
+        /// If `chunk_size` is 0.
+        #[must_use]
+        pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+            assert!(chunk_size > 0, "chunk size must be positive");
+            self.chunker.chunk_size = chunk_size;
+            self
+        }
+
+        /// Emits all the output of each input chunk as soon as it is
+        /// consumed.
+        ///
+        /// This may produce small output chunks. When compressing, it also
+        /// affects the compression ratio.
+        #[must_use]
+        pub fn flush_on_chunk(mut self) -> Self {
+            self.chunker.flush_on_chunk = true;
+            self
+        }
+
+        #[doc = concat!("Acquires a reference to the underlying ", stringify!($inner), ".")]
+        pub fn get_ref(&self) -> &$inner_type {
+            &self.$field.$inner
+        }
+
+        #[doc = concat!("Acquires a mutable reference to the underlying ", stringify!($inner), ".")]
+        pub fn get_mut(&mut self) -> &mut $inner_type {
+            &mut self.$field.$inner
+        }
+
+        #[doc = concat!("Returns the underlying ", stringify!($inner), ".")]
+        pub fn into_inner(self) -> $inner_type {
+            self.$field.$inner
+        }
+    };
+}
+
+impl<S> Encoder<'static, S>
+where
+    S: TryStream + Unpin,
+    S::Ok: AsRef<[u8]>,
+    S::Error: Into<io::Error>,
+{
+    /// Creates a new encoder.
+    ///
+    /// A level of `0` uses zstd's default (currently `3`).
+    pub fn new(stream: S, level: i32) -> io::Result<Self> {
+        Self::with_dictionary(stream, level, &[])
+    }
+
+    /// Creates a new encoder, using an existing dictionary.
+    ///
+    /// The dictionary must be the same as the one used during decompression.
+    pub fn with_dictionary(
+        stream: S,
+        level: i32,
+        dictionary: &[u8],
+    ) -> io::Result<Self> {
+        let encoder = raw::Encoder::with_dictionary(level, dictionary)?;
+        Ok(Self::with_encoder(stream, encoder))
+    }
+}
+
+impl<'a, S> Encoder<'a, S>
+where
+    S: TryStream + Unpin,
+    S::Ok: AsRef<[u8]>,
+    S::Error: Into<io::Error>,
+{
+    /// Creates a new encoder, using an existing `EncoderDictionary`.
+    ///
+    /// The dictionary must be the same as the one used during decompression.
+    pub fn with_prepared_dictionary<'b>(
+        stream: S,
+        dictionary: &EncoderDictionary<'b>,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let encoder = raw::Encoder::with_prepared_dictionary(dictionary)?;
+        Ok(Self::with_encoder(stream, encoder))
+    }
+
+    fn with_encoder(stream: S, encoder: raw::Encoder<'a>) -> Self {
+        Encoder {
+            input: Input::new(stream),
+            chunker: Chunker::new(encoder, zstd_safe::CCtx::out_size()),
+        }
+    }
+
+    chunk_options!(input.stream: S);
+
+    crate::encoder_common!(chunker);
+}
+
+impl<S> Stream for Encoder<'_, S>
+where
+    S: TryStream + Unpin,
+    S::Ok: AsRef<[u8]>,
+    S::Error: Into<io::Error>,
+{
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        this.input.poll_next(&mut this.chunker, cx)
+    }
+}
+
+impl<S> Decoder<'static, S>
+where
+    S: TryStream + Unpin,
+    S::Ok: AsRef<[u8]>,
+    S::Error: Into<io::Error>,
+{
+    /// Creates a new decoder.
+    pub fn new(stream: S) -> io::Result<Self> {
+        Self::with_dictionary(stream, &[])
+    }
+
+    /// Creates a new decoder, using an existing dictionary.
+    ///
+    /// The dictionary must be the same as the one used during compression.
+    pub fn with_dictionary(stream: S, dictionary: &[u8]) -> io::Result<Self> {
+        let decoder = raw::Decoder::with_dictionary(dictionary)?;
+        Ok(Self::with_decoder(stream, decoder))
+    }
+}
+
+impl<'a, S> Decoder<'a, S>
+where
+    S: TryStream + Unpin,
+    S::Ok: AsRef<[u8]>,
+    S::Error: Into<io::Error>,
+{
+    /// Creates a new decoder, using an existing `DecoderDictionary`.
+    ///
+    /// The dictionary must be the same as the one used during compression.
+    pub fn with_prepared_dictionary<'b>(
+        stream: S,
+        dictionary: &DecoderDictionary<'b>,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let decoder = raw::Decoder::with_prepared_dictionary(dictionary)?;
+        Ok(Self::with_decoder(stream, decoder))
+    }
+
+    fn with_decoder(stream: S, decoder: raw::Decoder<'a>) -> Self {
+        Decoder {
+            input: Input::new(stream),
+            chunker: Chunker::new(decoder, zstd_safe::DCtx::out_size()),
+        }
+    }
+
+    chunk_options!(input.stream: S);
+
+    crate::decoder_common!(chunker);
+}
+
+impl<S> Stream for Decoder<'_, S>
+where
+    S: TryStream + Unpin,
+    S::Ok: AsRef<[u8]>,
+    S::Error: Into<io::Error>,
+{
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        this.input.poll_next(&mut this.chunker, cx)
+    }
+}
+
+impl<Si> EncoderSink<'static, Si>
+where
+    Si: Sink<Bytes> + Unpin,
+    Si::Error: Into<io::Error>,
+{
+    /// Creates a new encoder.
+    ///
+    /// A level of `0` uses zstd's default (currently `3`).
+    pub fn new(sink: Si, level: i32) -> io::Result<Self> {
+        Self::with_dictionary(sink, level, &[])
+    }
+
+    /// Creates a new encoder, using an existing dictionary.
+    ///
+    /// The dictionary must be the same as the one used during decompression.
+    pub fn with_dictionary(
+        sink: Si,
+        level: i32,
+        dictionary: &[u8],
+    ) -> io::Result<Self> {
+        let encoder = raw::Encoder::with_dictionary(level, dictionary)?;
+        Ok(Self::with_encoder(sink, encoder))
+    }
+}
+
+impl<'a, Si> EncoderSink<'a, Si>
+where
+    Si: Sink<Bytes> + Unpin,
+    Si::Error: Into<io::Error>,
+{
+    /// Creates a new encoder, using an existing `EncoderDictionary`.
+    ///
+    /// The dictionary must be the same as the one used during decompression.
+    pub fn with_prepared_dictionary<'b>(
+        sink: Si,
+        dictionary: &EncoderDictionary<'b>,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let encoder = raw::Encoder::with_prepared_dictionary(dictionary)?;
+        Ok(Self::with_encoder(sink, encoder))
+    }
+
+    fn with_encoder(sink: Si, encoder: raw::Encoder<'a>) -> Self {
+        EncoderSink {
+            output: Output::new(sink),
+            chunker: Chunker::new(encoder, zstd_safe::CCtx::out_size()),
+        }
+    }
+
+    chunk_options!(output.sink: Si);
+
+    crate::encoder_common!(chunker);
+}
+
+impl<Si> Sink<Bytes> for EncoderSink<'_, Si>
+where
+    Si: Sink<Bytes> + Unpin,
+    Si::Error: Into<io::Error>,
+{
+    type Error = io::Error;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.output.poll_drain(&mut this.chunker, cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Bytes) -> io::Result<()> {
+        let this = self.get_mut();
+        this.output.start_send(&this.chunker, item);
+        Ok(())
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.output.poll_flush(&mut this.chunker, cx)
+    }
+
+    fn poll_close(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.output.poll_close(&mut this.chunker, cx)
+    }
+}
+
+impl<Si> DecoderSink<'static, Si>
+where
+    Si: Sink<Bytes> + Unpin,
+    Si::Error: Into<io::Error>,
+{
+    /// Creates a new decoder.
+    pub fn new(sink: Si) -> io::Result<Self> {
+        Self::with_dictionary(sink, &[])
+    }
+
+    /// Creates a new decoder, using an existing dictionary.
+    ///
+    /// The dictionary must be the same as the one used during compression.
+    pub fn with_dictionary(sink: Si, dictionary: &[u8]) -> io::Result<Self> {
+        let decoder = raw::Decoder::with_dictionary(dictionary)?;
+        Ok(Self::with_decoder(sink, decoder))
+    }
+}
+
+impl<'a, Si> DecoderSink<'a, Si>
+where
+    Si: Sink<Bytes> + Unpin,
+    Si::Error: Into<io::Error>,
+{
+    /// Creates a new decoder, using an existing `DecoderDictionary`.
+    ///
+    /// The dictionary must be the same as the one used during compression.
+    pub fn with_prepared_dictionary<'b>(
+        sink: Si,
+        dictionary: &DecoderDictionary<'b>,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let decoder = raw::Decoder::with_prepared_dictionary(dictionary)?;
+        Ok(Self::with_decoder(sink, decoder))
+    }
+
+    fn with_decoder(sink: Si, decoder: raw::Decoder<'a>) -> Self {
+        DecoderSink {
+            output: Output::new(sink),
+            chunker: Chunker::new(decoder, zstd_safe::DCtx::out_size()),
+        }
+    }
+
+    chunk_options!(output.sink: Si);
+
+    crate::decoder_common!(chunker);
+}
+
+impl<Si> Sink<Bytes> for DecoderSink<'_, Si>
+where
+    Si: Sink<Bytes> + Unpin,
+    Si::Error: Into<io::Error>,
+{
+    type Error = io::Error;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.output.poll_drain(&mut this.chunker, cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Bytes) -> io::Result<()> {
+        let this = self.get_mut();
+        this.output.start_send(&this.chunker, item);
+        Ok(())
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.output.poll_flush(&mut this.chunker, cx)
+    }
+
+    fn poll_close(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.output.poll_close(&mut this.chunker, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Decoder, DecoderSink, Encoder, EncoderSink};
+    use std::convert::Infallible;
+    use std::io::{self, Read};
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use bytes::Bytes;
+    use futures_executor::block_on;
+    use futures_util::{stream, SinkExt, StreamExt, TryStreamExt};
+
+    const TEXT: &[u8] = include_bytes!("../../assets/example.txt");
+
+    fn never(e: Infallible) -> io::Error {
+        match e {}
+    }
+
+    /// A sink that is only ready every other time it is polled.
+    #[derive(Default)]
+    struct SlowSink {
+        chunks: Vec<Bytes>,
+        ready: bool,
+    }
+
+    impl SlowSink {
+        fn poll_slowly(
+            &mut self,
+            cx: &mut Context<'_>,
+        ) -> Poll<io::Result<()>> {
+            self.ready = !self.ready;
+            if self.ready {
+                Poll::Ready(Ok(()))
+            } else {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    impl futures_sink::Sink<Bytes> for SlowSink {
+        type Error = io::Error;
+
+        fn poll_ready(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<io::Result<()>> {
+            self.get_mut().poll_slowly(cx)
+        }
+
+        fn start_send(self: Pin<&mut Self>, item: Bytes) -> io::Result<()> {
+            self.get_mut().chunks.push(item);
+            Ok(())
+        }
+
+        fn poll_flush(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<io::Result<()>> {
+            self.get_mut().poll_slowly(cx)
+        }
+
+        fn poll_close(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<io::Result<()>> {
+            self.get_mut().poll_slowly(cx)
+        }
+    }
+
+    fn chunks(
+        data: &[u8],
+        size: usize,
+    ) -> impl futures_core::Stream<Item = io::Result<&[u8]>> {
+        stream::iter(data.chunks(size).map(Ok))
+    }
+
+    #[test]
+    fn test_stream_cycle() {
+        block_on(async {
+            let encoder =
+                Encoder::new(chunks(TEXT, 100), 1).unwrap().chunk_size(64);
+            let compressed: Vec<Bytes> = encoder.try_collect().await.unwrap();
+            let (last, full) = compressed.split_last().unwrap();
+            assert!(full.iter().all(|chunk| chunk.len() == 64));
+            assert!(!last.is_empty() && last.len() <= 64);
+
+            let compressed = compressed.concat();
+            assert_eq!(crate::decode_all(&compressed[..]).unwrap(), TEXT);
+
+            // Concatenated frames are decoded one after the other.
+            let twice = [&compressed[..], &compressed[..]].concat();
+            let decoder =
+                Decoder::new(chunks(&twice, 7)).unwrap().chunk_size(100);
+            let output: Vec<Bytes> = decoder.try_collect().await.unwrap();
+            assert!(output.iter().all(|chunk| chunk.len() <= 100));
+            assert_eq!(output.concat(), [TEXT, TEXT].concat());
+        });
+    }
+
+    #[test]
+    fn test_flush_on_chunk() {
+        block_on(async {
+            let mut encoder =
+                Encoder::new(chunks(TEXT, 100), 1).unwrap().flush_on_chunk();
+
+            // The first output chunk is enough to decode the first input
+            // chunk.
+            let first = encoder.next().await.unwrap().unwrap();
+            let mut decoder =
+                crate::stream::read::Decoder::new(&first[..]).unwrap();
+            let mut output = [0u8; 100];
+            decoder.read_exact(&mut output).unwrap();
+            assert_eq!(&output[..], &TEXT[..100]);
+        });
+    }
+
+    #[test]
+    fn test_sink_cycle() {
+        block_on(async {
+            let sink = Vec::<Bytes>::new().sink_map_err(never);
+            let mut encoder =
+                EncoderSink::new(sink, 1).unwrap().chunk_size(64);
+            for chunk in TEXT.chunks(100) {
+                encoder.feed(Bytes::copy_from_slice(chunk)).await.unwrap();
+            }
+            encoder.close().await.unwrap();
+            let compressed = encoder.into_inner().into_inner();
+            let (_, full) = compressed.split_last().unwrap();
+            assert!(full.iter().all(|chunk| chunk.len() == 64));
+
+            let sink = Vec::<Bytes>::new().sink_map_err(never);
+            let mut decoder = DecoderSink::new(sink).unwrap().flush_on_chunk();
+            for chunk in compressed {
+                decoder.send(chunk).await.unwrap();
+            }
+            decoder.close().await.unwrap();
+            assert_eq!(decoder.into_inner().into_inner().concat(), TEXT);
+        });
+    }
+
+    #[test]
+    fn test_pending_sink() {
+        block_on(async {
+            let mut encoder = EncoderSink::new(SlowSink::default(), 1)
+                .unwrap()
+                .chunk_size(64);
+            encoder.send(Bytes::from_static(TEXT)).await.unwrap();
+            encoder.close().await.unwrap();
+            let compressed = encoder.into_inner().chunks.concat();
+
+            // Waiting for the sink doesn't start another frame.
+            assert_eq!(
+                zstd_safe::find_frame_compressed_size(&compressed),
+                Ok(compressed.len())
+            );
+            assert_eq!(crate::decode_all(&compressed[..]).unwrap(), TEXT);
+        });
+    }
+
+    #[test]
+    fn test_incomplete_frame() {
+        block_on(async {
+            let compressed = crate::encode_all(TEXT, 1).unwrap();
+            let truncated = &compressed[..compressed.len() - 1];
+
+            let decoder = Decoder::new(chunks(truncated, 100)).unwrap();
+            let result: io::Result<Vec<Bytes>> = decoder.try_collect().await;
+            assert_eq!(
+                result.unwrap_err().kind(),
+                io::ErrorKind::UnexpectedEof
+            );
+
+            let sink = Vec::<Bytes>::new().sink_map_err(never);
+            let mut decoder = DecoderSink::new(sink).unwrap();
+            decoder
+                .send(Bytes::copy_from_slice(truncated))
+                .await
+                .unwrap();
+            let err = decoder.close().await.unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        });
+    }
+}
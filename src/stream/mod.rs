@@ -15,9 +15,22 @@ pub mod zio;
 #[cfg(test)]
 mod tests;
 
+pub mod parallel;
 pub mod raw;
+pub mod seekable;
 
-pub use self::functions::{copy_decode, copy_encode, decode_all, encode_all};
+#[cfg(feature = "async-tokio")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "async-tokio")))]
+pub mod tokio;
+
+#[cfg(feature = "async-futures")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "async-futures")))]
+pub mod futures;
+
+pub use self::functions::{
+    copy_decode, copy_decode_with_progress, copy_encode,
+    copy_encode_with_progress, decode_all, encode_all,
+};
 pub use self::read::Decoder;
 pub use self::write::{AutoFinishEncoder, Encoder};
 
@@ -59,6 +72,83 @@ macro_rules! decoder_parameters {
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
+/// Parameter-getters for the decoder. Relies on a `get_parameter` method.
+macro_rules! decoder_getters {
+    () => {
+        /// Returns the maximum back-reference distance, as a power of 2.
+        ///
+        /// `0` means the default limit is used.
+        #[cfg(feature = "experimental")]
+        #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+        pub fn get_window_log_max(&self) -> io::Result<u32> {
+            self.get_parameter(zstd_safe::DParameter::WindowLogMax(0))
+                .map(|value| value as u32)
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+/// Parameter-getters for the encoder. Relies on a `get_parameter` method.
+macro_rules! encoder_getters {
+    () => {
+        /// Returns the current compression level.
+        #[cfg(feature = "experimental")]
+        #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+        pub fn get_compression_level(&self) -> io::Result<i32> {
+            self.get_parameter(zstd_safe::CParameter::CompressionLevel(0))
+        }
+
+        /// Returns the maximum back-reference distance, as a power of 2.
+        ///
+        /// `0` means the window size is picked from the compression level.
+        #[cfg(feature = "experimental")]
+        #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+        pub fn get_window_log(&self) -> io::Result<u32> {
+            self.get_parameter(zstd_safe::CParameter::WindowLog(0))
+                .map(|value| value as u32)
+        }
+
+        /// Returns whether a content checksum is included in each frame.
+        #[cfg(feature = "experimental")]
+        #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+        pub fn get_checksum_flag(&self) -> io::Result<bool> {
+            self.get_parameter(zstd_safe::CParameter::ChecksumFlag(false))
+                .map(|value| value != 0)
+        }
+
+        /// Returns whether the content size is included in each frame, when
+        /// known.
+        #[cfg(feature = "experimental")]
+        #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+        pub fn get_content_size_flag(&self) -> io::Result<bool> {
+            self.get_parameter(zstd_safe::CParameter::ContentSizeFlag(false))
+                .map(|value| value != 0)
+        }
+
+        /// Returns whether the dictionary ID is included in each frame.
+        #[cfg(feature = "experimental")]
+        #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+        pub fn get_dict_id_flag(&self) -> io::Result<bool> {
+            self.get_parameter(zstd_safe::CParameter::DictIdFlag(false))
+                .map(|value| value != 0)
+        }
+
+        /// Returns the number of worker threads used for compression.
+        #[cfg(all(feature = "experimental", feature = "zstdmt"))]
+        #[cfg_attr(
+            feature = "doc-cfg",
+            doc(cfg(all(feature = "experimental", feature = "zstdmt")))
+        )]
+        pub fn get_nb_workers(&self) -> io::Result<u32> {
+            self.get_parameter(zstd_safe::CParameter::NbWorkers(0))
+                .map(|value| value as u32)
+        }
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 /// Common functions for the decoder, both in read and write mode.
@@ -72,7 +162,20 @@ macro_rules! decoder_common {
             self.$readwrite.operation_mut().set_parameter(parameter)
         }
 
+        /// Returns the raw value of a decompression parameter.
+        ///
+        /// Only the kind of `parameter` is used: the value it holds is ignored.
+        #[cfg(feature = "experimental")]
+        #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+        pub fn get_parameter(
+            &self,
+            parameter: zstd_safe::DParameter,
+        ) -> io::Result<i32> {
+            self.$readwrite.operation().get_parameter(parameter)
+        }
+
         $crate::decoder_parameters!();
+        $crate::decoder_getters!();
     };
 }
 
@@ -222,6 +325,60 @@ macro_rules! encoder_common {
             self.$readwrite.operation_mut().set_pledged_src_size(size)
         }
 
+        /// Compresses using the threads of a shared pool.
+        ///
+        /// This also sets the number of workers to the size of the pool.
+        ///
+        /// Fails if a different pool was already set.
+        #[cfg(all(feature = "experimental", feature = "zstdmt"))]
+        #[cfg_attr(
+            feature = "doc-cfg",
+            doc(cfg(all(feature = "experimental", feature = "zstdmt")))
+        )]
+        pub fn set_thread_pool(
+            &mut self,
+            pool: &$crate::ThreadPool,
+        ) -> io::Result<()> {
+            self.$readwrite.operation_mut().set_thread_pool(pool)
+        }
+
+        /// Sets all compression parameters at once.
+        ///
+        /// Nothing is changed if any of them is invalid.
+        #[cfg(feature = "experimental")]
+        #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+        pub fn set_compression_parameters(
+            &mut self,
+            parameters: &zstd_safe::CompressionParameters,
+        ) -> io::Result<()> {
+            self.$readwrite
+                .operation_mut()
+                .set_compression_parameters(parameters)
+        }
+
+        /// Sets all compression and frame parameters at once.
+        #[cfg(feature = "experimental")]
+        #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+        pub fn set_parameters(
+            &mut self,
+            parameters: &zstd_safe::Parameters,
+        ) -> io::Result<()> {
+            self.$readwrite.operation_mut().set_parameters(parameters)
+        }
+
+        /// Returns the raw value of a compression parameter.
+        ///
+        /// Only the kind of `parameter` is used: the value it holds is ignored.
+        #[cfg(feature = "experimental")]
+        #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+        pub fn get_parameter(
+            &self,
+            parameter: zstd_safe::CParameter,
+        ) -> io::Result<i32> {
+            self.$readwrite.operation().get_parameter(parameter)
+        }
+
         $crate::encoder_parameters!();
+        $crate::encoder_getters!();
     };
 }
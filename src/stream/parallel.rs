@@ -0,0 +1,711 @@
+//! Compress and decompress multi-frame streams on several threads.
+//!
+//! Streams produced by `zstdmt`, by the seekable format or by chunked writers
+//! hold many independent frames. [`ParallelDecoder`] splits its input at
+//! frame boundaries and decompresses these frames concurrently, while still
+//! producing the output in order.
+//!
+//! [`ParallelEncoder`] produces such streams: it splits its input in chunks,
+//! and compresses each of them as an independent frame. Unlike the `zstdmt`
+//! feature, this only relies on Rust threads, and the output can itself be
+//! decompressed in parallel.
+//!
+//! Frames are only processed in parallel with each other: a stream made of
+//! a single frame is decompressed by a single thread.
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{self, Read, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use zstd_safe::ErrorKind;
+
+use crate::bulk::{Compressor, Decompressor};
+use crate::error::{relocate, Position};
+use crate::stream::seekable::{SeekTable, MAX_FRAMES, MAX_FRAME_SIZE};
+
+/// Default bound on the memory used by frames being decompressed.
+const DEFAULT_MEMORY_LIMIT: usize = 64 << 20;
+
+/// A pool of threads processing jobs, and returning results in order.
+struct Workers<J, T> {
+    jobs: Option<mpsc::Sender<(u64, J)>>,
+    // `None` if the job panicked.
+    results: mpsc::Receiver<(u64, Option<T>)>,
+    handles: Vec<thread::JoinHandle<()>>,
+
+    // Results received out of order, by job index.
+    pending: BTreeMap<u64, Option<T>>,
+    sent: u64,
+    received: u64,
+}
+
+impl<J: Send + 'static, T: Send + 'static> Workers<J, T> {
+    /// Spawns one thread for each of the given functions.
+    fn spawn<F>(name: &str, functions: Vec<F>) -> io::Result<Self>
+    where
+        F: FnMut(J) -> T + Send + 'static,
+    {
+        let (jobs, job_receiver) = mpsc::channel();
+        let (result_sender, results) = mpsc::channel();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+        let handles = functions
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut function)| {
+                let job_receiver = Arc::clone(&job_receiver);
+                let result_sender = result_sender.clone();
+                thread::Builder::new()
+                    .name(format!("{}-{}", name, i))
+                    .spawn(move || loop {
+                        let job = job_receiver.lock().map(|jobs| jobs.recv());
+                        let (index, job) = match job {
+                            Ok(Ok(job)) => job,
+                            _ => return,
+                        };
+                        // A panic is reported as the result of its job, and
+                        // stops this thread.
+                        let result =
+                            panic::catch_unwind(AssertUnwindSafe(|| {
+                                function(job)
+                            }))
+                            .ok();
+                        let panicked = result.is_none();
+                        if result_sender.send((index, result)).is_err()
+                            || panicked
+                        {
+                            return;
+                        }
+                    })
+            })
+            .collect::<io::Result<_>>()?;
+
+        Ok(Workers {
+            jobs: Some(jobs),
+            results,
+            handles,
+            pending: BTreeMap::new(),
+            sent: 0,
+            received: 0,
+        })
+    }
+
+    /// Returns the number of jobs sent and not yet received.
+    fn in_flight(&self) -> usize {
+        (self.sent - self.received) as usize
+    }
+
+    /// Queues a job for the next available thread.
+    fn send(&mut self, job: J) -> io::Result<()> {
+        let jobs = self.jobs.as_ref().expect("workers are running");
+        jobs.send((self.sent, job)).map_err(|_| workers_gone())?;
+        self.sent += 1;
+        Ok(())
+    }
+
+    /// Waits for the result of the oldest job not received yet.
+    fn receive(&mut self) -> io::Result<T> {
+        assert!(self.in_flight() > 0, "no job in flight");
+        loop {
+            if let Some(result) = self.pending.remove(&self.received) {
+                self.received += 1;
+                return result.ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::Other,
+                        "worker thread panicked",
+                    )
+                });
+            }
+            let (index, result) =
+                self.results.recv().map_err(|_| workers_gone())?;
+            self.pending.insert(index, result);
+        }
+    }
+}
+
+impl<J, T> Drop for Workers<J, T> {
+    fn drop(&mut self) {
+        // Threads stop once the job queue is closed and empty.
+        self.jobs = None;
+        for handle in self.handles.drain(..) {
+            handle.join().ok();
+        }
+    }
+}
+
+/// Error returned when the worker threads stopped unexpectedly.
+fn workers_gone() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "worker threads stopped")
+}
+
+/// A frame to decompress and, when it is small enough to be allocated
+/// upfront, its decompressed size.
+type DecodeJob = (Vec<u8>, Option<usize>);
+
+/// A frame handed to the workers and not yet returned to the reader.
+struct InFlight {
+    compressed_size: usize,
+    cost: usize,
+}
+
+/// Decompresses frames of a zstd stream on several threads.
+///
+/// The output is identical to what [`Decoder`](super::Decoder) would produce.
+///
+/// The source is read from the calling thread, so it doesn't need to be
+/// `Send`. Frames are decompressed on worker threads, each re-using its own
+/// [`Decompressor`].
+pub struct ParallelDecoder<R> {
+    reader: R,
+
+    // Data read from `reader` and not yet sent to the workers.
+    input: Vec<u8>,
+    eof: bool,
+
+    workers: Workers<DecodeJob, io::Result<Vec<u8>>>,
+    in_flight: VecDeque<InFlight>,
+    max_in_flight: usize,
+
+    // Memory used by the frames in flight, and its bound.
+    memory: usize,
+    memory_limit: usize,
+
+    // Start of the next frame to be returned.
+    position: Position,
+    output: Vec<u8>,
+    output_pos: usize,
+}
+
+impl<R: Read> ParallelDecoder<R> {
+    /// Creates a new decoder, using `threads` worker threads.
+    ///
+    /// `threads` is at least 1.
+    pub fn new(reader: R, threads: usize) -> io::Result<Self> {
+        let threads = threads.max(1);
+        let functions = (0..threads)
+            .map(|_| {
+                let mut decompressor = Decompressor::default();
+                let mut context = None;
+                move |(frame, capacity): DecodeJob| match capacity {
+                    Some(capacity) => {
+                        decompressor.decompress(&frame, capacity)
+                    }
+                    None => decode_frame(&mut context, &frame),
+                }
+            })
+            .collect();
+
+        Ok(ParallelDecoder {
+            reader,
+            input: Vec::new(),
+            eof: false,
+            workers: Workers::spawn("zstd-decoder", functions)?,
+            in_flight: VecDeque::new(),
+            max_in_flight: 2 * threads,
+            memory: 0,
+            memory_limit: DEFAULT_MEMORY_LIMIT,
+            position: Position::default(),
+            output: Vec::new(),
+            output_pos: 0,
+        })
+    }
+
+    /// Sets a bound on the memory used by frames being decompressed.
+    ///
+    /// This counts the compressed frames waiting for a worker, and the
+    /// decompressed frames waiting to be read, using the content size from
+    /// their header. No more frames are read from the source while this is
+    /// exceeded, but at least one frame is always decompressed, whatever its
+    /// size.
+    ///
+    /// Frames without a content size are only counted by their compressed
+    /// size: the memory used by their output is not bounded. Frames larger
+    /// than the limit are counted as the whole limit.
+    ///
+    /// Defaults to 64 MiB.
+    pub fn memory_limit(mut self, limit: usize) -> Self {
+        self.memory_limit = limit;
+        self
+    }
+
+    /// Acquires a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Reads more data from the source.
+    ///
+    /// Reads at least as much as is already buffered, so that large frames
+    /// are found with a logarithmic number of scans.
+    fn fill(&mut self) -> io::Result<()> {
+        let wanted = self.input.len().max(zstd_safe::DCtx::in_size());
+        let read = (&mut self.reader)
+            .take(wanted as u64)
+            .read_to_end(&mut self.input)?;
+        self.eof = read < wanted;
+        Ok(())
+    }
+
+    /// Extracts the next frame from the source.
+    ///
+    /// Data that isn't a valid frame, or trailing data that isn't a complete
+    /// frame, is returned as is with everything buffered after it, to fail
+    /// in the worker like it would in a decoder.
+    fn next_frame(&mut self) -> io::Result<Option<Vec<u8>>> {
+        loop {
+            if !self.input.is_empty() {
+                match zstd_safe::find_frame_compressed_size(&self.input) {
+                    Ok(size) => {
+                        return Ok(Some(self.input.drain(..size).collect()))
+                    }
+                    Err(code)
+                        if self.eof
+                            || ErrorKind::from_code(code)
+                                != Some(ErrorKind::SrcSizeWrong) =>
+                    {
+                        // Decoding stops there: don't read any further.
+                        self.eof = true;
+                        return Ok(Some(std::mem::take(&mut self.input)));
+                    }
+                    // The frame is incomplete: read more of it.
+                    Err(_) => (),
+                }
+            } else if self.eof {
+                return Ok(None);
+            }
+            self.fill()?;
+        }
+    }
+
+    /// Sends frames to the workers, as long as the bounds allow it.
+    fn dispatch(&mut self) -> io::Result<()> {
+        while self.in_flight.len() < self.max_in_flight
+            && (self.in_flight.is_empty() || self.memory < self.memory_limit)
+        {
+            let frame = match self.next_frame()? {
+                Some(frame) => frame,
+                None => break,
+            };
+            // Larger frames are decompressed in streaming mode.
+            let content_size =
+                zstd_safe::get_frame_content_size(&frame).ok().flatten();
+            let capacity = content_size
+                .filter(|&size| size <= self.memory_limit as u64)
+                .map(|size| size as usize);
+            let compressed_size = frame.len();
+            let cost = compressed_size
+                + content_size.map_or(0, |size| {
+                    size.min(self.memory_limit as u64) as usize
+                });
+
+            self.workers.send((frame, capacity))?;
+            self.memory += cost;
+            self.in_flight.push_back(InFlight {
+                compressed_size,
+                cost,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for ParallelDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.output_pos < self.output.len() {
+                let remaining = &self.output[self.output_pos..];
+                let n = remaining.len().min(buf.len());
+                buf[..n].copy_from_slice(&remaining[..n]);
+                self.output_pos += n;
+                return Ok(n);
+            }
+
+            self.dispatch()?;
+            let frame = match self.in_flight.pop_front() {
+                Some(frame) => frame,
+                None => return Ok(0),
+            };
+            let result = self.workers.receive()?;
+            self.memory -= frame.cost;
+
+            let start = self.position;
+            self.position.input_offset += frame.compressed_size as u64;
+            self.position.frame_index += 1;
+            self.output = result.map_err(|e| relocate(e, start))?;
+            self.output_pos = 0;
+            self.position.output_offset += self.output.len() as u64;
+        }
+    }
+}
+
+/// Decompresses the entire content of `source` on `threads` threads.
+///
+/// This produces the same output as [`decode_all`](super::decode_all).
+pub fn decode<R: Read>(source: R, threads: usize) -> io::Result<Vec<u8>> {
+    let mut result = Vec::new();
+    ParallelDecoder::new(source, threads)?.read_to_end(&mut result)?;
+    Ok(result)
+}
+
+/// Decompresses a frame in streaming mode.
+fn decode_frame(
+    context: &mut Option<zstd_safe::DCtx<'static>>,
+    frame: &[u8],
+) -> io::Result<Vec<u8>> {
+    let context = context.get_or_insert_with(zstd_safe::DCtx::create);
+    context
+        .reset(zstd_safe::ResetDirective::SessionOnly)
+        .map_err(crate::map_error_code)?;
+
+    let mut result = Vec::new();
+    super::read::Decoder::with_context(frame, context)
+        .single_frame()
+        .read_to_end(&mut result)?;
+    Ok(result)
+}
+
+/// Compresses data as independent frames on several threads.
+///
+/// The input is split in chunks of a fixed size, each compressed by a worker
+/// thread with its own [`Compressor`] into a frame that includes its content
+/// size. Frames are written in order to the underlying writer, from the
+/// calling thread.
+///
+/// Any decoder can read the output, and [`ParallelDecoder`] can decompress
+/// it in parallel. Since each frame is independent, damage to one of them
+/// doesn't prevent decoding the others.
+///
+/// [`finish`](Self::finish) must be called to write the last frame.
+pub struct ParallelEncoder<W: Write> {
+    writer: W,
+
+    // Data not yet sent to the workers.
+    input: Vec<u8>,
+    chunk_size: usize,
+
+    workers: Workers<Vec<u8>, io::Result<Vec<u8>>>,
+    // Decompressed size of each frame in flight.
+    in_flight: VecDeque<usize>,
+    max_in_flight: usize,
+
+    table: Option<SeekTable>,
+}
+
+impl<W: Write> ParallelEncoder<W> {
+    /// Creates a new encoder, using `threads` worker threads.
+    ///
+    /// * `level`: compression level (1-22). A level of `0` uses zstd's
+    ///   default (currently `3`).
+    /// * `threads`: number of worker threads, at least 1.
+    /// * `chunk_size`: decompressed size of each frame. Larger chunks improve
+    ///   the compression ratio, but use more memory. Must be between 1 and
+    ///   [`MAX_FRAME_SIZE`].
+    pub fn new(
+        writer: W,
+        level: i32,
+        threads: usize,
+        chunk_size: usize,
+    ) -> io::Result<Self> {
+        if chunk_size == 0 || chunk_size > MAX_FRAME_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid chunk size",
+            ));
+        }
+
+        let threads = threads.max(1);
+        let functions = (0..threads)
+            .map(|_| {
+                let mut compressor = Compressor::new(level)?;
+                Ok(move |chunk: Vec<u8>| compressor.compress(&chunk))
+            })
+            .collect::<io::Result<_>>()?;
+
+        Ok(ParallelEncoder {
+            writer,
+            input: Vec::new(),
+            chunk_size,
+            workers: Workers::spawn("zstd-encoder", functions)?,
+            in_flight: VecDeque::new(),
+            max_in_flight: 2 * threads,
+            table: None,
+        })
+    }
+
+    /// Appends a seek table to the output, making it a seekable archive.
+    ///
+    /// The archive can then be read with
+    /// [`SeekableDecoder`](super::seekable::SeekableDecoder).
+    ///
+    /// # Panics
+    ///
+    /// If some data was already written.
+    pub fn with_seek_table(mut self) -> Self {
+        assert!(
+            self.input.is_empty() && self.workers.sent == 0,
+            "the seek table must be enabled before writing"
+        );
+        self.table = Some(SeekTable::new());
+        self
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Acquires a mutable reference to the underlying writer.
+    ///
+    /// Note that mutation of the writer may result in surprising results if
+    /// this encoder is continued to be used.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Returns the seek table of the frames written so far, if enabled.
+    pub fn seek_table(&self) -> Option<&SeekTable> {
+        self.table.as_ref()
+    }
+
+    /// Sends the buffered data to the workers as a new frame.
+    fn send_chunk(&mut self) -> io::Result<()> {
+        while self.in_flight.len() >= self.max_in_flight {
+            self.write_frame()?;
+        }
+        let chunk = std::mem::take(&mut self.input);
+        self.in_flight.push_back(chunk.len());
+        self.workers.send(chunk)
+    }
+
+    /// Waits for the oldest frame in flight, and writes it out.
+    fn write_frame(&mut self) -> io::Result<()> {
+        let decompressed_size = self
+            .in_flight
+            .pop_front()
+            .expect("a frame should be in flight");
+        let frame = self.workers.receive()??;
+
+        // Check the limit first, so the output only holds frames listed in
+        // the seek table.
+        if let Some(table) = &self.table {
+            if table.num_frames() == MAX_FRAMES {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "too many frames for a seekable archive",
+                ));
+            }
+        }
+        self.writer.write_all(&frame)?;
+
+        if let Some(table) = &mut self.table {
+            table.push(frame.len() as u64, decompressed_size as u64);
+        }
+        Ok(())
+    }
+
+    /// Compresses the buffered data and writes out every frame in flight.
+    fn write_frames(&mut self) -> io::Result<()> {
+        if !self.input.is_empty() {
+            self.send_chunk()?;
+        }
+        while !self.in_flight.is_empty() {
+            self.write_frame()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the remaining frames, and the seek table if enabled.
+    ///
+    /// Returns the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_frames()?;
+        if let Some(table) = &self.table {
+            self.writer.write_all(&table.to_bytes())?;
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: Write> Write for ParallelEncoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.input.len() == self.chunk_size {
+            self.send_chunk()?;
+        }
+        let len = buf.len().min(self.chunk_size - self.input.len());
+        self.input.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    /// Ends the current frame early, and writes out every frame.
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_frames()?;
+        self.writer.flush()
+    }
+}
+
+fn _assert_traits() {
+    fn _assert_send<T: Send>(_: T) {}
+
+    _assert_send(ParallelDecoder::new(io::empty(), 1));
+    _assert_send(ParallelEncoder::new(Vec::new(), 1, 1, 1024));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, ParallelDecoder, ParallelEncoder, Workers};
+    use std::io::{self, Cursor, Read, Seek, Write};
+
+    const TEXT: &[u8] =
+        include_bytes!("../../zstd-safe/zstd-sys/src/bindings_zstd.rs");
+
+    /// Compresses `data` in many frames, with and without a content size,
+    /// separated by skippable frames.
+    fn encode_frames(data: &[u8]) -> Vec<u8> {
+        let mut result = Vec::new();
+        for (i, chunk) in data.chunks(10_000).enumerate() {
+            // A skippable frame holding a small payload.
+            result.extend(zstd_safe::MAGIC_SKIPPABLE_START.to_le_bytes());
+            result.extend(4u32.to_le_bytes());
+            result.extend(b"meta");
+
+            if i % 2 == 0 {
+                result.extend(crate::encode_all(chunk, 1).unwrap());
+            } else {
+                let mut encoder =
+                    crate::stream::Encoder::new(result, 1).unwrap();
+                encoder.include_contentsize(false).unwrap();
+                encoder.write_all(chunk).unwrap();
+                result = encoder.finish().unwrap();
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_worker_panic() {
+        let function = |job: u32| {
+            assert_ne!(job, 1, "job failed");
+            job
+        };
+        let mut workers = Workers::spawn("test", vec![function; 2]).unwrap();
+        for job in 0..3 {
+            workers.send(job).unwrap();
+        }
+        assert_eq!(workers.receive().unwrap(), 0);
+        assert!(workers.receive().is_err());
+        assert_eq!(workers.receive().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_decode() {
+        let compressed = encode_frames(TEXT);
+        assert_eq!(decode(&compressed[..], 4).unwrap(), TEXT);
+        assert_eq!(decode(&compressed[..], 0).unwrap(), TEXT);
+
+        // A tiny bound still makes progress, one frame at a time.
+        let mut decoder = ParallelDecoder::new(&compressed[..], 3)
+            .unwrap()
+            .memory_limit(1);
+        let mut output = Vec::new();
+        io::copy(&mut decoder, &mut output).unwrap();
+        assert_eq!(output, TEXT);
+
+        assert!(decode(io::empty(), 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_errors() {
+        let compressed = encode_frames(TEXT);
+
+        let truncated = &compressed[..compressed.len() - 1];
+        let err = decode(truncated, 4).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        // Damage the start of the third frame, after the first chunk.
+        let mut offset = 0;
+        for _ in 0..2 {
+            offset +=
+                zstd_safe::find_frame_compressed_size(&compressed[offset..])
+                    .unwrap();
+        }
+        let mut damaged = compressed.clone();
+        for byte in &mut damaged[offset..offset + 8] {
+            *byte = 0xff;
+        }
+
+        // Frames before the damaged one are still returned.
+        let mut decoder = ParallelDecoder::new(&damaged[..], 4).unwrap();
+        let mut output = Vec::new();
+        let err = decoder.read_to_end(&mut output).unwrap_err();
+        assert_eq!(output, &TEXT[..10_000]);
+
+        let position =
+            crate::Error::from_io(&err).unwrap().position().unwrap();
+        assert_eq!(position.input_offset, offset as u64);
+        assert_eq!(position.output_offset, 10_000);
+        assert_eq!(position.frame_index, 2);
+
+        // Invalid data fails without reading the rest of the source.
+        let source = (&damaged[..]).chain(io::repeat(0xff)).take(1 << 30);
+        let mut decoder = ParallelDecoder::new(source, 4).unwrap();
+        assert!(io::copy(&mut decoder, &mut io::sink()).is_err());
+        assert!(decoder.get_ref().limit() > (1 << 30) - (1 << 20));
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut encoder =
+            ParallelEncoder::new(Vec::new(), 3, 4, 1000).unwrap();
+        // Writes don't need to be aligned with chunks.
+        for piece in TEXT.chunks(777) {
+            encoder.write_all(piece).unwrap();
+        }
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(crate::decode_all(&compressed[..]).unwrap(), TEXT);
+        assert_eq!(decode(&compressed[..], 4).unwrap(), TEXT);
+
+        // Every chunk is an independent frame, with its content size.
+        let mut rest = &compressed[..];
+        let mut num_frames = 0;
+        while !rest.is_empty() {
+            let size = zstd_safe::find_frame_compressed_size(rest).unwrap();
+            let content_size = zstd_safe::get_frame_content_size(rest);
+            assert!(matches!(content_size, Ok(Some(1..=1000))));
+            rest = &rest[size..];
+            num_frames += 1;
+        }
+        assert_eq!(num_frames, (TEXT.len() + 999) / 1000);
+
+        let encoder = ParallelEncoder::new(Vec::new(), 3, 2, 1000).unwrap();
+        assert!(encoder.finish().unwrap().is_empty());
+
+        assert!(ParallelEncoder::new(Vec::new(), 3, 2, 0).is_err());
+    }
+
+    #[test]
+    fn test_encode_seekable() {
+        let mut encoder = ParallelEncoder::new(Vec::new(), 1, 3, 1000)
+            .unwrap()
+            .with_seek_table();
+        encoder.write_all(TEXT).unwrap();
+        encoder.flush().unwrap();
+        let table = encoder.seek_table().unwrap();
+        assert_eq!(table.num_frames(), (TEXT.len() + 999) / 1000);
+        assert_eq!(table.decompressed_size(), TEXT.len() as u64);
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(decode(&compressed[..], 2).unwrap(), TEXT);
+
+        let mut decoder = crate::stream::seekable::SeekableDecoder::new(
+            Cursor::new(compressed),
+        )
+        .unwrap();
+        decoder.seek(io::SeekFrom::Start(2500)).unwrap();
+        let mut buffer = [0u8; 1000];
+        decoder.read_exact(&mut buffer).unwrap();
+        assert_eq!(&buffer[..], &TEXT[2500..3500]);
+    }
+}
@@ -4,9 +4,13 @@
 //! of data using buffers.
 //!
 //! They are mostly thin wrappers around `zstd_safe::{DCtx, CCtx}`.
+#[cfg(feature = "experimental")]
+use std::alloc::GlobalAlloc;
 use std::io;
 
 pub use zstd_safe::{CParameter, DParameter, InBuffer, OutBuffer, WriteBuf};
+#[cfg(feature = "experimental")]
+pub use zstd_safe::{DictContentType, DictLoadMethod};
 
 use crate::dict::{DecoderDictionary, EncoderDictionary};
 use crate::map_error_code;
@@ -133,6 +137,8 @@ pub struct Status {
 /// An in-memory decoder for streams of data.
 pub struct Decoder<'a> {
     context: MaybeOwnedDCtx<'a>,
+    #[cfg(feature = "experimental")]
+    skippable_frames: Option<SkippableFrames<'a>>,
 }
 
 impl Decoder<'static> {
@@ -148,18 +154,48 @@ impl Decoder<'static> {
         context
             .load_dictionary(dictionary)
             .map_err(map_error_code)?;
-        Ok(Decoder {
-            context: MaybeOwnedDCtx::Owned(context),
-        })
+        Ok(Decoder::from_context(MaybeOwnedDCtx::Owned(context)))
     }
 }
 
 impl<'a> Decoder<'a> {
+    fn from_context(context: MaybeOwnedDCtx<'a>) -> Self {
+        Decoder {
+            context,
+            #[cfg(feature = "experimental")]
+            skippable_frames: None,
+        }
+    }
+
     /// Creates a new decoder which employs the provided context for deserialization.
     pub fn with_context(context: &'a mut zstd_safe::DCtx<'static>) -> Self {
-        Self {
-            context: MaybeOwnedDCtx::Borrowed(context),
-        }
+        Self::from_context(MaybeOwnedDCtx::Borrowed(context))
+    }
+
+    /// Creates a new decoder using `allocator` for all its memory.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_allocator<A: GlobalAlloc + Sync>(
+        allocator: &'a A,
+    ) -> io::Result<Self> {
+        let context = zstd_safe::DCtx::try_create_with_allocator(allocator)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::OutOfMemory,
+                    "failed to allocate zstd context",
+                )
+            })?;
+        Ok(Decoder::from_context(MaybeOwnedDCtx::Owned(context)))
+    }
+
+    /// Estimates the memory needed to decode the frame starting with `src`.
+    ///
+    /// `src` only needs to contain the frame header.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn estimate_memory_for_frame(src: &[u8]) -> io::Result<usize> {
+        zstd_safe::estimate_dstream_size_from_frame(src)
+            .map_err(map_error_code)
     }
 
     /// Creates a new decoder, using an existing `DecoderDictionary`.
@@ -173,9 +209,7 @@ impl<'a> Decoder<'a> {
         context
             .ref_ddict(dictionary.as_ddict())
             .map_err(map_error_code)?;
-        Ok(Decoder {
-            context: MaybeOwnedDCtx::Owned(context),
-        })
+        Ok(Decoder::from_context(MaybeOwnedDCtx::Owned(context)))
     }
 
     /// Creates a new decoder, using a ref prefix
@@ -185,9 +219,43 @@ impl<'a> Decoder<'a> {
     {
         let mut context = zstd_safe::DCtx::create();
         context.ref_prefix(ref_prefix).map_err(map_error_code)?;
-        Ok(Decoder {
-            context: MaybeOwnedDCtx::Owned(context),
-        })
+        Ok(Decoder::from_context(MaybeOwnedDCtx::Owned(context)))
+    }
+
+    /// Creates a new decoder initialized with the given dictionary, loaded
+    /// and interpreted as specified.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_dictionary_advanced<'b>(
+        dictionary: &'b [u8],
+        load_method: DictLoadMethod,
+        content_type: DictContentType,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let mut context = zstd_safe::DCtx::create();
+        context
+            .load_dictionary_advanced(dictionary, load_method, content_type)
+            .map_err(map_error_code)?;
+        Ok(Decoder::from_context(MaybeOwnedDCtx::Owned(context)))
+    }
+
+    /// Creates a new decoder, using a ref prefix interpreted as specified.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_ref_prefix_advanced<'b>(
+        ref_prefix: &'b [u8],
+        content_type: DictContentType,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let mut context = zstd_safe::DCtx::create();
+        context
+            .ref_prefix_advanced(ref_prefix, content_type)
+            .map_err(map_error_code)?;
+        Ok(Decoder::from_context(MaybeOwnedDCtx::Owned(context)))
     }
 
     /// Sets a decompression parameter for this decoder.
@@ -199,6 +267,49 @@ impl<'a> Decoder<'a> {
         .map_err(map_error_code)?;
         Ok(())
     }
+
+    /// Returns the raw value of a decompression parameter.
+    ///
+    /// Only the kind of `parameter` is used: the value it holds is ignored.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn get_parameter(&self, parameter: DParameter) -> io::Result<i32> {
+        match &self.context {
+            MaybeOwnedDCtx::Owned(x) => x.get_parameter(parameter),
+            MaybeOwnedDCtx::Borrowed(x) => x.get_parameter(parameter),
+        }
+        .map_err(map_error_code)
+    }
+
+    crate::decoder_getters!();
+
+    /// Sets a callback to receive the content of skippable frames.
+    ///
+    /// By default, skippable frames are silently skipped. When a handler is
+    /// set, it will instead be called with the magic variant and the user
+    /// data of each skippable frame found between regular frames.
+    ///
+    /// Skippable frames are buffered entirely in memory before calling the
+    /// handler. Decoding fails on skippable frames holding more than
+    /// `max_size` bytes of user data.
+    ///
+    /// This should be set before decoding any data.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn set_skippable_frame_handler<F>(
+        &mut self,
+        max_size: usize,
+        handler: F,
+    ) where
+        F: FnMut(u32, &[u8]) + Send + 'a,
+    {
+        self.skippable_frames = Some(SkippableFrames {
+            handler: Box::new(handler),
+            max_size,
+            state: SkippableState::FrameStart,
+            buffer: Vec::new(),
+        });
+    }
 }
 
 impl Operation for Decoder<'_> {
@@ -207,11 +318,25 @@ impl Operation for Decoder<'_> {
         input: &mut InBuffer<'_>,
         output: &mut OutBuffer<'_, C>,
     ) -> io::Result<usize> {
-        match &mut self.context {
-            MaybeOwnedDCtx::Owned(x) => x.decompress_stream(output, input),
-            MaybeOwnedDCtx::Borrowed(x) => x.decompress_stream(output, input),
+        #[cfg(feature = "experimental")]
+        if let Some(skippable_frames) = &mut self.skippable_frames {
+            if let Some(hint) =
+                skippable_frames.run(&mut self.context, input, output)?
+            {
+                return Ok(hint);
+            }
         }
-        .map_err(map_error_code)
+
+        let hint = self.context.decompress_stream(output, input)?;
+
+        #[cfg(feature = "experimental")]
+        if let Some(skippable_frames) = &mut self.skippable_frames {
+            if hint == 0 {
+                skippable_frames.state = SkippableState::FrameStart;
+            }
+        }
+
+        Ok(hint)
     }
 
     fn flush<C: WriteBuf + ?Sized>(
@@ -241,6 +366,13 @@ impl Operation for Decoder<'_> {
             }
         }
         .map_err(map_error_code)?;
+
+        #[cfg(feature = "experimental")]
+        if let Some(skippable_frames) = &mut self.skippable_frames {
+            skippable_frames.state = SkippableState::FrameStart;
+            skippable_frames.buffer.clear();
+        }
+
         Ok(())
     }
 
@@ -283,18 +415,90 @@ impl Encoder<'static> {
             .load_dictionary(dictionary)
             .map_err(map_error_code)?;
 
-        Ok(Encoder {
-            context: MaybeOwnedCCtx::Owned(context),
-        })
+        Ok(Encoder::from_context(MaybeOwnedCCtx::Owned(context)))
+    }
+
+    /// Creates a new encoder configured with the given parameters.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_cctx_params(
+        params: &zstd_safe::CCtxParams,
+    ) -> io::Result<Self> {
+        let mut context = zstd_safe::CCtx::create();
+
+        context
+            .set_parameters_using_cctx_params(params)
+            .map_err(map_error_code)?;
+
+        Ok(Encoder::from_context(MaybeOwnedCCtx::Owned(context)))
+    }
+
+    /// Creates a new encoder using the threads of a shared pool.
+    #[cfg(all(feature = "experimental", feature = "zstdmt"))]
+    #[cfg_attr(
+        feature = "doc-cfg",
+        doc(cfg(all(feature = "experimental", feature = "zstdmt")))
+    )]
+    pub fn with_thread_pool(
+        level: i32,
+        pool: &crate::ThreadPool,
+    ) -> io::Result<Self> {
+        let mut encoder = Self::new(level)?;
+        encoder.set_thread_pool(pool)?;
+        Ok(encoder)
     }
 }
 
 impl<'a> Encoder<'a> {
+    fn from_context(context: MaybeOwnedCCtx<'a>) -> Self {
+        Encoder { context }
+    }
+
     /// Creates a new encoder that uses the provided context for serialization.
     pub fn with_context(context: &'a mut zstd_safe::CCtx<'static>) -> Self {
-        Self {
-            context: MaybeOwnedCCtx::Borrowed(context),
-        }
+        Encoder::from_context(MaybeOwnedCCtx::Borrowed(context))
+    }
+
+    /// Creates a new encoder using `allocator` for all its memory.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_allocator<A: GlobalAlloc + Sync>(
+        level: i32,
+        allocator: &'a A,
+    ) -> io::Result<Self> {
+        let mut context = zstd_safe::CCtx::try_create_with_allocator(
+            allocator,
+        )
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::OutOfMemory,
+                "failed to allocate zstd context",
+            )
+        })?;
+
+        context
+            .set_parameter(CParameter::CompressionLevel(level))
+            .map_err(map_error_code)?;
+
+        Ok(Encoder::from_context(MaybeOwnedCCtx::Owned(context)))
+    }
+
+    /// Estimates the memory needed by an encoder.
+    ///
+    /// This assumes no dictionary and a single thread. `window_log` overrides
+    /// the window size implied by the level, and `pledged_size` is the
+    /// expected input size, if known.
+    ///
+    /// Returns an error if `window_log` is out of bounds.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn estimate_memory(
+        level: i32,
+        window_log: Option<u32>,
+        pledged_size: Option<u64>,
+    ) -> io::Result<usize> {
+        zstd_safe::estimate_cstream_size_for(level, window_log, pledged_size)
+            .map_err(map_error_code)
     }
 
     /// Creates a new encoder using an existing `EncoderDictionary`.
@@ -308,9 +512,7 @@ impl<'a> Encoder<'a> {
         context
             .ref_cdict(dictionary.as_cdict())
             .map_err(map_error_code)?;
-        Ok(Encoder {
-            context: MaybeOwnedCCtx::Owned(context),
-        })
+        Ok(Encoder::from_context(MaybeOwnedCCtx::Owned(context)))
     }
 
     /// Creates a new encoder initialized with the given ref prefix.
@@ -329,9 +531,78 @@ impl<'a> Encoder<'a> {
 
         context.ref_prefix(ref_prefix).map_err(map_error_code)?;
 
-        Ok(Encoder {
-            context: MaybeOwnedCCtx::Owned(context),
-        })
+        Ok(Encoder::from_context(MaybeOwnedCCtx::Owned(context)))
+    }
+
+    /// Creates a new encoder initialized with the given dictionary, loaded
+    /// and interpreted as specified.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_dictionary_advanced<'b>(
+        level: i32,
+        dictionary: &'b [u8],
+        load_method: DictLoadMethod,
+        content_type: DictContentType,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let mut context = zstd_safe::CCtx::create();
+
+        context
+            .set_parameter(CParameter::CompressionLevel(level))
+            .map_err(map_error_code)?;
+
+        context
+            .load_dictionary_advanced(dictionary, load_method, content_type)
+            .map_err(map_error_code)?;
+
+        Ok(Encoder::from_context(MaybeOwnedCCtx::Owned(context)))
+    }
+
+    /// Creates a new encoder initialized with the given ref prefix,
+    /// interpreted as specified.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_ref_prefix_advanced<'b>(
+        level: i32,
+        ref_prefix: &'b [u8],
+        content_type: DictContentType,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let mut context = zstd_safe::CCtx::create();
+
+        context
+            .set_parameter(CParameter::CompressionLevel(level))
+            .map_err(map_error_code)?;
+
+        context
+            .ref_prefix_advanced(ref_prefix, content_type)
+            .map_err(map_error_code)?;
+
+        Ok(Encoder::from_context(MaybeOwnedCCtx::Owned(context)))
+    }
+
+    /// Compresses using the threads of a shared pool.
+    ///
+    /// This also sets the number of workers to the size of the pool.
+    ///
+    /// Fails if a different pool was already set.
+    #[cfg(all(feature = "experimental", feature = "zstdmt"))]
+    #[cfg_attr(
+        feature = "doc-cfg",
+        doc(cfg(all(feature = "experimental", feature = "zstdmt")))
+    )]
+    pub fn set_thread_pool(
+        &mut self,
+        pool: &crate::ThreadPool,
+    ) -> io::Result<()> {
+        match &mut self.context {
+            MaybeOwnedCCtx::Owned(x) => pool.attach(x),
+            MaybeOwnedCCtx::Borrowed(x) => pool.attach(x),
+        }
     }
 
     /// Sets a compression parameter for this encoder.
@@ -344,6 +615,57 @@ impl<'a> Encoder<'a> {
         Ok(())
     }
 
+    /// Returns the raw value of a compression parameter.
+    ///
+    /// Only the kind of `parameter` is used: the value it holds is ignored.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn get_parameter(&self, parameter: CParameter) -> io::Result<i32> {
+        match &self.context {
+            MaybeOwnedCCtx::Owned(x) => x.get_parameter(parameter),
+            MaybeOwnedCCtx::Borrowed(x) => x.get_parameter(parameter),
+        }
+        .map_err(map_error_code)
+    }
+
+    crate::encoder_getters!();
+
+    /// Sets all compression parameters at once.
+    ///
+    /// Nothing is changed if any of them is invalid.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn set_compression_parameters(
+        &mut self,
+        parameters: &zstd_safe::CompressionParameters,
+    ) -> io::Result<()> {
+        match &mut self.context {
+            MaybeOwnedCCtx::Owned(x) => {
+                x.set_compression_parameters(parameters)
+            }
+            MaybeOwnedCCtx::Borrowed(x) => {
+                x.set_compression_parameters(parameters)
+            }
+        }
+        .map_err(map_error_code)?;
+        Ok(())
+    }
+
+    /// Sets all compression and frame parameters at once.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn set_parameters(
+        &mut self,
+        parameters: &zstd_safe::Parameters,
+    ) -> io::Result<()> {
+        match &mut self.context {
+            MaybeOwnedCCtx::Owned(x) => x.set_parameters(parameters),
+            MaybeOwnedCCtx::Borrowed(x) => x.set_parameters(parameters),
+        }
+        .map_err(map_error_code)?;
+        Ok(())
+    }
+
     /// Sets the size of the input expected by zstd.
     ///
     /// May affect compression ratio.
@@ -367,6 +689,47 @@ impl<'a> Encoder<'a> {
         .map_err(map_error_code)?;
         Ok(())
     }
+
+    /// Returns the progress of the frame currently being compressed.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn frame_progression(&self) -> zstd_safe::FrameProgression {
+        match &self.context {
+            MaybeOwnedCCtx::Owned(x) => x.frame_progression(),
+            MaybeOwnedCCtx::Borrowed(x) => x.frame_progression(),
+        }
+    }
+
+    /// Uses the given sequence producer to find matches.
+    ///
+    /// If the producer fails or panics on a block, the built-in match finder
+    /// is used instead.
+    ///
+    /// This is not supported when using a borrowed context (see
+    /// `Encoder::with_context`): register the producer on that context
+    /// directly instead.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn set_sequence_producer<P>(
+        &mut self,
+        producer: &'a mut P,
+    ) -> io::Result<()>
+    where
+        P: zstd_safe::SequenceProducer + Send,
+    {
+        match &mut self.context {
+            MaybeOwnedCCtx::Owned(x) => x
+                .register_sequence_producer(producer)
+                .map_err(map_error_code)?,
+            MaybeOwnedCCtx::Borrowed(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "cannot set a sequence producer on a borrowed context",
+                ))
+            }
+        };
+        Ok(())
+    }
 }
 
 impl<'a> Operation for Encoder<'a> {
@@ -429,6 +792,145 @@ enum MaybeOwnedDCtx<'a> {
     Borrowed(&'a mut zstd_safe::DCtx<'static>),
 }
 
+impl MaybeOwnedDCtx<'_> {
+    fn decompress_stream<C: WriteBuf + ?Sized>(
+        &mut self,
+        output: &mut OutBuffer<'_, C>,
+        input: &mut InBuffer<'_>,
+    ) -> io::Result<usize> {
+        match self {
+            MaybeOwnedDCtx::Owned(x) => x.decompress_stream(output, input),
+            MaybeOwnedDCtx::Borrowed(x) => x.decompress_stream(output, input),
+        }
+        .map_err(map_error_code)
+    }
+}
+
+/// Receives the magic variant and user data of skippable frames.
+#[cfg(feature = "experimental")]
+type SkippableFrameHandler<'a> = Box<dyn FnMut(u32, &[u8]) + Send + 'a>;
+
+/// Intercepts skippable frames before they reach the decompression context.
+#[cfg(feature = "experimental")]
+struct SkippableFrames<'a> {
+    handler: SkippableFrameHandler<'a>,
+    max_size: usize,
+    state: SkippableState,
+
+    // Beginning of the current frame, until we know what to do with it.
+    buffer: Vec<u8>,
+}
+
+#[cfg(feature = "experimental")]
+enum SkippableState {
+    // Looking for the magic number of the next frame.
+    FrameStart,
+    // Buffering a skippable frame.
+    Skippable,
+    // Feeding the buffered start of a regular frame to the context,
+    // starting at the given offset.
+    Replay(usize),
+    // In the middle of a regular frame.
+    Regular,
+}
+
+#[cfg(feature = "experimental")]
+impl SkippableFrames<'_> {
+    /// Handles any skippable frame at the beginning of `input`.
+    ///
+    /// Returns `None` if `input` should be given to the context instead.
+    fn run<C: WriteBuf + ?Sized>(
+        &mut self,
+        context: &mut MaybeOwnedDCtx<'_>,
+        input: &mut InBuffer<'_>,
+        output: &mut OutBuffer<'_, C>,
+    ) -> io::Result<Option<usize>> {
+        const MAGIC_SIZE: usize = 4;
+        const HEADER_SIZE: usize = zstd_safe::SKIPPABLEHEADERSIZE as usize;
+
+        loop {
+            match self.state {
+                SkippableState::Regular => return Ok(None),
+                SkippableState::FrameStart => {
+                    if !self.fill(input, MAGIC_SIZE) {
+                        return Ok(Some(MAGIC_SIZE - self.buffer.len()));
+                    }
+                    self.state = if zstd_safe::is_skippable_frame(&self.buffer)
+                    {
+                        SkippableState::Skippable
+                    } else {
+                        SkippableState::Replay(0)
+                    };
+                }
+                SkippableState::Skippable => {
+                    if !self.fill(input, HEADER_SIZE) {
+                        return Ok(Some(HEADER_SIZE - self.buffer.len()));
+                    }
+                    let mut size = [0u8; 4];
+                    size.copy_from_slice(
+                        &self.buffer[MAGIC_SIZE..HEADER_SIZE],
+                    );
+                    let frame_size = Some(u32::from_le_bytes(size) as usize)
+                        .filter(|&size| size <= self.max_size)
+                        .and_then(|size| HEADER_SIZE.checked_add(size))
+                        .ok_or_else(|| {
+                            io::Error::new(
+                                io::ErrorKind::Other,
+                                "skippable frame is too large",
+                            )
+                        })?;
+
+                    if !self.fill(input, frame_size) {
+                        return Ok(Some(frame_size - self.buffer.len()));
+                    }
+
+                    let mut data =
+                        Vec::with_capacity(frame_size - HEADER_SIZE);
+                    let (_, magic_variant) = zstd_safe::read_skippable_frame(
+                        &mut data,
+                        &self.buffer,
+                    )
+                    .map_err(map_error_code)?;
+                    (self.handler)(magic_variant, &data);
+
+                    self.buffer.clear();
+                    self.state = SkippableState::FrameStart;
+                    return Ok(Some(0));
+                }
+                SkippableState::Replay(pos) => {
+                    let mut src = InBuffer::around(&self.buffer[pos..]);
+                    let hint = context.decompress_stream(output, &mut src)?;
+                    let pos = pos + src.pos();
+
+                    if pos < self.buffer.len() {
+                        self.state = SkippableState::Replay(pos);
+                    } else {
+                        self.buffer.clear();
+                        self.state = if hint == 0 {
+                            SkippableState::FrameStart
+                        } else {
+                            SkippableState::Regular
+                        };
+                    }
+                    return Ok(Some(hint));
+                }
+            }
+        }
+    }
+
+    /// Moves bytes from `input` to `self.buffer`, up to `size` bytes in total.
+    ///
+    /// Returns `true` if the buffer now holds `size` bytes.
+    fn fill(&mut self, input: &mut InBuffer<'_>, size: usize) -> bool {
+        let missing = size.saturating_sub(self.buffer.len());
+        let available = &input.src[input.pos()..];
+        let len = usize::min(missing, available.len());
+        self.buffer.extend_from_slice(&available[..len]);
+        input.set_pos(input.pos() + len);
+        self.buffer.len() >= size
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
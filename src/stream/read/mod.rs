@@ -1,4 +1,6 @@
 //! Implement pull-based [`Read`] trait for both compressing and decompressing.
+#[cfg(feature = "experimental")]
+use std::alloc::GlobalAlloc;
 use std::io::{self, BufRead, BufReader, Read};
 
 use crate::dict::{DecoderDictionary, EncoderDictionary};
@@ -59,6 +61,19 @@ impl<'a, R: BufRead> Decoder<'a, R> {
         }
     }
 
+    /// Creates a new decoder using `allocator` for all its memory.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_allocator<A: GlobalAlloc + Sync>(
+        reader: R,
+        allocator: &'a A,
+    ) -> io::Result<Self> {
+        let decoder = raw::Decoder::with_allocator(allocator)?;
+        let reader = zio::Reader::new(reader, decoder);
+
+        Ok(Decoder { reader })
+    }
+
     /// Sets this `Decoder` to stop after the first frame.
     ///
     /// By default, it keeps concatenating frames until EOF is reached.
@@ -68,6 +83,27 @@ impl<'a, R: BufRead> Decoder<'a, R> {
         self
     }
 
+    /// Sets a callback to receive the content of skippable frames.
+    ///
+    /// By default, skippable frames are silently skipped. With this, the
+    /// handler is instead called with the magic variant and the user data of
+    /// each skippable frame found in the stream.
+    ///
+    /// Skippable frames are kept in memory until the handler is called, so
+    /// reading fails on frames holding more than `max_size` bytes.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    #[must_use]
+    pub fn on_skippable_frame<F>(mut self, max_size: usize, handler: F) -> Self
+    where
+        F: FnMut(u32, &[u8]) + Send + 'a,
+    {
+        self.reader
+            .operation_mut()
+            .set_skippable_frame_handler(max_size, handler);
+        self
+    }
+
     /// Creates a new decoder, using an existing `DecoderDictionary`.
     ///
     /// The dictionary must be the same as the one used during compression.
@@ -100,6 +136,51 @@ impl<'a, R: BufRead> Decoder<'a, R> {
         Ok(Decoder { reader })
     }
 
+    /// Creates a new decoder, using a dictionary loaded and interpreted as
+    /// specified.
+    ///
+    /// The dictionary must be the same as the one used during compression.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_dictionary_advanced<'b>(
+        reader: R,
+        dictionary: &'b [u8],
+        load_method: raw::DictLoadMethod,
+        content_type: raw::DictContentType,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let decoder = raw::Decoder::with_dictionary_advanced(
+            dictionary,
+            load_method,
+            content_type,
+        )?;
+        let reader = zio::Reader::new(reader, decoder);
+
+        Ok(Decoder { reader })
+    }
+
+    /// Creates a new decoder, using a ref prefix interpreted as specified.
+    ///
+    /// The prefix must be the same as the one used during compression.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_ref_prefix_advanced<'b>(
+        reader: R,
+        ref_prefix: &'b [u8],
+        content_type: raw::DictContentType,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let decoder =
+            raw::Decoder::with_ref_prefix_advanced(ref_prefix, content_type)?;
+        let reader = zio::Reader::new(reader, decoder);
+
+        Ok(Decoder { reader })
+    }
+
     /// Recommendation for the size of the output buffer.
     pub fn recommended_output_size() -> usize {
         zstd_safe::DCtx::out_size()
@@ -163,9 +244,53 @@ impl<R: BufRead> Encoder<'static, R> {
 
         Ok(Encoder { reader })
     }
+
+    /// Creates a new encoder, configured with the given parameters.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_cctx_params(
+        reader: R,
+        params: &zstd_safe::CCtxParams,
+    ) -> io::Result<Self> {
+        let encoder = raw::Encoder::with_cctx_params(params)?;
+        let reader = zio::Reader::new(reader, encoder);
+
+        Ok(Encoder { reader })
+    }
+
+    /// Creates a new encoder, using the threads of a shared pool.
+    #[cfg(all(feature = "experimental", feature = "zstdmt"))]
+    #[cfg_attr(
+        feature = "doc-cfg",
+        doc(cfg(all(feature = "experimental", feature = "zstdmt")))
+    )]
+    pub fn with_thread_pool(
+        reader: R,
+        level: i32,
+        pool: &crate::ThreadPool,
+    ) -> io::Result<Self> {
+        let encoder = raw::Encoder::with_thread_pool(level, pool)?;
+        let reader = zio::Reader::new(reader, encoder);
+
+        Ok(Encoder { reader })
+    }
 }
 
 impl<'a, R: BufRead> Encoder<'a, R> {
+    /// Creates a new encoder using `allocator` for all its memory.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_allocator<A: GlobalAlloc + Sync>(
+        reader: R,
+        level: i32,
+        allocator: &'a A,
+    ) -> io::Result<Self> {
+        let encoder = raw::Encoder::with_allocator(level, allocator)?;
+        let reader = zio::Reader::new(reader, encoder);
+
+        Ok(Encoder { reader })
+    }
+
     /// Creates a new encoder, using an existing `EncoderDictionary`.
     ///
     /// The dictionary must be the same as the one used during compression.
@@ -182,6 +307,31 @@ impl<'a, R: BufRead> Encoder<'a, R> {
         Ok(Encoder { reader })
     }
 
+    /// Creates a new encoder, using a dictionary loaded and interpreted as
+    /// specified.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_dictionary_advanced<'b>(
+        reader: R,
+        level: i32,
+        dictionary: &'b [u8],
+        load_method: raw::DictLoadMethod,
+        content_type: raw::DictContentType,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let encoder = raw::Encoder::with_dictionary_advanced(
+            level,
+            dictionary,
+            load_method,
+            content_type,
+        )?;
+        let reader = zio::Reader::new(reader, encoder);
+
+        Ok(Encoder { reader })
+    }
+
     /// Recommendation for the size of the output buffer.
     pub fn recommended_output_size() -> usize {
         zstd_safe::CCtx::out_size()
@@ -0,0 +1,712 @@
+//! Read and write the zstd seekable format.
+//!
+//! A seekable archive is a regular sequence of independent zstd frames,
+//! followed by a skippable frame holding a _seek table_: the compressed and
+//! decompressed size of each frame.
+//!
+//! Any zstd decoder can read such an archive as a normal stream, but the seek
+//! table also lets [`SeekableDecoder`] jump to an arbitrary offset and only
+//! decode the frames that cover it.
+//!
+//! The format is described in the zstd repository, under
+//! `contrib/seekable_format/zstd_seekable_compression_format.md`.
+//!
+//! Per-frame checksums in the seek table are not written. When reading an
+//! archive that includes them, they are ignored.
+use std::convert::TryFrom;
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+
+use crate::stream::raw::{self, InBuffer, Operation, OutBuffer, WriteBuf};
+use crate::stream::zio;
+
+/// Magic number of the skippable frame holding the seek table.
+const SEEK_TABLE_FRAME_MAGIC: u32 = zstd_safe::MAGIC_SKIPPABLE_START | 0xE;
+
+/// Magic number ending the seek table.
+const SEEKABLE_MAGIC: u32 = 0x8F92_EAB1;
+
+/// Size of the seek table footer: frame count, descriptor and magic number.
+const FOOTER_SIZE: u64 = 9;
+
+/// Flag in the seek table descriptor indicating per-frame checksums.
+const CHECKSUM_FLAG: u8 = 0x80;
+
+/// Largest allowed decompressed size for a single frame.
+pub const MAX_FRAME_SIZE: usize = 0x4000_0000;
+
+/// Largest allowed number of frames in a seekable archive.
+pub const MAX_FRAMES: usize = 0x0800_0000;
+
+/// Location of each frame in a seekable archive.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SeekTable {
+    // Offset of the start of each frame, followed by the end of the last one.
+    compressed_offsets: Vec<u64>,
+    decompressed_offsets: Vec<u64>,
+}
+
+impl SeekTable {
+    /// Creates an empty seek table.
+    pub(crate) fn new() -> Self {
+        SeekTable {
+            compressed_offsets: vec![0],
+            decompressed_offsets: vec![0],
+        }
+    }
+
+    /// Reads the seek table at the end of the given seekable archive.
+    ///
+    /// The position of `reader` after this call is unspecified.
+    pub fn from_reader<R: Read + Seek>(reader: &mut R) -> io::Result<Self> {
+        reader.seek(SeekFrom::End(-(FOOTER_SIZE as i64)))?;
+        let mut footer = [0u8; FOOTER_SIZE as usize];
+        reader.read_exact(&mut footer)?;
+
+        if read_u32(&footer[5..]) != SEEKABLE_MAGIC {
+            return Err(invalid_data("missing seekable magic number"));
+        }
+        let num_frames = read_u32(&footer[..4]) as usize;
+        if num_frames > MAX_FRAMES {
+            return Err(invalid_data("too many frames in seek table"));
+        }
+        let descriptor = footer[4];
+        let entry_size = if descriptor & CHECKSUM_FLAG != 0 {
+            12
+        } else {
+            8
+        };
+
+        // The skippable frame header is followed by the entries and the footer.
+        let table_size = (num_frames * entry_size) as u64 + FOOTER_SIZE;
+        reader.seek(SeekFrom::End(-(table_size as i64) - 8))?;
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header)?;
+        if read_u32(&header[..4]) != SEEK_TABLE_FRAME_MAGIC
+            || u64::from(read_u32(&header[4..])) != table_size
+        {
+            return Err(invalid_data("invalid seek table frame header"));
+        }
+
+        let mut entries = vec![0u8; num_frames * entry_size];
+        reader.read_exact(&mut entries)?;
+
+        let mut table = SeekTable::new();
+        for entry in entries.chunks_exact(entry_size) {
+            table.push(
+                u64::from(read_u32(&entry[..4])),
+                u64::from(read_u32(&entry[4..8])),
+            );
+        }
+
+        Ok(table)
+    }
+
+    /// Appends a frame to this table.
+    pub(crate) fn push(
+        &mut self,
+        compressed_size: u64,
+        decompressed_size: u64,
+    ) {
+        let compressed = self.compressed_size() + compressed_size;
+        let decompressed = self.decompressed_size() + decompressed_size;
+        self.compressed_offsets.push(compressed);
+        self.decompressed_offsets.push(decompressed);
+    }
+
+    /// Returns the serialized table, as a skippable frame.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let num_frames = self.num_frames();
+        let table_size = num_frames * 8 + FOOTER_SIZE as usize;
+
+        let mut result = Vec::with_capacity(table_size + 8);
+        result.extend_from_slice(&SEEK_TABLE_FRAME_MAGIC.to_le_bytes());
+        result.extend_from_slice(&(table_size as u32).to_le_bytes());
+        for frame in 0..num_frames {
+            let compressed = self.frame_compressed_size(frame) as u32;
+            let decompressed = self.frame_decompressed_size(frame) as u32;
+            result.extend_from_slice(&compressed.to_le_bytes());
+            result.extend_from_slice(&decompressed.to_le_bytes());
+        }
+        result.extend_from_slice(&(num_frames as u32).to_le_bytes());
+        result.push(0);
+        result.extend_from_slice(&SEEKABLE_MAGIC.to_le_bytes());
+        result
+    }
+
+    /// Returns the number of frames in the archive.
+    pub fn num_frames(&self) -> usize {
+        self.compressed_offsets.len() - 1
+    }
+
+    /// Returns the total size of the frames, excluding the seek table.
+    pub fn compressed_size(&self) -> u64 {
+        *self.compressed_offsets.last().unwrap()
+    }
+
+    /// Returns the total decompressed size of the archive.
+    pub fn decompressed_size(&self) -> u64 {
+        *self.decompressed_offsets.last().unwrap()
+    }
+
+    /// Returns the offset of the given frame in the archive.
+    ///
+    /// # Panics
+    ///
+    /// If `frame >= self.num_frames()`.
+    pub fn frame_compressed_offset(&self, frame: usize) -> u64 {
+        assert!(frame < self.num_frames());
+        self.compressed_offsets[frame]
+    }
+
+    /// Returns the offset of the given frame in the decompressed data.
+    ///
+    /// # Panics
+    ///
+    /// If `frame >= self.num_frames()`.
+    pub fn frame_decompressed_offset(&self, frame: usize) -> u64 {
+        assert!(frame < self.num_frames());
+        self.decompressed_offsets[frame]
+    }
+
+    /// Returns the compressed size of the given frame.
+    ///
+    /// # Panics
+    ///
+    /// If `frame >= self.num_frames()`.
+    pub fn frame_compressed_size(&self, frame: usize) -> u64 {
+        self.compressed_offsets[frame + 1] - self.compressed_offsets[frame]
+    }
+
+    /// Returns the decompressed size of the given frame.
+    ///
+    /// # Panics
+    ///
+    /// If `frame >= self.num_frames()`.
+    pub fn frame_decompressed_size(&self, frame: usize) -> u64 {
+        self.decompressed_offsets[frame + 1] - self.decompressed_offsets[frame]
+    }
+
+    /// Returns the index of the frame containing the given decompressed
+    /// offset.
+    ///
+    /// Returns `None` if `offset` is past the end of the decompressed data.
+    pub fn frame_at_offset(&self, offset: u64) -> Option<usize> {
+        if offset >= self.decompressed_size() {
+            return None;
+        }
+        // Find the last frame starting at or before `offset`.
+        // Empty frames share their offset with the next one, so skip them.
+        let frame = self
+            .decompressed_offsets
+            .partition_point(|&start| start <= offset);
+        Some(frame - 1)
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// An encoder producing a seekable archive.
+///
+/// Input is split into independent frames of at most `frame_size` bytes
+/// (before compression). The seek table is written by [`finish()`].
+///
+/// Don't forget to call [`finish()`] before dropping it!
+///
+/// [`finish()`]: SeekableEncoder::finish
+pub struct SeekableEncoder<'a, W: Write> {
+    writer: zio::Writer<W, SeekableOperation<'a>>,
+}
+
+/// Compression operation cutting its input into frames.
+struct SeekableOperation<'a> {
+    encoder: raw::Encoder<'a>,
+    table: SeekTable,
+
+    max_frame_size: usize,
+    frame_compressed: usize,
+    frame_decompressed: usize,
+
+    finish: FinishState,
+}
+
+enum FinishState {
+    // Still compressing frames.
+    Frames,
+    // Writing out the serialized seek table, starting at the given offset.
+    SeekTable(Vec<u8>, usize),
+}
+
+impl<'a> SeekableOperation<'a> {
+    fn new(encoder: raw::Encoder<'a>, max_frame_size: usize) -> Self {
+        SeekableOperation {
+            encoder,
+            table: SeekTable::new(),
+            max_frame_size,
+            frame_compressed: 0,
+            frame_decompressed: 0,
+            finish: FinishState::Frames,
+        }
+    }
+
+    /// Ends the current frame.
+    ///
+    /// Returns the number of bytes still to write; keep calling until it
+    /// returns `Ok(0)`.
+    fn end_frame<C: WriteBuf + ?Sized>(
+        &mut self,
+        output: &mut OutBuffer<'_, C>,
+    ) -> io::Result<usize> {
+        let before = output.pos();
+        let remaining = self.encoder.finish(output, true)?;
+        self.frame_compressed += output.pos() - before;
+
+        if remaining == 0 {
+            if self.table.num_frames() == MAX_FRAMES {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "too many frames for a seekable archive",
+                ));
+            }
+            self.table.push(
+                self.frame_compressed as u64,
+                self.frame_decompressed as u64,
+            );
+            self.frame_compressed = 0;
+            self.frame_decompressed = 0;
+        }
+
+        Ok(remaining)
+    }
+}
+
+impl Operation for SeekableOperation<'_> {
+    fn run<C: WriteBuf + ?Sized>(
+        &mut self,
+        input: &mut InBuffer<'_>,
+        output: &mut OutBuffer<'_, C>,
+    ) -> io::Result<usize> {
+        if self.frame_decompressed == self.max_frame_size {
+            let remaining = self.end_frame(output)?;
+            if remaining != 0 {
+                return Ok(remaining);
+            }
+        }
+
+        // Only feed what still fits in the current frame.
+        let available = self.max_frame_size - self.frame_decompressed;
+        let end = input.src.len().min(input.pos + available);
+        let mut src = InBuffer {
+            src: &input.src[..end],
+            pos: input.pos,
+        };
+
+        let before = output.pos();
+        let hint = self.encoder.run(&mut src, output)?;
+        self.frame_compressed += output.pos() - before;
+        self.frame_decompressed += src.pos - input.pos;
+        input.set_pos(src.pos);
+
+        Ok(hint)
+    }
+
+    fn flush<C: WriteBuf + ?Sized>(
+        &mut self,
+        output: &mut OutBuffer<'_, C>,
+    ) -> io::Result<usize> {
+        let before = output.pos();
+        let remaining = self.encoder.flush(output)?;
+        self.frame_compressed += output.pos() - before;
+        Ok(remaining)
+    }
+
+    fn finish<C: WriteBuf + ?Sized>(
+        &mut self,
+        output: &mut OutBuffer<'_, C>,
+        _finished_frame: bool,
+    ) -> io::Result<usize> {
+        loop {
+            match self.finish {
+                FinishState::Frames => {
+                    // Only end the last frame if it was actually started.
+                    if self.frame_compressed > 0 || self.frame_decompressed > 0
+                    {
+                        let remaining = self.end_frame(output)?;
+                        if remaining != 0 {
+                            return Ok(remaining);
+                        }
+                    }
+                    self.finish =
+                        FinishState::SeekTable(self.table.to_bytes(), 0);
+                }
+                FinishState::SeekTable(ref table, ref mut offset) => {
+                    let src = &table[*offset..];
+                    let output_pos = output.pos();
+                    let len = src.len().min(output.capacity() - output_pos);
+
+                    // Safe because:
+                    // * `output_pos + len <= output.capacity()`
+                    // * `src` and `output` do not overlap since we have `&mut`
+                    //   to `output`.
+                    unsafe {
+                        std::ptr::copy_nonoverlapping(
+                            src.as_ptr(),
+                            output.as_mut_ptr().add(output_pos),
+                            len,
+                        );
+                        output.set_pos(output_pos + len);
+                    }
+                    *offset += len;
+
+                    return Ok(table.len() - *offset);
+                }
+            }
+        }
+    }
+}
+
+impl<W: Write> SeekableEncoder<'static, W> {
+    /// Creates a new seekable encoder.
+    ///
+    /// * `level`: compression level (1-22). A level of `0` uses zstd's
+    ///   default (currently `3`).
+    /// * `frame_size`: maximum decompressed size of each frame. Smaller
+    ///   frames make seeking cheaper, but reduce the compression ratio.
+    ///   Must be between 1 and [`MAX_FRAME_SIZE`].
+    pub fn new(writer: W, level: i32, frame_size: usize) -> io::Result<Self> {
+        Self::with_encoder(writer, raw::Encoder::new(level)?, frame_size)
+    }
+
+    /// Creates a new seekable encoder, using an existing dictionary.
+    ///
+    /// Every frame is compressed with the dictionary, so it will also be
+    /// needed during decompression.
+    pub fn with_dictionary(
+        writer: W,
+        level: i32,
+        dictionary: &[u8],
+        frame_size: usize,
+    ) -> io::Result<Self> {
+        let encoder = raw::Encoder::with_dictionary(level, dictionary)?;
+        Self::with_encoder(writer, encoder, frame_size)
+    }
+}
+
+impl<'a, W: Write> SeekableEncoder<'a, W> {
+    fn with_encoder(
+        writer: W,
+        encoder: raw::Encoder<'a>,
+        frame_size: usize,
+    ) -> io::Result<Self> {
+        if frame_size == 0 || frame_size > MAX_FRAME_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid frame size for a seekable archive",
+            ));
+        }
+        let operation = SeekableOperation::new(encoder, frame_size);
+        Ok(SeekableEncoder {
+            writer: zio::Writer::new(writer, operation),
+        })
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        self.writer.writer()
+    }
+
+    /// Acquires a mutable reference to the underlying writer.
+    ///
+    /// Note that mutation of the writer may result in surprising results if
+    /// this encoder is continued to be used.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.writer.writer_mut()
+    }
+
+    /// Returns the seek table for the frames completed so far.
+    pub fn seek_table(&self) -> &SeekTable {
+        &self.writer.operation().table
+    }
+
+    /// **Required**: Finishes the last frame and writes the seek table.
+    ///
+    /// This returns the inner writer in case you need it.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.finish()?;
+        Ok(self.writer.into_inner().0)
+    }
+
+    /// Sets the given zstd compression parameter.
+    ///
+    /// It applies to all frames compressed after this call.
+    pub fn set_parameter(
+        &mut self,
+        parameter: zstd_safe::CParameter,
+    ) -> io::Result<()> {
+        self.writer.operation_mut().encoder.set_parameter(parameter)
+    }
+
+    crate::encoder_parameters!();
+}
+
+impl<W: Write> Write for SeekableEncoder<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// A decoder for seekable archives, implementing both `Read` and `Seek`.
+///
+/// Seeking is lazy: the next read only decodes the frame covering the new
+/// position, starting from the beginning of that frame.
+pub struct SeekableDecoder<'a, R> {
+    reader: zio::Reader<BufReader<R>, raw::Decoder<'a>>,
+    table: SeekTable,
+
+    // Position requested by the user.
+    pos: u64,
+    // Position of the next byte the inner decoder will produce.
+    decoded_pos: u64,
+}
+
+impl<R: Read + Seek> SeekableDecoder<'static, R> {
+    /// Creates a new decoder, reading the seek table from `reader`.
+    pub fn new(reader: R) -> io::Result<Self> {
+        Self::with_decoder(reader, raw::Decoder::new()?)
+    }
+
+    /// Creates a new decoder, using an existing dictionary.
+    ///
+    /// The dictionary must be the same as the one used during compression.
+    pub fn with_dictionary(reader: R, dictionary: &[u8]) -> io::Result<Self> {
+        Self::with_decoder(reader, raw::Decoder::with_dictionary(dictionary)?)
+    }
+}
+
+impl<'a, R: Read + Seek> SeekableDecoder<'a, R> {
+    fn with_decoder(
+        mut reader: R,
+        decoder: raw::Decoder<'a>,
+    ) -> io::Result<Self> {
+        let table = SeekTable::from_reader(&mut reader)?;
+        reader.seek(SeekFrom::Start(0))?;
+
+        let buffer_size = zstd_safe::DCtx::in_size();
+        let reader = BufReader::with_capacity(buffer_size, reader);
+
+        Ok(SeekableDecoder {
+            reader: zio::Reader::new(reader, decoder),
+            table,
+            pos: 0,
+            decoded_pos: 0,
+        })
+    }
+
+    /// Returns the seek table read from the archive.
+    pub fn seek_table(&self) -> &SeekTable {
+        &self.table
+    }
+
+    /// Acquire a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        self.reader.reader().get_ref()
+    }
+
+    /// Return the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader.into_inner().into_inner()
+    }
+
+    /// Sets a decompression parameter on the decompression stream.
+    pub fn set_parameter(
+        &mut self,
+        parameter: zstd_safe::DParameter,
+    ) -> io::Result<()> {
+        self.reader.operation_mut().set_parameter(parameter)
+    }
+
+    crate::decoder_parameters!();
+
+    /// Makes sure the next byte out of the inner decoder is at `self.pos`.
+    fn sync_position(&mut self) -> io::Result<()> {
+        let target = match self.table.frame_at_offset(self.pos) {
+            Some(frame) => frame,
+            None => return Ok(()),
+        };
+
+        // We can keep decoding forward if we're already in the right frame.
+        let current = self.table.frame_at_offset(self.decoded_pos);
+        if self.pos < self.decoded_pos || current != Some(target) {
+            let offset = self.table.frame_compressed_offset(target);
+            self.reader.reader_mut().seek(SeekFrom::Start(offset))?;
+            self.reader.operation_mut().reinit()?;
+            self.decoded_pos = self.table.frame_decompressed_offset(target);
+        }
+
+        // Decode and discard until we reach the requested position.
+        let skip = self.pos - self.decoded_pos;
+        let skipped =
+            io::copy(&mut (&mut self.reader).take(skip), &mut io::sink())?;
+        self.decoded_pos += skipped;
+        if skipped != skip {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "seekable archive ended early",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read + Seek> Read for SeekableDecoder<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining =
+            self.table.decompressed_size().saturating_sub(self.pos);
+        if remaining == 0 || buf.is_empty() {
+            return Ok(0);
+        }
+        self.sync_position()?;
+
+        // Never read past the end of the last frame.
+        let len = usize::try_from(remaining).unwrap_or(usize::MAX);
+        let len = buf.len().min(len);
+        let read = self.reader.read(&mut buf[..len])?;
+        if read == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "seekable archive ended early",
+            ));
+        }
+
+        self.pos += read as u64;
+        self.decoded_pos += read as u64;
+        Ok(read)
+    }
+}
+
+impl<R: Read + Seek> Seek for SeekableDecoder<'_, R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(offset) => {
+                self.pos = offset;
+                return Ok(offset);
+            }
+            SeekFrom::End(offset) => (self.table.decompressed_size(), offset),
+            SeekFrom::Current(offset) => (self.pos, offset),
+        };
+
+        let new_pos = if offset >= 0 {
+            base.checked_add(offset as u64)
+        } else {
+            base.checked_sub(offset.unsigned_abs())
+        };
+
+        match new_pos {
+            Some(new_pos) => {
+                self.pos = new_pos;
+                Ok(new_pos)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+fn _assert_traits() {
+    use std::io::Cursor;
+
+    fn _assert_send<T: Send>(_: T) {}
+
+    _assert_send(SeekableEncoder::new(Vec::new(), 1, 1024));
+    _assert_send(SeekableDecoder::new(Cursor::new(Vec::new())));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SeekableDecoder, SeekableEncoder};
+    use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+
+    const TEXT: &[u8] =
+        include_bytes!("../../zstd-safe/zstd-sys/src/bindings_zstd.rs");
+
+    fn encode(data: &[u8], frame_size: usize) -> Vec<u8> {
+        let mut encoder =
+            SeekableEncoder::new(Vec::new(), 1, frame_size).unwrap();
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_cycle() {
+        let compressed = encode(TEXT, 1000);
+
+        // Regular decoders can read seekable archives.
+        assert_eq!(crate::decode_all(&compressed[..]).unwrap(), TEXT);
+
+        let mut decoder =
+            SeekableDecoder::new(Cursor::new(compressed)).unwrap();
+        let table = decoder.seek_table();
+        assert_eq!(table.num_frames(), (TEXT.len() + 999) / 1000);
+        assert_eq!(table.decompressed_size(), TEXT.len() as u64);
+
+        let mut output = Vec::new();
+        decoder.read_to_end(&mut output).unwrap();
+        assert_eq!(output, TEXT);
+    }
+
+    #[test]
+    fn test_seek() {
+        let compressed = encode(TEXT, 1000);
+        let mut decoder =
+            SeekableDecoder::new(Cursor::new(compressed)).unwrap();
+
+        // Jump around: forward across frames, within a frame, and backward.
+        for &offset in &[2500, 2600, 10, 999, 1000, 2600] {
+            decoder.seek(SeekFrom::Start(offset as u64)).unwrap();
+            let mut buffer = [0u8; 300];
+            decoder.read_exact(&mut buffer).unwrap();
+            assert_eq!(&buffer[..], &TEXT[offset..offset + 300]);
+        }
+
+        let end = decoder.seek(SeekFrom::End(-5)).unwrap();
+        assert_eq!(end, TEXT.len() as u64 - 5);
+        let mut buffer = Vec::new();
+        decoder.read_to_end(&mut buffer).unwrap();
+        assert_eq!(&buffer[..], &TEXT[TEXT.len() - 5..]);
+
+        assert!(decoder
+            .seek(SeekFrom::Current(-(TEXT.len() as i64) - 1))
+            .is_err());
+    }
+
+    #[test]
+    fn test_empty() {
+        let compressed = encode(b"", 1000);
+        assert!(crate::decode_all(&compressed[..]).unwrap().is_empty());
+
+        let mut decoder =
+            SeekableDecoder::new(Cursor::new(compressed)).unwrap();
+        assert_eq!(decoder.seek_table().num_frames(), 0);
+        let mut output = Vec::new();
+        decoder.read_to_end(&mut output).unwrap();
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_not_seekable() {
+        let compressed = crate::encode_all(TEXT, 1).unwrap();
+        assert!(SeekableDecoder::new(Cursor::new(compressed)).is_err());
+    }
+}
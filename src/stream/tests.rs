@@ -38,6 +38,115 @@ fn test_concatenated_frames() {
     );
 }
 
+#[cfg(feature = "experimental")]
+#[test]
+fn test_skippable_frames() {
+    use std::io::{BufReader, Read, Write};
+
+    let mut enc = Encoder::new(Vec::new(), 1).unwrap();
+    enc.write_skippable_frame(0, b"header").unwrap();
+    enc.write_all(b"foo").unwrap();
+    enc.write_skippable_frame(7, b"").unwrap();
+    enc.write_all(b"bar").unwrap();
+    let compressed = enc.finish().unwrap();
+
+    // No empty frame should be written before the first skippable frame.
+    assert!(zstd_safe::is_skippable_frame(&compressed));
+    assert_eq!(decode_all(&compressed[..]).unwrap(), b"foobar");
+
+    // Feed the decoder one byte at a time to split frames across reads.
+    let mut frames = Vec::new();
+    let mut dec =
+        Decoder::with_buffer(BufReader::with_capacity(1, &compressed[..]))
+            .unwrap()
+            .on_skippable_frame(6, |magic_variant, data| {
+                frames.push((magic_variant, data.to_vec()))
+            });
+    let mut buf = Vec::new();
+    dec.read_to_end(&mut buf).unwrap();
+    drop(dec);
+
+    assert_eq!(buf, b"foobar");
+    assert_eq!(frames, vec![(0, b"header".to_vec()), (7, Vec::new())]);
+
+    // Frames larger than the limit are refused before being buffered.
+    let mut dec = Decoder::new(&compressed[..])
+        .unwrap()
+        .on_skippable_frame(5, |_, _| panic!("frame should be refused"));
+    assert!(dec.read_to_end(&mut Vec::new()).is_err());
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_skippable_frame_write_error() {
+    use crate::frame::{FrameKind, Frames};
+    use std::io::Write;
+
+    /// Fails the first write after `fail` is set.
+    struct FailOnce {
+        inner: Vec<u8>,
+        fail: bool,
+    }
+
+    impl Write for FailOnce {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if std::mem::take(&mut self.fail) {
+                return Err(io::Error::new(io::ErrorKind::Other, "oops"));
+            }
+            self.inner.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let writer = FailOnce {
+        inner: Vec::new(),
+        fail: false,
+    };
+    let mut enc = Encoder::new(writer, 1).unwrap();
+    enc.write_all(b"foo").unwrap();
+    enc.get_mut().fail = true;
+    assert!(enc.write_skippable_frame(3, b"meta").is_err());
+
+    // Both the end of the first frame and the skippable frame are pending.
+    enc.write_all(b"bar").unwrap();
+    let compressed = enc.finish().unwrap().inner;
+    assert_eq!(decode_all(&compressed[..]).unwrap(), b"foobar");
+
+    let kinds: Vec<FrameKind> = Frames::new(&compressed)
+        .map(|frame| frame.unwrap().kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            FrameKind::Zstd,
+            FrameKind::Skippable { variant: 3 },
+            FrameKind::Zstd
+        ]
+    );
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_estimate_memory() {
+    use super::raw;
+
+    let default = raw::Encoder::estimate_memory(3, None, None).unwrap();
+    let small = raw::Encoder::estimate_memory(3, None, Some(1024)).unwrap();
+    let large_window =
+        raw::Encoder::estimate_memory(3, Some(27), None).unwrap();
+    assert!(small < default);
+    assert!(default < large_window);
+    assert!(raw::Encoder::estimate_memory(3, Some(64), None).is_err());
+
+    let compressed = encode_all(&b"foobar"[..], 3).unwrap();
+    let needed = raw::Decoder::estimate_memory_for_frame(&compressed).unwrap();
+    assert!(needed > 0);
+    assert!(raw::Decoder::estimate_memory_for_frame(b"garbage").is_err());
+}
+
 #[test]
 fn test_flush() {
     use std::io::Write;
@@ -148,11 +257,175 @@ fn test_invalid_frame() {
     // I really hope this data is invalid...
     let data = &[1u8, 2u8, 3u8, 4u8, 5u8];
     let mut dec = Decoder::new(&data[..]).unwrap();
+    let err = dec.read_to_end(&mut Vec::new()).unwrap_err();
     assert_eq!(
-        dec.read_to_end(&mut Vec::new()).err().map(|e| e.kind()),
-        Some(io::ErrorKind::Other),
+        err.kind(),
+        io::ErrorKind::InvalidData,
         "did not encounter expected 'invalid frame' error"
     );
+    assert_eq!(
+        crate::Error::from_io(&err).map(crate::Error::kind),
+        Some(crate::ErrorKind::PrefixUnknown)
+    );
+}
+
+/// Returns two frames of `data`, the second one damaged, and where it starts.
+fn damaged_frames(data: &[u8]) -> (Vec<u8>, usize) {
+    let mut compressor = crate::bulk::Compressor::new(1).unwrap();
+    compressor
+        .set_parameter(zstd_safe::CParameter::ChecksumFlag(true))
+        .unwrap();
+    let mut compressed = compressor.compress(data).unwrap();
+    let second_frame = compressed.len();
+    compressed.extend(compressor.compress(data).unwrap());
+
+    let middle = second_frame + (compressed.len() - second_frame) / 2;
+    for byte in &mut compressed[middle..middle + 16] {
+        *byte = 0xff;
+    }
+    (compressed, second_frame)
+}
+
+#[test]
+fn test_error_position() {
+    use std::io::{Read, Write};
+
+    let data = include_bytes!("../../assets/example.txt");
+    let (compressed, second_frame) = damaged_frames(data);
+
+    let check = |err: io::Error| {
+        let position =
+            crate::Error::from_io(&err).unwrap().position().unwrap();
+        assert_eq!(position.frame_index, 1);
+        assert!(position.input_offset >= second_frame as u64);
+        assert!(position.input_offset < compressed.len() as u64);
+        assert!(position.output_offset >= data.len() as u64);
+        assert!(position.output_offset <= 2 * data.len() as u64);
+        assert!(err.to_string().contains("frame 1"));
+    };
+
+    let mut decoder = Decoder::new(&compressed[..]).unwrap();
+    check(decoder.read_to_end(&mut Vec::new()).unwrap_err());
+
+    let mut decoder = crate::stream::write::Decoder::new(Vec::new()).unwrap();
+    check(
+        decoder
+            .write_all(&compressed)
+            .and_then(|_| decoder.flush())
+            .unwrap_err(),
+    );
+
+    check(crate::bulk::decompress(&compressed, 2 * data.len()).unwrap_err());
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_dict_content_type() {
+    use crate::dict::{DictContentType, DictLoadMethod};
+    use std::io::{Read, Write};
+
+    // A raw prefix that happens to start with the dictionary magic number.
+    let mut prefix = zstd_safe::MAGIC_DICTIONARY.to_le_bytes().to_vec();
+    prefix.extend_from_slice(b"The quick brown fox jumps over the lazy dog.");
+    let input = b"The lazy dog jumps over the quick brown fox.";
+
+    let mut encoder = Encoder::with_dictionary_advanced(
+        Vec::new(),
+        3,
+        &prefix,
+        DictLoadMethod::ByRef,
+        DictContentType::RawContent,
+    )
+    .unwrap();
+    encoder.write_all(input).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let mut decoder = Decoder::with_dictionary_advanced(
+        &compressed[..],
+        &prefix,
+        DictLoadMethod::ByRef,
+        DictContentType::RawContent,
+    )
+    .unwrap();
+    let mut output = Vec::new();
+    decoder.read_to_end(&mut output).unwrap();
+    assert_eq!(output, input);
+
+    let mut encoder = Encoder::with_ref_prefix_advanced(
+        Vec::new(),
+        3,
+        &prefix,
+        DictContentType::RawContent,
+    )
+    .unwrap();
+    encoder.write_all(input).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let mut decoder = Decoder::with_ref_prefix_advanced(
+        &compressed[..],
+        &prefix,
+        DictContentType::RawContent,
+    )
+    .unwrap();
+    let mut output = Vec::new();
+    decoder.read_to_end(&mut output).unwrap();
+    assert_eq!(output, input);
+
+    // Auto-detection mistakes the prefix for a full dictionary.
+    assert!(Decoder::with_dictionary_advanced(
+        &compressed[..],
+        &prefix,
+        DictLoadMethod::ByRef,
+        DictContentType::Auto,
+    )
+    .is_err());
+}
+
+#[cfg(all(feature = "experimental", feature = "zstdmt"))]
+#[test]
+fn test_thread_pool() {
+    use std::io::{Read, Write};
+
+    let input: Vec<u8> = (0..1_000_000u32)
+        .flat_map(|i| (i % 1000).to_le_bytes())
+        .collect();
+    let pool = crate::ThreadPool::new(2).unwrap();
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let mut encoder =
+                Encoder::with_thread_pool(Vec::new(), 1, &pool).unwrap();
+            let input = input.clone();
+            std::thread::spawn(move || {
+                assert_eq!(encoder.get_nb_workers().unwrap(), 2);
+                encoder.write_all(&input).unwrap();
+                encoder.finish().unwrap()
+            })
+        })
+        .collect();
+    // Encoders keep the pool alive.
+    drop(pool);
+
+    for handle in handles {
+        assert_eq!(decode_all(&handle.join().unwrap()[..]).unwrap(), input);
+    }
+
+    let pool = crate::ThreadPool::new(1).unwrap();
+    let mut encoder =
+        super::read::Encoder::with_thread_pool(&input[..], 3, &pool).unwrap();
+    let mut compressed = Vec::new();
+    encoder.read_to_end(&mut compressed).unwrap();
+    assert_eq!(decode_all(&compressed[..]).unwrap(), input);
+
+    // Borrowed contexts keep the pool alive too.
+    let mut context = zstd_safe::CCtx::create();
+    super::raw::Encoder::with_context(&mut context)
+        .set_thread_pool(&pool)
+        .unwrap();
+    drop(pool);
+    let mut compressed = Vec::with_capacity(zstd_safe::compress_bound(1000));
+    context.compress2(&mut compressed, &input[..1000]).unwrap();
+    assert_eq!(decode_all(&compressed[..]).unwrap(), &input[..1000]);
 }
 
 #[test]
@@ -276,3 +549,30 @@ fn test_finish_empty_encoder() {
     enc.write_all(b"this should not work").unwrap_err();
     enc.finish().unwrap();
 }
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_cctx_params() {
+    use std::io::{Read, Write};
+    use zstd_safe::{CCtxParams, CParameter};
+
+    let mut params = CCtxParams::create();
+    params.init(5).unwrap();
+    params
+        .set_parameter(CParameter::ChecksumFlag(true))
+        .unwrap();
+
+    let mut enc = Encoder::with_cctx_params(Vec::new(), &params).unwrap();
+    enc.write_all(b"foobar").unwrap();
+    let written = enc.finish().unwrap();
+
+    let mut enc =
+        super::read::Encoder::with_cctx_params(&b"foobar"[..], &params)
+            .unwrap();
+    let mut read = Vec::new();
+    enc.read_to_end(&mut read).unwrap();
+
+    assert_eq!(written, read);
+    assert!(zstd_safe::get_frame_header(&written).unwrap().checksum_flag);
+    assert_eq!(decode_all(&written[..]).unwrap(), b"foobar");
+}
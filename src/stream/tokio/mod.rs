@@ -0,0 +1,24 @@
+//! Compress and decompress Zstd streams with tokio's async I/O traits.
+//!
+//! The types in this module mirror those in [`stream::read`] and
+//! [`stream::write`], implementing [`AsyncRead`] and [`AsyncWrite`]
+//! instead of `Read` and `Write`.
+//!
+//! The wrapped reader or writer must be `Unpin`. Others can be used through
+//! `Box::pin`.
+//!
+//! [`stream::read`]: crate::stream::read
+//! [`stream::write`]: crate::stream::write
+//! [`AsyncRead`]: ::tokio::io::AsyncRead
+//! [`AsyncWrite`]: ::tokio::io::AsyncWrite
+
+pub mod read;
+pub mod write;
+
+mod zio;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::read::Decoder;
+pub use self::write::Encoder;
@@ -0,0 +1,188 @@
+//! Implement pull-based [`AsyncRead`] for both compressing and decompressing.
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use ::tokio::io::{AsyncBufRead, AsyncRead, BufReader, ReadBuf};
+
+use super::zio;
+use crate::dict::{DecoderDictionary, EncoderDictionary};
+use crate::stream::raw;
+
+/// A decoder that decompress input data from another `AsyncRead`.
+pub struct Decoder<'a, R> {
+    reader: zio::Reader<R, raw::Decoder<'a>>,
+}
+
+/// An encoder that compress input data from another `AsyncRead`.
+pub struct Encoder<'a, R> {
+    reader: zio::Reader<R, raw::Encoder<'a>>,
+}
+
+impl<R: AsyncRead> Decoder<'static, BufReader<R>> {
+    /// Creates a new decoder.
+    pub fn new(reader: R) -> io::Result<Self> {
+        let buffer_size = zstd_safe::DCtx::in_size();
+
+        Self::with_buffer(BufReader::with_capacity(buffer_size, reader))
+    }
+}
+
+impl<R: AsyncBufRead> Decoder<'static, R> {
+    /// Creates a new decoder around an `AsyncBufRead`.
+    pub fn with_buffer(reader: R) -> io::Result<Self> {
+        Self::with_dictionary(reader, &[])
+    }
+
+    /// Creates a new decoder, using an existing dictionary.
+    ///
+    /// The dictionary must be the same as the one used during compression.
+    pub fn with_dictionary(reader: R, dictionary: &[u8]) -> io::Result<Self> {
+        let decoder = raw::Decoder::with_dictionary(dictionary)?;
+        let reader = zio::Reader::new(reader, decoder);
+
+        Ok(Decoder { reader })
+    }
+}
+
+impl<'a, R: AsyncBufRead> Decoder<'a, R> {
+    /// Sets this `Decoder` to stop after the first frame.
+    ///
+    /// By default, it keeps concatenating frames until EOF is reached.
+    #[must_use]
+    pub fn single_frame(mut self) -> Self {
+        self.reader.set_single_frame();
+        self
+    }
+
+    /// Creates a new decoder, using an existing `DecoderDictionary`.
+    ///
+    /// The dictionary must be the same as the one used during compression.
+    pub fn with_prepared_dictionary<'b>(
+        reader: R,
+        dictionary: &DecoderDictionary<'b>,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let decoder = raw::Decoder::with_prepared_dictionary(dictionary)?;
+        let reader = zio::Reader::new(reader, decoder);
+
+        Ok(Decoder { reader })
+    }
+
+    /// Acquire a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        self.reader.reader()
+    }
+
+    /// Acquire a mutable reference to the underlying reader.
+    ///
+    /// Note that mutation of the reader may result in surprising results if
+    /// this decoder is continued to be used.
+    pub fn get_mut(&mut self) -> &mut R {
+        self.reader.reader_mut()
+    }
+
+    /// Return the inner `AsyncBufRead`.
+    pub fn into_inner(self) -> R {
+        self.reader.into_inner()
+    }
+
+    crate::decoder_common!(reader);
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncRead for Decoder<'_, R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().reader).poll_read(cx, buf)
+    }
+}
+
+impl<R: AsyncRead> Encoder<'static, BufReader<R>> {
+    /// Creates a new encoder.
+    pub fn new(reader: R, level: i32) -> io::Result<Self> {
+        let buffer_size = zstd_safe::CCtx::in_size();
+
+        Self::with_buffer(BufReader::with_capacity(buffer_size, reader), level)
+    }
+}
+
+impl<R: AsyncBufRead> Encoder<'static, R> {
+    /// Creates a new encoder around an `AsyncBufRead`.
+    pub fn with_buffer(reader: R, level: i32) -> io::Result<Self> {
+        Self::with_dictionary(reader, level, &[])
+    }
+
+    /// Creates a new encoder, using an existing dictionary.
+    ///
+    /// The dictionary must be the same as the one used during decompression.
+    pub fn with_dictionary(
+        reader: R,
+        level: i32,
+        dictionary: &[u8],
+    ) -> io::Result<Self> {
+        let encoder = raw::Encoder::with_dictionary(level, dictionary)?;
+        let reader = zio::Reader::new(reader, encoder);
+
+        Ok(Encoder { reader })
+    }
+}
+
+impl<'a, R: AsyncBufRead> Encoder<'a, R> {
+    /// Creates a new encoder, using an existing `EncoderDictionary`.
+    ///
+    /// The dictionary must be the same as the one used during decompression.
+    pub fn with_prepared_dictionary<'b>(
+        reader: R,
+        dictionary: &EncoderDictionary<'b>,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let encoder = raw::Encoder::with_prepared_dictionary(dictionary)?;
+        let reader = zio::Reader::new(reader, encoder);
+
+        Ok(Encoder { reader })
+    }
+
+    /// Acquire a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        self.reader.reader()
+    }
+
+    /// Acquire a mutable reference to the underlying reader.
+    ///
+    /// Note that mutation of the reader may result in surprising results if
+    /// this encoder is continued to be used.
+    pub fn get_mut(&mut self) -> &mut R {
+        self.reader.reader_mut()
+    }
+
+    /// Return the inner `AsyncBufRead`.
+    pub fn into_inner(self) -> R {
+        self.reader.into_inner()
+    }
+
+    crate::encoder_common!(reader);
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncRead for Encoder<'_, R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().reader).poll_read(cx, buf)
+    }
+}
+
+fn _assert_traits() {
+    fn _assert_send<T: Send>(_: T) {}
+
+    _assert_send(Decoder::new(&b""[..]));
+    _assert_send(Encoder::new(&b""[..], 1));
+}
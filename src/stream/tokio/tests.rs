@@ -0,0 +1,158 @@
+use super::{read, write};
+use std::future::Future;
+use std::io::{self, Read};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use ::tokio::io::{
+    AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf,
+};
+
+const TEXT: &[u8] = include_bytes!("../../../assets/example.txt");
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    ::tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+/// Wraps a reader or writer, returning `Pending` every other call and only
+/// moving a few bytes at a time.
+struct Stutter<T> {
+    inner: T,
+    pending: bool,
+}
+
+impl<T: Unpin> Stutter<T> {
+    fn new(inner: T) -> Self {
+        Stutter {
+            inner,
+            pending: false,
+        }
+    }
+
+    fn stutter(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        self.pending = !self.pending;
+        if self.pending {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for Stutter<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.stutter(cx));
+        let mut chunk = [0u8; 7];
+        let len = buf.remaining().min(chunk.len());
+        let mut chunk = ReadBuf::new(&mut chunk[..len]);
+        ready!(Pin::new(&mut this.inner).poll_read(cx, &mut chunk))?;
+        buf.put_slice(chunk.filled());
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for Stutter<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.stutter(cx));
+        let len = buf.len().min(7);
+        Pin::new(&mut this.inner).poll_write(cx, &buf[..len])
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.stutter(cx));
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.stutter(cx));
+        Pin::new(&mut this.inner).poll_shutdown(cx)
+    }
+}
+
+#[test]
+fn test_cycle() {
+    block_on(async {
+        let mut encoder =
+            write::Encoder::new(Stutter::new(Vec::new()), 1).unwrap();
+        encoder.write_all(TEXT).await.unwrap();
+        encoder.shutdown().await.unwrap();
+        let compressed = encoder.into_inner().inner;
+        assert_eq!(crate::decode_all(&compressed[..]).unwrap(), TEXT);
+
+        let mut decoder =
+            read::Decoder::new(Stutter::new(&compressed[..])).unwrap();
+        let mut output = Vec::new();
+        decoder.read_to_end(&mut output).await.unwrap();
+        assert_eq!(output, TEXT);
+    });
+}
+
+#[test]
+fn test_read_encoder() {
+    block_on(async {
+        let mut encoder = read::Encoder::new(Stutter::new(TEXT), 3).unwrap();
+        let mut compressed = Vec::new();
+        encoder.read_to_end(&mut compressed).await.unwrap();
+
+        let mut decoder =
+            write::Decoder::new(Stutter::new(Vec::new())).unwrap();
+        decoder.write_all(&compressed).await.unwrap();
+        decoder.shutdown().await.unwrap();
+        assert_eq!(decoder.into_inner().inner, TEXT);
+    });
+}
+
+#[test]
+fn test_flush() {
+    block_on(async {
+        let mut encoder = write::Encoder::new(Vec::new(), 1).unwrap();
+        encoder.write_all(&TEXT[..100]).await.unwrap();
+        encoder.flush().await.unwrap();
+
+        // Flushed data can be decoded before the end of the frame.
+        let mut decoder =
+            crate::stream::read::Decoder::new(&encoder.get_ref()[..]).unwrap();
+        let mut output = [0u8; 100];
+        decoder.read_exact(&mut output).unwrap();
+        assert_eq!(&output[..], &TEXT[..100]);
+    });
+}
+
+#[test]
+fn test_incomplete_frame() {
+    block_on(async {
+        let compressed = crate::encode_all(TEXT, 1).unwrap();
+        let truncated = &compressed[..compressed.len() - 1];
+
+        let mut decoder = write::Decoder::new(Vec::new()).unwrap();
+        decoder.write_all(truncated).await.unwrap();
+        let err = decoder.shutdown().await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut decoder = read::Decoder::new(truncated).unwrap();
+        let err = decoder.read_to_end(&mut Vec::new()).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    });
+}
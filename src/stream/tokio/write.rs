@@ -0,0 +1,209 @@
+//! Implement push-based [`AsyncWrite`] for both compressing and decompressing.
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use ::tokio::io::AsyncWrite;
+
+use super::zio;
+use crate::dict::{DecoderDictionary, EncoderDictionary};
+use crate::stream::raw;
+
+/// An encoder that compress and forward data to another `AsyncWrite`.
+///
+/// Don't forget to call [`shutdown()`] before dropping it: this writes the
+/// end of the frame, then shuts down the inner writer.
+///
+/// [`shutdown()`]: ::tokio::io::AsyncWriteExt::shutdown
+pub struct Encoder<'a, W> {
+    // output writer (compressed data)
+    writer: zio::Writer<W, raw::Encoder<'a>>,
+}
+
+/// A decoder that decompress and forward data to another `AsyncWrite`.
+///
+/// [`shutdown()`] checks that the last frame was complete, then shuts down
+/// the inner writer.
+///
+/// [`shutdown()`]: ::tokio::io::AsyncWriteExt::shutdown
+pub struct Decoder<'a, W> {
+    // output writer (decompressed data)
+    writer: zio::Writer<W, raw::Decoder<'a>>,
+}
+
+impl<W: AsyncWrite> Encoder<'static, W> {
+    /// Creates a new encoder.
+    ///
+    /// `level`: compression level (1-22).
+    ///
+    /// A level of `0` uses zstd's default (currently `3`).
+    pub fn new(writer: W, level: i32) -> io::Result<Self> {
+        Self::with_dictionary(writer, level, &[])
+    }
+
+    /// Creates a new encoder, using an existing dictionary.
+    ///
+    /// The dictionary must be the same as the one used during decompression.
+    pub fn with_dictionary(
+        writer: W,
+        level: i32,
+        dictionary: &[u8],
+    ) -> io::Result<Self> {
+        let encoder = raw::Encoder::with_dictionary(level, dictionary)?;
+        let writer = zio::Writer::new(writer, encoder);
+
+        Ok(Encoder { writer })
+    }
+}
+
+impl<'a, W: AsyncWrite> Encoder<'a, W> {
+    /// Creates a new encoder, using an existing `EncoderDictionary`.
+    ///
+    /// The dictionary must be the same as the one used during decompression.
+    pub fn with_prepared_dictionary<'b>(
+        writer: W,
+        dictionary: &EncoderDictionary<'b>,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let encoder = raw::Encoder::with_prepared_dictionary(dictionary)?;
+        let writer = zio::Writer::new(writer, encoder);
+
+        Ok(Encoder { writer })
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        self.writer.writer()
+    }
+
+    /// Acquires a mutable reference to the underlying writer.
+    ///
+    /// Note that mutation of the writer may result in surprising results if
+    /// this encoder is continued to be used.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.writer.writer_mut()
+    }
+
+    /// Returns the inner writer.
+    ///
+    /// The output is incomplete unless [`shutdown()`] was called first.
+    ///
+    /// [`shutdown()`]: ::tokio::io::AsyncWriteExt::shutdown
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+
+    crate::encoder_common!(writer);
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for Encoder<'_, W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().writer).poll_write(cx, buf)
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().writer).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().writer).poll_shutdown(cx)
+    }
+}
+
+impl<W: AsyncWrite> Decoder<'static, W> {
+    /// Creates a new decoder.
+    pub fn new(writer: W) -> io::Result<Self> {
+        Self::with_dictionary(writer, &[])
+    }
+
+    /// Creates a new decoder, using an existing dictionary.
+    ///
+    /// The dictionary must be the same as the one used during compression.
+    pub fn with_dictionary(writer: W, dictionary: &[u8]) -> io::Result<Self> {
+        let decoder = raw::Decoder::with_dictionary(dictionary)?;
+        let writer = zio::Writer::new(writer, decoder);
+
+        Ok(Decoder { writer })
+    }
+}
+
+impl<'a, W: AsyncWrite> Decoder<'a, W> {
+    /// Creates a new decoder, using an existing `DecoderDictionary`.
+    ///
+    /// The dictionary must be the same as the one used during compression.
+    pub fn with_prepared_dictionary<'b>(
+        writer: W,
+        dictionary: &DecoderDictionary<'b>,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let decoder = raw::Decoder::with_prepared_dictionary(dictionary)?;
+        let writer = zio::Writer::new(writer, decoder);
+
+        Ok(Decoder { writer })
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        self.writer.writer()
+    }
+
+    /// Acquires a mutable reference to the underlying writer.
+    ///
+    /// Note that mutation of the writer may result in surprising results if
+    /// this decoder is continued to be used.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.writer.writer_mut()
+    }
+
+    /// Returns the inner writer.
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+
+    crate::decoder_common!(writer);
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for Decoder<'_, W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().writer).poll_write(cx, buf)
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().writer).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().writer).poll_shutdown(cx)
+    }
+}
+
+fn _assert_traits() {
+    fn _assert_send<T: Send>(_: T) {}
+
+    _assert_send(Decoder::new(Vec::new()));
+    _assert_send(Encoder::new(Vec::new(), 1));
+}
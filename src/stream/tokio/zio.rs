@@ -0,0 +1,260 @@
+//! Async equivalents of [`zio::Reader`] and [`zio::Writer`].
+//!
+//! They share the state driving the [`Operation`] with the blocking
+//! versions, only waiting on the inner stream instead of blocking.
+//!
+//! [`zio::Reader`]: crate::stream::zio::Reader
+//! [`zio::Writer`]: crate::stream::zio::Writer
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use ::tokio::io::{AsyncBufRead, AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::stream::raw::Operation;
+use crate::stream::zio::{ReaderCore, WriterCore};
+
+// [ reader -> zstd ] -> output
+/// Implements the [`AsyncRead`] API around an [`Operation`].
+///
+/// It pulls input data from a wrapped `AsyncBufRead`.
+pub struct Reader<R, D> {
+    reader: R,
+    core: ReaderCore<D>,
+}
+
+impl<R, D> Reader<R, D> {
+    /// Creates a new `Reader`.
+    pub fn new(reader: R, operation: D) -> Self {
+        Reader {
+            reader,
+            core: ReaderCore::new(operation),
+        }
+    }
+
+    /// Sets `self` to stop after the first decoded frame.
+    pub fn set_single_frame(&mut self) {
+        self.core.set_single_frame();
+    }
+
+    /// Returns a reference to the underlying operation.
+    #[cfg(feature = "experimental")]
+    pub fn operation(&self) -> &D {
+        self.core.operation()
+    }
+
+    /// Returns a mutable reference to the underlying operation.
+    pub fn operation_mut(&mut self) -> &mut D {
+        self.core.operation_mut()
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn reader_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R, D> Reader<R, D>
+where
+    R: AsyncBufRead + Unpin,
+    D: Operation,
+{
+    /// Fills `buf` with some output, and returns how much was written.
+    fn poll_read_slice(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        // Keep trying until _something_ has been written.
+        // The first run doesn't wait for new input.
+        let mut first = true;
+        loop {
+            let input = if first || !self.core.wants_input() {
+                None
+            } else {
+                Some(ready!(Pin::new(&mut self.reader).poll_fill_buf(cx))?)
+            };
+            first = false;
+
+            let (bytes_read, written) = self.core.step(input, buf)?;
+            Pin::new(&mut self.reader).consume(bytes_read);
+
+            if let Some(written) = written {
+                return Poll::Ready(Ok(written));
+            }
+        }
+    }
+}
+
+impl<R, D> AsyncRead for Reader<R, D>
+where
+    R: AsyncBufRead + Unpin,
+    D: Operation + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+        let written = ready!(self
+            .get_mut()
+            .poll_read_slice(cx, buf.initialize_unfilled()))?;
+        buf.advance(written);
+        Poll::Ready(Ok(()))
+    }
+}
+
+// input -> [ zstd -> buffer -> writer ]
+/// Implements the [`AsyncWrite`] API around an [`Operation`].
+///
+/// It forwards the output to a wrapped `AsyncWrite`.
+pub struct Writer<W, D> {
+    writer: W,
+    core: WriterCore<D>,
+}
+
+impl<W, D> Writer<W, D> {
+    /// Creates a new `Writer`.
+    pub fn new(writer: W, operation: D) -> Self {
+        Writer {
+            writer,
+            core: WriterCore::new(operation),
+        }
+    }
+
+    /// Gives a reference to the inner writer.
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    /// Gives a mutable reference to the inner writer.
+    pub fn writer_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Gives a reference to the inner operation.
+    #[cfg(feature = "experimental")]
+    pub fn operation(&self) -> &D {
+        self.core.operation()
+    }
+
+    /// Gives a mutable reference to the inner operation.
+    pub fn operation_mut(&mut self) -> &mut D {
+        self.core.operation_mut()
+    }
+
+    /// Returns the inner writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W, D> Writer<W, D>
+where
+    W: AsyncWrite + Unpin,
+    D: Operation,
+{
+    /// Attempt to write the buffered output to the wrapped writer.
+    ///
+    /// Returns `Ok(())` once all the buffer has been written.
+    fn poll_write_from_offset(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        while !self.core.pending().is_empty() {
+            match ready!(
+                Pin::new(&mut self.writer).poll_write(cx, self.core.pending())
+            ) {
+                Ok(0) => {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "writer will not accept any more data",
+                    )))
+                }
+                Ok(n) => self.core.advance(n),
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => (),
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Writes out everything left in the operation.
+    fn poll_finish(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        loop {
+            ready!(self.poll_write_from_offset(cx))?;
+
+            if self.core.is_finished() {
+                return Poll::Ready(Ok(()));
+            }
+
+            self.core.finish_step()?;
+        }
+    }
+}
+
+impl<W, D> AsyncWrite for Writer<W, D>
+where
+    W: AsyncWrite + Unpin,
+    D: Operation + Unpin,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        this.core.check_not_finished()?;
+        // Keep trying until _something_ has been consumed.
+        loop {
+            // First, write any pending data from the buffer.
+            ready!(this.poll_write_from_offset(cx))?;
+
+            if let Some(bytes_read) = this.core.write_step(buf)? {
+                return Poll::Ready(Ok(bytes_read));
+            }
+        }
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let mut finished = this.core.is_finished();
+        loop {
+            ready!(this.poll_write_from_offset(cx))?;
+
+            if finished {
+                break;
+            }
+
+            finished = this.core.flush_step()?;
+        }
+
+        Pin::new(&mut this.writer).poll_flush(cx)
+    }
+
+    /// Ends the stream, then shuts down the inner writer.
+    fn poll_shutdown(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_finish(cx))?;
+        Pin::new(&mut this.writer).poll_shutdown(cx)
+    }
+}
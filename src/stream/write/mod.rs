@@ -1,4 +1,6 @@
 //! Implement push-based [`Write`] trait for both compressing and decompressing.
+#[cfg(feature = "experimental")]
+use std::alloc::GlobalAlloc;
 use std::io::{self, Write};
 
 use zstd_safe;
@@ -190,6 +192,34 @@ impl<W: Write> Encoder<'static, W> {
         let writer = zio::Writer::new(writer, encoder);
         Ok(Encoder { writer })
     }
+
+    /// Creates a new encoder, configured with the given parameters.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_cctx_params(
+        writer: W,
+        params: &zstd_safe::CCtxParams,
+    ) -> io::Result<Self> {
+        let encoder = raw::Encoder::with_cctx_params(params)?;
+        let writer = zio::Writer::new(writer, encoder);
+        Ok(Encoder { writer })
+    }
+
+    /// Creates a new encoder, using the threads of a shared pool.
+    #[cfg(all(feature = "experimental", feature = "zstdmt"))]
+    #[cfg_attr(
+        feature = "doc-cfg",
+        doc(cfg(all(feature = "experimental", feature = "zstdmt")))
+    )]
+    pub fn with_thread_pool(
+        writer: W,
+        level: i32,
+        pool: &crate::ThreadPool,
+    ) -> io::Result<Self> {
+        let encoder = raw::Encoder::with_thread_pool(level, pool)?;
+        let writer = zio::Writer::new(writer, encoder);
+        Ok(Encoder { writer })
+    }
 }
 
 impl<'a, W: Write> Encoder<'a, W> {
@@ -206,6 +236,19 @@ impl<'a, W: Write> Encoder<'a, W> {
         }
     }
 
+    /// Creates a new encoder using `allocator` for all its memory.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_allocator<A: GlobalAlloc + Sync>(
+        writer: W,
+        level: i32,
+        allocator: &'a A,
+    ) -> io::Result<Self> {
+        let encoder = raw::Encoder::with_allocator(level, allocator)?;
+        let writer = zio::Writer::new(writer, encoder);
+        Ok(Encoder { writer })
+    }
+
     /// Creates a new encoder, using an existing prepared `EncoderDictionary`.
     ///
     /// (Provides better compression ratio for small files,
@@ -236,6 +279,51 @@ impl<'a, W: Write> Encoder<'a, W> {
         Ok(Encoder { writer })
     }
 
+    /// Creates a new encoder, using a dictionary loaded and interpreted as
+    /// specified.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_dictionary_advanced<'b>(
+        writer: W,
+        level: i32,
+        dictionary: &'b [u8],
+        load_method: raw::DictLoadMethod,
+        content_type: raw::DictContentType,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let encoder = raw::Encoder::with_dictionary_advanced(
+            level,
+            dictionary,
+            load_method,
+            content_type,
+        )?;
+        let writer = zio::Writer::new(writer, encoder);
+        Ok(Encoder { writer })
+    }
+
+    /// Creates a new encoder, using a ref prefix interpreted as specified.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_ref_prefix_advanced<'b>(
+        writer: W,
+        level: i32,
+        ref_prefix: &'b [u8],
+        content_type: raw::DictContentType,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let encoder = raw::Encoder::with_ref_prefix_advanced(
+            level,
+            ref_prefix,
+            content_type,
+        )?;
+        let writer = zio::Writer::new(writer, encoder);
+        Ok(Encoder { writer })
+    }
+
     /// Returns a wrapper around `self` that will finish the stream on drop.
     pub fn auto_finish(self) -> AutoFinishEncoder<'a, W> {
         AutoFinishEncoder {
@@ -315,6 +403,36 @@ impl<'a, W: Write> Encoder<'a, W> {
         zstd_safe::CCtx::in_size()
     }
 
+    /// Returns the progress of the current frame.
+    ///
+    /// This is mostly useful with multithreaded compression, to know how much
+    /// input is still waiting to be compressed.
+    ///
+    /// Note that `flushed` counts data that left the zstd context, some of
+    /// which may still be buffered by this encoder.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn progress(&self) -> zstd_safe::FrameProgression {
+        self.writer.operation().frame_progression()
+    }
+
+    /// Writes a skippable frame holding `data`.
+    ///
+    /// Skippable frames can hold arbitrary metadata, and are ignored by
+    /// regular decoders. `magic_variant` must be in the `0..=15` range.
+    ///
+    /// If some data was written since the last frame, that frame is ended
+    /// first. Data written afterward will go to a new frame.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn write_skippable_frame(
+        &mut self,
+        magic_variant: u32,
+        data: &[u8],
+    ) -> io::Result<()> {
+        self.writer.write_skippable_frame(magic_variant, data)
+    }
+
     crate::encoder_common!(writer);
 }
 
@@ -346,6 +464,18 @@ impl<W: Write> Decoder<'static, W> {
 }
 
 impl<'a, W: Write> Decoder<'a, W> {
+    /// Creates a new decoder using `allocator` for all its memory.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_allocator<A: GlobalAlloc + Sync>(
+        writer: W,
+        allocator: &'a A,
+    ) -> io::Result<Self> {
+        let decoder = raw::Decoder::with_allocator(allocator)?;
+        let writer = zio::Writer::new(writer, decoder);
+        Ok(Decoder { writer })
+    }
+
     /// Creates a new decoder, using an existing prepared `DecoderDictionary`.
     ///
     /// (Provides better compression ratio for small files,
@@ -362,6 +492,28 @@ impl<'a, W: Write> Decoder<'a, W> {
         Ok(Decoder { writer })
     }
 
+    /// Creates a new decoder, using a dictionary loaded and interpreted as
+    /// specified.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn with_dictionary_advanced<'b>(
+        writer: W,
+        dictionary: &'b [u8],
+        load_method: raw::DictLoadMethod,
+        content_type: raw::DictContentType,
+    ) -> io::Result<Self>
+    where
+        'b: 'a,
+    {
+        let decoder = raw::Decoder::with_dictionary_advanced(
+            dictionary,
+            load_method,
+            content_type,
+        )?;
+        let writer = zio::Writer::new(writer, decoder);
+        Ok(Decoder { writer })
+    }
+
     /// Acquires a reference to the underlying writer.
     pub fn get_ref(&self) -> &W {
         self.writer.writer()
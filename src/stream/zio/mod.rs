@@ -4,4 +4,8 @@ mod reader;
 mod writer;
 
 pub use self::reader::Reader;
+#[cfg(feature = "async-tokio")]
+pub(crate) use self::reader::ReaderCore;
 pub use self::writer::Writer;
+#[cfg(feature = "async-tokio")]
+pub(crate) use self::writer::WriterCore;
@@ -1,5 +1,6 @@
 use std::io::{self, BufRead, Read};
 
+use crate::error::{locate, Position};
 use crate::stream::raw::{InBuffer, Operation, OutBuffer};
 
 // [ reader -> zstd ] -> output
@@ -11,12 +12,23 @@ use crate::stream::raw::{InBuffer, Operation, OutBuffer};
 /// input data from a wrapped `Read`.
 pub struct Reader<R, D> {
     reader: R,
+    core: ReaderCore<D>,
+}
+
+/// The state of a [`Reader`], without the inner reader.
+///
+/// This drives the operation one step at a time, so blocking and async
+/// readers can share it.
+pub(crate) struct ReaderCore<D> {
     operation: D,
 
     state: State,
 
     single_frame: bool,
     finished_frame: bool,
+
+    // Bytes consumed and produced so far, used to locate errors.
+    position: Position,
 }
 
 enum State {
@@ -28,6 +40,124 @@ enum State {
     Finished,
 }
 
+impl<D> ReaderCore<D> {
+    pub(crate) fn new(operation: D) -> Self {
+        ReaderCore {
+            operation,
+            state: State::Reading,
+            single_frame: false,
+            finished_frame: false,
+            position: Position::default(),
+        }
+    }
+
+    /// Sets `self` to stop after the first decoded frame.
+    pub(crate) fn set_single_frame(&mut self) {
+        self.single_frame = true;
+    }
+
+    pub(crate) fn operation(&self) -> &D {
+        &self.operation
+    }
+
+    pub(crate) fn operation_mut(&mut self) -> &mut D {
+        &mut self.operation
+    }
+
+    /// Returns `true` if the next step needs input from the inner reader.
+    pub(crate) fn wants_input(&self) -> bool {
+        matches!(self.state, State::Reading)
+    }
+
+    /// Runs one step of the operation, writing into `output`.
+    ///
+    /// `input` is the content of the inner reader's buffer, empty at the end
+    /// of the input, or `None` to run without new input.
+    ///
+    /// Returns the number of bytes consumed from `input`, and, once the read
+    /// call should return, the number of bytes written to `output`.
+    pub(crate) fn step(
+        &mut self,
+        input: Option<&[u8]>,
+        output: &mut [u8],
+    ) -> io::Result<(usize, Option<usize>)>
+    where
+        D: Operation,
+    {
+        match self.state {
+            State::Reading => {
+                // It's possible we don't have any new data to read.
+                // (In this case we may still have zstd's own buffer to clear.)
+                let input = match input {
+                    Some([]) => {
+                        self.state = State::PastEof;
+                        return self.step(None, output);
+                    }
+                    Some(input) => input,
+                    None => b"",
+                };
+
+                let mut src = InBuffer::around(input);
+                let mut dst = OutBuffer::around(output);
+
+                // We don't want empty input (from the first run) to cause a
+                // frame re-initialization.
+                if self.finished_frame && !input.is_empty() {
+                    self.operation.reinit()?;
+                    self.finished_frame = false;
+                }
+
+                // Phase 1: feed input to the operation
+                let hint = match self.operation.run(&mut src, &mut dst) {
+                    Ok(hint) => hint,
+                    Err(e) => {
+                        let mut position = self.position;
+                        position.advance(src.pos(), dst.pos());
+                        return Err(locate(e, position));
+                    }
+                };
+                self.position.advance(src.pos(), dst.pos());
+
+                if hint == 0 {
+                    // In practice this only happens when decoding, when we
+                    // just finished reading a frame.
+                    self.finished_frame = true;
+                    self.position.frame_index += 1;
+                    if self.single_frame {
+                        self.state = State::Finished;
+                    }
+                }
+
+                // If nothing was written, we need more data.
+                let written = Some(dst.pos()).filter(|&n| n > 0);
+                Ok((src.pos(), written))
+            }
+            State::PastEof => {
+                let mut dst = OutBuffer::around(output);
+
+                // We already sent all the input we could get to zstd. Time to
+                // flush out the buffer and be done with it.
+
+                // Phase 2: flush out the operation's buffer
+                // Keep calling `finish()` until the buffer is empty.
+                let hint = self
+                    .operation
+                    .finish(&mut dst, self.finished_frame)
+                    .map_err(|e| locate(e, self.position))?;
+                self.position.advance(0, dst.pos());
+                if hint == 0 {
+                    // This indicates that the footer is complete.
+                    // This is the only way to terminate the stream cleanly.
+                    self.state = State::Finished;
+                }
+
+                Ok((0, Some(dst.pos())))
+            }
+            State::Finished => Ok((0, Some(0))),
+        }
+    }
+}
+
 impl<R, D> Reader<R, D> {
     /// Creates a new `Reader`.
     ///
@@ -35,21 +165,23 @@ impl<R, D> Reader<R, D> {
     pub fn new(reader: R, operation: D) -> Self {
         Reader {
             reader,
-            operation,
-            state: State::Reading,
-            single_frame: false,
-            finished_frame: false,
+            core: ReaderCore::new(operation),
         }
     }
 
     /// Sets `self` to stop after the first decoded frame.
     pub fn set_single_frame(&mut self) {
-        self.single_frame = true;
+        self.core.set_single_frame();
+    }
+
+    /// Returns a reference to the underlying operation.
+    pub fn operation(&self) -> &D {
+        self.core.operation()
     }
 
     /// Returns a mutable reference to the underlying operation.
     pub fn operation_mut(&mut self) -> &mut D {
-        &mut self.operation
+        self.core.operation_mut()
     }
 
     /// Returns a mutable reference to the underlying reader.
@@ -74,7 +206,9 @@ impl<R, D> Reader<R, D> {
     where
         D: Operation,
     {
-        self.operation.flush(&mut OutBuffer::around(output))
+        self.core
+            .operation_mut()
+            .flush(&mut OutBuffer::around(output))
     }
 }
 // Read and retry on Interrupted errors.
@@ -109,93 +243,22 @@ where
 {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         // Keep trying until _something_ has been written.
+        // The first run doesn't wait for new input.
         let mut first = true;
         loop {
-            match self.state {
-                State::Reading => {
-                    let (bytes_read, bytes_written) = {
-                        // Start with a fresh pool of un-processed data.
-                        // This is the only line that can return an interruption error.
-                        let input = if first {
-                            // eprintln!("First run, no input coming.");
-                            b""
-                        } else {
-                            fill_buf(&mut self.reader)?
-                        };
-
-                        // eprintln!("Input = {:?}", input);
-
-                        // It's possible we don't have any new data to read.
-                        // (In this case we may still have zstd's own buffer to clear.)
-                        if !first && input.is_empty() {
-                            self.state = State::PastEof;
-                            continue;
-                        }
-                        first = false;
-
-                        let mut src = InBuffer::around(input);
-                        let mut dst = OutBuffer::around(buf);
-
-                        // We don't want empty input (from first=true) to cause a frame
-                        // re-initialization.
-                        if self.finished_frame && !input.is_empty() {
-                            // eprintln!("!! Reigniting !!");
-                            self.operation.reinit()?;
-                            self.finished_frame = false;
-                        }
-
-                        // Phase 1: feed input to the operation
-                        let hint = self.operation.run(&mut src, &mut dst)?;
-                        // eprintln!(
-                        //     "Hint={} Just run our operation:\n In={:?}\n Out={:?}",
-                        //     hint, src, dst
-                        // );
-
-                        if hint == 0 {
-                            // In practice this only happens when decoding, when we just finished
-                            // reading a frame.
-                            self.finished_frame = true;
-                            if self.single_frame {
-                                self.state = State::Finished;
-                            }
-                        }
-
-                        // eprintln!("Output: {:?}", dst);
-
-                        (src.pos(), dst.pos())
-                    };
-
-                    self.reader.consume(bytes_read);
-
-                    if bytes_written > 0 {
-                        return Ok(bytes_written);
-                    }
-
-                    // We need more data! Try again!
-                }
-                State::PastEof => {
-                    let mut dst = OutBuffer::around(buf);
-
-                    // We already sent all the input we could get to zstd. Time to flush out the
-                    // buffer and be done with it.
-
-                    // Phase 2: flush out the operation's buffer
-                    // Keep calling `finish()` until the buffer is empty.
-                    let hint = self
-                        .operation
-                        .finish(&mut dst, self.finished_frame)?;
-                    // eprintln!("Hint: {} ; Output: {:?}", hint, dst);
-                    if hint == 0 {
-                        // This indicates that the footer is complete.
-                        // This is the only way to terminate the stream cleanly.
-                        self.state = State::Finished;
-                    }
-
-                    return Ok(dst.pos());
-                }
-                State::Finished => {
-                    return Ok(0);
-                }
+            // This is the only line that can return an interruption error.
+            let input = if first || !self.core.wants_input() {
+                None
+            } else {
+                Some(fill_buf(&mut self.reader)?)
+            };
+            first = false;
+
+            let (bytes_read, written) = self.core.step(input, buf)?;
+            self.reader.consume(bytes_read);
+
+            if let Some(written) = written {
+                return Ok(written);
             }
         }
     }
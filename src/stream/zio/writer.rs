@@ -1,5 +1,6 @@
 use std::io::{self, Write};
 
+use crate::error::{locate, Position};
 use crate::stream::raw::{InBuffer, Operation, OutBuffer};
 
 // input -> [ zstd -> buffer -> writer ]
@@ -12,6 +13,15 @@ use crate::stream::raw::{InBuffer, Operation, OutBuffer};
 /// output to a wrapped `Write`.
 pub struct Writer<W, D> {
     writer: W,
+    core: WriterCore<D>,
+}
+
+/// The state of a [`Writer`], without the inner writer.
+///
+/// This drives the operation one step at a time into a buffer, so blocking
+/// and async writers can share it. The buffer must be written out with
+/// [`pending`](Self::pending) before each step.
+pub(crate) struct WriterCore<D> {
     operation: D,
 
     offset: usize,
@@ -22,6 +32,240 @@ pub struct Writer<W, D> {
     finished: bool,
 
     finished_frame: bool,
+
+    // When `true`, the operation was given some input since the last frame
+    // was ended.
+    frame_started: bool,
+
+    // Bytes consumed and produced so far, used to locate errors.
+    position: Position,
+}
+
+impl<D> WriterCore<D> {
+    pub(crate) fn new(operation: D) -> Self {
+        WriterCore {
+            operation,
+
+            offset: 0,
+            // 32KB buffer? That's what flate2 uses
+            buffer: Vec::with_capacity(32 * 1024),
+
+            finished: false,
+            finished_frame: false,
+            frame_started: false,
+            position: Position::default(),
+        }
+    }
+
+    pub(crate) fn operation(&self) -> &D {
+        &self.operation
+    }
+
+    pub(crate) fn operation_mut(&mut self) -> &mut D {
+        &mut self.operation
+    }
+
+    pub(crate) fn into_operation(self) -> D {
+        self.operation
+    }
+
+    /// Returns `true` once the operation is finished.
+    ///
+    /// All that's left is to write out the buffer.
+    pub(crate) fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Returns the output not yet written to the inner writer.
+    pub(crate) fn pending(&self) -> &[u8] {
+        &self.buffer[self.offset..]
+    }
+
+    /// Marks `n` bytes of the pending output as written.
+    pub(crate) fn advance(&mut self, n: usize) {
+        self.offset += n;
+    }
+
+    /// Run the given closure on `self.buffer`.
+    ///
+    /// The buffer will be cleared, and made available wrapped in an `OutBuffer`.
+    fn with_buffer<F, T>(&mut self, f: F) -> T
+    where
+        F: FnOnce(&mut OutBuffer<'_, Vec<u8>>, &mut D) -> T,
+    {
+        self.buffer.clear();
+        self.offset = 0;
+        let mut output = OutBuffer::around(&mut self.buffer);
+        // eprintln!("Output: {:?}", output);
+        f(&mut output, &mut self.operation)
+    }
+
+    /// Returns the error for writes after the end of the stream.
+    pub(crate) fn check_not_finished(&self) -> io::Result<()> {
+        if self.finished {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "encoder is finished",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Runs the operation on `buf`.
+    ///
+    /// Returns the number of bytes consumed, or `None` if nothing was
+    /// consumed and the output must be written out before trying again.
+    pub(crate) fn write_step(
+        &mut self,
+        buf: &[u8],
+    ) -> io::Result<Option<usize>>
+    where
+        D: Operation,
+    {
+        // Support writing concatenated frames by re-initializing the
+        // context.
+        if self.finished_frame {
+            self.operation.reinit()?;
+            self.finished_frame = false;
+        }
+
+        let mut src = InBuffer::around(buf);
+        let hint = self.with_buffer(|dst, op| op.run(&mut src, dst));
+        let bytes_read = src.pos;
+
+        // eprintln!(
+        //     "Write Hint: {:?}\n src: {:?}\n dst: {:?}",
+        //     hint, src, self.buffer
+        // );
+
+        let hint = match hint {
+            Ok(hint) => hint,
+            Err(e) => {
+                let mut position = self.position;
+                position.advance(bytes_read, self.buffer.len());
+                return Err(locate(e, position));
+            }
+        };
+        self.position.advance(bytes_read, self.buffer.len());
+
+        if hint == 0 {
+            self.finished_frame = true;
+            self.position.frame_index += 1;
+        }
+        if bytes_read > 0 {
+            self.frame_started = true;
+        }
+
+        // As soon as we've consumed something, return: if an error occurs
+        // later, the user couldn't know that some data _was_ written.
+        if bytes_read > 0 || buf.is_empty() {
+            return Ok(Some(bytes_read));
+        }
+        Ok(None)
+    }
+
+    /// Flushes some of the operation's internal buffer.
+    ///
+    /// Returns `true` once everything was flushed.
+    pub(crate) fn flush_step(&mut self) -> io::Result<bool>
+    where
+        D: Operation,
+    {
+        let hint = self.with_buffer(|dst, op| op.flush(dst));
+        let hint = hint.map_err(|e| locate(e, self.position))?;
+        self.position.advance(0, self.buffer.len());
+
+        if !self.buffer.is_empty() {
+            self.frame_started = true;
+        }
+
+        Ok(hint == 0)
+    }
+
+    /// Finishes some of the current frame.
+    ///
+    /// Once everything is finished, [`is_finished`](Self::is_finished)
+    /// returns `true`.
+    pub(crate) fn finish_step(&mut self) -> io::Result<()>
+    where
+        D: Operation,
+    {
+        let finished_frame = self.finished_frame;
+        let hint = self.with_buffer(|dst, op| op.finish(dst, finished_frame));
+        // println!("Hint: {:?}\nOut:{:?}", hint, &self.buffer);
+
+        // We return here if zstd had a problem.
+        // Could happen with invalid data, ...
+        let hint = hint.map_err(|e| locate(e, self.position))?;
+        self.position.advance(0, self.buffer.len());
+
+        if hint != 0 && self.buffer.is_empty() {
+            // This happens if we are decoding an incomplete frame.
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "incomplete frame",
+            ));
+        }
+
+        // println!("Finishing {}, {}", bytes_written, hint);
+
+        self.finished = hint == 0;
+        Ok(())
+    }
+
+    /// Ends the current frame, if any, and buffers a skippable frame after
+    /// the pending output.
+    ///
+    /// Nothing is buffered if this returns an error.
+    #[cfg(feature = "experimental")]
+    pub(crate) fn buffer_skippable_frame(
+        &mut self,
+        magic_variant: u32,
+        data: &[u8],
+    ) -> io::Result<()>
+    where
+        D: Operation,
+    {
+        self.check_not_finished()?;
+
+        let mut frame = Vec::with_capacity(
+            data.len() + zstd_safe::SKIPPABLEHEADERSIZE as usize,
+        );
+        zstd_safe::write_skippable_frame(&mut frame, data, magic_variant)
+            .map_err(crate::map_error_code)?;
+
+        // End the current frame, after the output still pending.
+        let pending = self.buffer.len();
+        let mut position = self.position;
+        while self.frame_started {
+            self.buffer.reserve(zstd_safe::CCtx::out_size());
+            let start = self.buffer.len();
+            let finished_frame = self.finished_frame;
+            let hint = {
+                let mut dst = OutBuffer::around_pos(&mut self.buffer, start);
+                self.operation.finish(&mut dst, finished_frame)
+            };
+            let hint = match hint {
+                Ok(hint) => hint,
+                Err(e) => {
+                    self.buffer.truncate(pending);
+                    return Err(locate(e, position));
+                }
+            };
+            position.advance(0, self.buffer.len() - start);
+            if hint == 0 {
+                self.operation.reinit()?;
+                self.frame_started = false;
+                position.frame_index += 1;
+            }
+        }
+
+        self.buffer.extend_from_slice(&frame);
+        position.advance(0, frame.len());
+        position.frame_index += 1;
+        self.position = position;
+        Ok(())
+    }
 }
 
 impl<W, D> Writer<W, D>
@@ -35,14 +279,7 @@ where
     pub fn new(writer: W, operation: D) -> Self {
         Writer {
             writer,
-            operation,
-
-            offset: 0,
-            // 32KB buffer? That's what flate2 uses
-            buffer: Vec::with_capacity(32 * 1024),
-
-            finished: false,
-            finished_frame: false,
+            core: WriterCore::new(operation),
         }
     }
 
@@ -62,47 +299,34 @@ where
 
             // At this point the buffer has been fully written out.
 
-            if self.finished {
+            if self.core.is_finished() {
                 return Ok(());
             }
 
             // Let's fill this buffer again!
-
-            let finished_frame = self.finished_frame;
-            let hint =
-                self.with_buffer(|dst, op| op.finish(dst, finished_frame));
-            self.offset = 0;
-            // println!("Hint: {:?}\nOut:{:?}", hint, &self.buffer);
-
-            // We return here if zstd had a problem.
-            // Could happen with invalid data, ...
-            let hint = hint?;
-
-            if hint != 0 && self.buffer.is_empty() {
-                // This happens if we are decoding an incomplete frame.
-                return Err(io::Error::new(
-                    io::ErrorKind::UnexpectedEof,
-                    "incomplete frame",
-                ));
-            }
-
-            // println!("Finishing {}, {}", bytes_written, hint);
-
-            self.finished = hint == 0;
+            self.core.finish_step()?;
         }
     }
 
-    /// Run the given closure on `self.buffer`.
+    /// Ends the current frame, if any, and writes a skippable frame.
     ///
-    /// The buffer will be cleared, and made available wrapped in an `OutBuffer`.
-    fn with_buffer<F, T>(&mut self, f: F) -> T
-    where
-        F: FnOnce(&mut OutBuffer<'_, Vec<u8>>, &mut D) -> T,
-    {
-        self.buffer.clear();
-        let mut output = OutBuffer::around(&mut self.buffer);
-        // eprintln!("Output: {:?}", output);
-        f(&mut output, &mut self.operation)
+    /// The skippable frame holds `data`, with the magic number
+    /// `MAGIC_SKIPPABLE_START + magic_variant`. The next write will start a
+    /// new frame.
+    ///
+    /// The end of the current frame and the skippable frame are buffered
+    /// before anything is written. If this returns an error from the inner
+    /// writer, they are both still pending, and will be written by the next
+    /// call to `write`, `flush` or `finish`.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn write_skippable_frame(
+        &mut self,
+        magic_variant: u32,
+        data: &[u8],
+    ) -> io::Result<()> {
+        self.core.buffer_skippable_frame(magic_variant, data)?;
+        self.write_from_offset()
     }
 
     /// Attempt to write `self.buffer` to the wrapped writer.
@@ -111,15 +335,15 @@ where
     fn write_from_offset(&mut self) -> io::Result<()> {
         // The code looks a lot like `write_all`, but keeps track of what has
         // been written in case we're interrupted.
-        while self.offset < self.buffer.len() {
-            match self.writer.write(&self.buffer[self.offset..]) {
+        while !self.core.pending().is_empty() {
+            match self.writer.write(self.core.pending()) {
                 Ok(0) => {
                     return Err(io::Error::new(
                         io::ErrorKind::WriteZero,
                         "writer will not accept any more data",
                     ))
                 }
-                Ok(n) => self.offset += n,
+                Ok(n) => self.core.advance(n),
                 Err(ref e) if e.kind() == io::ErrorKind::Interrupted => (),
                 Err(e) => return Err(e),
             }
@@ -132,7 +356,7 @@ where
     /// Careful: if you call this before calling [`Writer::finish()`], the
     /// output may be incomplete.
     pub fn into_inner(self) -> (W, D) {
-        (self.writer, self.operation)
+        (self.writer, self.core.into_operation())
     }
 
     /// Gives a reference to the inner writer.
@@ -147,24 +371,24 @@ where
 
     /// Gives a reference to the inner operation.
     pub fn operation(&self) -> &D {
-        &self.operation
+        self.core.operation()
     }
 
     /// Gives a mutable reference to the inner operation.
     pub fn operation_mut(&mut self) -> &mut D {
-        &mut self.operation
+        self.core.operation_mut()
     }
 
     /// Returns the offset in the current buffer. Only useful for debugging.
     #[cfg(test)]
     pub fn offset(&self) -> usize {
-        self.offset
+        self.core.offset
     }
 
     /// Returns the current buffer. Only useful for debugging.
     #[cfg(test)]
     pub fn buffer(&self) -> &[u8] {
-        &self.buffer
+        &self.core.buffer
     }
 }
 
@@ -174,54 +398,21 @@ where
     D: Operation,
 {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        if self.finished {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                "encoder is finished",
-            ));
-        }
+        self.core.check_not_finished()?;
         // Keep trying until _something_ has been consumed.
-        // As soon as some input has been taken, we cannot afford
-        // to take any chance: if an error occurs, the user couldn't know
-        // that some data _was_ successfully written.
         loop {
             // First, write any pending data from `self.buffer`.
             self.write_from_offset()?;
             // At this point `self.buffer` can safely be discarded.
 
-            // Support writing concatenated frames by re-initializing the
-            // context.
-            if self.finished_frame {
-                self.operation.reinit()?;
-                self.finished_frame = false;
-            }
-
-            let mut src = InBuffer::around(buf);
-            let hint = self.with_buffer(|dst, op| op.run(&mut src, dst));
-            let bytes_read = src.pos;
-
-            // eprintln!(
-            //     "Write Hint: {:?}\n src: {:?}\n dst: {:?}",
-            //     hint, src, self.buffer
-            // );
-
-            self.offset = 0;
-            let hint = hint?;
-
-            if hint == 0 {
-                self.finished_frame = true;
-            }
-
-            // As we said, as soon as we've consumed something, return.
-            if bytes_read > 0 || buf.is_empty() {
-                // println!("Returning {}", bytes_read);
+            if let Some(bytes_read) = self.core.write_step(buf)? {
                 return Ok(bytes_read);
             }
         }
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        let mut finished = self.finished;
+        let mut finished = self.core.is_finished();
         loop {
             // If the output is blocked or has an error, return now.
             self.write_from_offset()?;
@@ -230,12 +421,7 @@ where
                 break;
             }
 
-            let hint = self.with_buffer(|dst, op| op.flush(dst));
-
-            self.offset = 0;
-            let hint = hint?;
-
-            finished = hint == 0;
+            finished = self.core.flush_step()?;
         }
 
         self.writer.flush()
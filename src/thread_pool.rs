@@ -0,0 +1,60 @@
+//! Thread pools shared between encoders.
+use std::io;
+use std::sync::Arc;
+
+use crate::map_error_code;
+
+/// A pool of compression threads, shared between many encoders.
+///
+/// By default, each multithreaded encoder spawns its own worker threads.
+/// Encoders given a pool use its threads instead, so the total number of
+/// threads stays fixed however many encoders run concurrently.
+///
+/// This is a cheap handle: clones refer to the same threads. Encoders
+/// using the pool keep a handle to it, so the threads are only stopped once
+/// the last handle and the last encoder using them are dropped.
+#[derive(Clone)]
+pub struct ThreadPool {
+    pool: Arc<zstd_safe::ThreadPool>,
+    num_threads: u32,
+}
+
+impl ThreadPool {
+    /// Creates a pool with `num_threads` threads.
+    pub fn new(num_threads: u32) -> io::Result<Self> {
+        let pool = zstd_safe::ThreadPool::try_new(num_threads as usize)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    "failed to create thread pool",
+                )
+            })?;
+        Ok(ThreadPool {
+            pool: Arc::new(pool),
+            num_threads,
+        })
+    }
+
+    /// Returns the number of threads in this pool.
+    pub fn num_threads(&self) -> u32 {
+        self.num_threads
+    }
+
+    /// Makes `context` compress using this pool.
+    ///
+    /// This also sets the number of workers to the size of the pool. The
+    /// context keeps a handle to the pool, and fails to attach a different
+    /// pool once it has one.
+    pub(crate) fn attach(
+        &self,
+        context: &mut zstd_safe::CCtx<'_>,
+    ) -> io::Result<()> {
+        context
+            .set_parameter(zstd_safe::CParameter::NbWorkers(self.num_threads))
+            .map_err(map_error_code)?;
+        context
+            .ref_shared_thread_pool(Arc::clone(&self.pool))
+            .map_err(map_error_code)?;
+        Ok(())
+    }
+}
@@ -35,10 +35,15 @@ pub use zstd_sys::ZSTD_strategy as Strategy;
 
 /// Reset directive.
 // pub use zstd_sys::ZSTD_ResetDirective as ResetDirective;
-use core::ffi::{c_char, c_int, c_ulonglong, c_void};
+use core::ffi::{c_char, c_int, c_uint, c_ulonglong, c_void};
 
+#[cfg(feature = "experimental")]
+use core::alloc::{GlobalAlloc, Layout};
 use core::marker::PhantomData;
+#[cfg(feature = "experimental")]
+use core::mem::ManuallyDrop;
 use core::num::{NonZeroU32, NonZeroU64};
+use core::ops::RangeInclusive;
 use core::ops::{Deref, DerefMut};
 use core::ptr::NonNull;
 use core::str;
@@ -89,6 +94,13 @@ fn parse_code(code: usize) -> SafeResult {
     }
 }
 
+/// Returns the error code for the given `ZSTD_ErrorCode`.
+#[cfg(feature = "experimental")]
+fn error_code(code: zstd_sys::ZSTD_ErrorCode) -> ErrorCode {
+    // Error codes are negated `ZSTD_ErrorCode` values.
+    0usize.wrapping_sub(code as usize)
+}
+
 /// Parse a content size value.
 ///
 /// zstd uses 2 special content size values to indicate either unknown size or parsing error.
@@ -213,7 +225,22 @@ pub fn compress_bound(src_size: usize) -> usize {
 ///
 /// It is recommended to allocate a single context per thread and re-use it
 /// for many compression operations.
-pub struct CCtx<'a>(NonNull<zstd_sys::ZSTD_CCtx>, PhantomData<&'a ()>);
+pub struct CCtx<'a>(
+    NonNull<zstd_sys::ZSTD_CCtx>,
+    PhantomData<&'a ()>,
+    SharedThreadPool,
+);
+
+/// The shared thread pool used by a context, kept alive until it is dropped.
+#[derive(Clone, Default)]
+struct SharedThreadPool(
+    #[cfg(all(
+        feature = "experimental",
+        feature = "zstdmt",
+        feature = "std"
+    ))]
+    Option<std::sync::Arc<ThreadPool>>,
+);
 
 impl Default for CCtx<'_> {
     fn default() -> Self {
@@ -230,6 +257,7 @@ impl<'a> CCtx<'a> {
         Some(CCtx(
             NonNull::new(unsafe { zstd_sys::ZSTD_createCCtx() })?,
             PhantomData,
+            SharedThreadPool::default(),
         ))
     }
 
@@ -243,6 +271,55 @@ impl<'a> CCtx<'a> {
             .expect("zstd returned null pointer when creating new context")
     }
 
+    /// Wraps the `ZSTD_createCCtx_advanced()` function.
+    ///
+    /// Creates a new context using `allocator` for all its memory.
+    ///
+    /// Returns `None` if the allocation failed. A panic in `allocator`
+    /// aborts the process.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn try_create_with_allocator<A: GlobalAlloc + Sync>(
+        allocator: &'a A,
+    ) -> Option<Self> {
+        // Safety: `allocator` outlives the context.
+        Some(CCtx(
+            NonNull::new(unsafe {
+                zstd_sys::ZSTD_createCCtx_advanced(custom_mem(allocator))
+            })?,
+            PhantomData,
+            SharedThreadPool::default(),
+        ))
+    }
+
+    /// Wraps the `ZSTD_initStaticCCtx()` function.
+    ///
+    /// Creates a context living in `workspace`, which will never allocate
+    /// memory. Use `estimate_cctx_size` to find out how large `workspace`
+    /// should be.
+    ///
+    /// Such a context cannot load dictionaries with `load_dictionary`, and
+    /// does not support multi-threading.
+    ///
+    /// Returns `None` if `workspace` is too small.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn init_static(workspace: &'a mut [u8]) -> Option<Self> {
+        let workspace = align_workspace(workspace);
+        // Safety: zstd only uses `workspace`, which outlives the context.
+        // Freeing a static context is a no-op.
+        Some(CCtx(
+            NonNull::new(unsafe {
+                zstd_sys::ZSTD_initStaticCCtx(
+                    ptr_mut_void(workspace),
+                    workspace.len(),
+                )
+            })?,
+            PhantomData,
+            SharedThreadPool::default(),
+        ))
+    }
+
     /// Wraps the `ZSTD_compressCCtx()` function
     pub fn compress<C: WriteBuf + ?Sized>(
         &mut self,
@@ -470,6 +547,58 @@ impl<'a> CCtx<'a> {
         })
     }
 
+    /// Wraps the `ZSTD_CCtx_loadDictionary_advanced()` function.
+    ///
+    /// Same as `load_dictionary`, with control over how the dictionary is
+    /// loaded and interpreted.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn load_dictionary_advanced<'b>(
+        &mut self,
+        dict: &'b [u8],
+        load_method: DictLoadMethod,
+        content_type: DictContentType,
+    ) -> SafeResult
+    where
+        'b: 'a,
+    {
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_CCtx_loadDictionary_advanced(
+                self.0.as_ptr(),
+                ptr_void(dict),
+                dict.len(),
+                load_method.as_sys(),
+                content_type.as_sys(),
+            )
+        })
+    }
+
+    /// Wraps the `ZSTD_CCtx_refPrefix_advanced()` function.
+    ///
+    /// Same as `ref_prefix`, with control over how the prefix is
+    /// interpreted. `ref_prefix` always uses `DictContentType::RawContent`.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn ref_prefix_advanced<'b>(
+        &mut self,
+        prefix: &'b [u8],
+        content_type: DictContentType,
+    ) -> SafeResult
+    where
+        'b: 'a,
+    {
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_CCtx_refPrefix_advanced(
+                self.0.as_ptr(),
+                ptr_void(prefix),
+                prefix.len(),
+                content_type.as_sys(),
+            )
+        })
+    }
+
     /// Performs a step of a streaming compression operation.
     ///
     /// This will read some data from `input` and/or write some data to `output`.
@@ -567,6 +696,120 @@ impl<'a> CCtx<'a> {
         parse_code(code)
     }
 
+    /// Wraps the `ZSTD_getFrameProgression()` function.
+    ///
+    /// Returns the progress of the frame currently being compressed.
+    ///
+    /// This is mostly useful with multithreaded compression, where input is
+    /// buffered and compressed in the background.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn frame_progression(&self) -> FrameProgression {
+        // Safety: Just FFI
+        unsafe { zstd_sys::ZSTD_getFrameProgression(self.0.as_ptr()) }.into()
+    }
+
+    /// Wraps the `ZSTD_toFlushNow()` function.
+    ///
+    /// Returns the amount of compressed data ready to be flushed right away.
+    ///
+    /// Only meaningful with multithreaded compression: it is always `0`
+    /// otherwise.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn to_flush_now(&mut self) -> usize {
+        // Safety: Just FFI
+        unsafe { zstd_sys::ZSTD_toFlushNow(self.0.as_ptr()) }
+    }
+
+    /// Wraps the `ZSTD_compressBegin()` function.
+    ///
+    /// Starts a new frame with the buffer-less API, using the given
+    /// compression level.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn compress_begin<'s>(
+        &'s mut self,
+        compression_level: CompressionLevel,
+    ) -> Result<BufferlessCompressor<'s, 'a>, ErrorCode> {
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_compressBegin(self.0.as_ptr(), compression_level)
+        })?;
+        Ok(BufferlessCompressor::new(self))
+    }
+
+    /// Wraps the `ZSTD_compressBegin_usingDict()` function.
+    ///
+    /// Starts a new frame with the buffer-less API, using the given
+    /// dictionary and compression level.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn compress_begin_using_dict<'s>(
+        &'s mut self,
+        dictionary: &'s [u8],
+        compression_level: CompressionLevel,
+    ) -> Result<BufferlessCompressor<'s, 'a>, ErrorCode> {
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_compressBegin_usingDict(
+                self.0.as_ptr(),
+                ptr_void(dictionary),
+                dictionary.len(),
+                compression_level,
+            )
+        })?;
+        Ok(BufferlessCompressor::new(self))
+    }
+
+    /// Wraps the `ZSTD_compressBegin_usingCDict()` function.
+    ///
+    /// Starts a new frame with the buffer-less API, using the given
+    /// prepared dictionary.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn compress_begin_using_cdict<'s>(
+        &'s mut self,
+        cdict: &'s CDict<'_>,
+    ) -> Result<BufferlessCompressor<'s, 'a>, ErrorCode> {
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_compressBegin_usingCDict(
+                self.0.as_ptr(),
+                cdict.0.as_ptr(),
+            )
+        })?;
+        Ok(BufferlessCompressor::new(self))
+    }
+
+    /// Wraps the `ZSTD_compressBegin_advanced()` function.
+    ///
+    /// Starts a new frame with the buffer-less API, using the given
+    /// dictionary (may be empty) and parameters.
+    ///
+    /// If `pledged_src_size` is given, the total size of the data compressed
+    /// in this frame must match it.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn compress_begin_advanced<'s>(
+        &'s mut self,
+        dictionary: &'s [u8],
+        params: &Parameters,
+        pledged_src_size: Option<u64>,
+    ) -> Result<BufferlessCompressor<'s, 'a>, ErrorCode> {
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_compressBegin_advanced(
+                self.0.as_ptr(),
+                ptr_void(dictionary),
+                dictionary.len(),
+                (*params).into(),
+                pledged_src_size.unwrap_or(CONTENTSIZE_UNKNOWN),
+            )
+        })?;
+        Ok(BufferlessCompressor::new(self))
+    }
+
     /// Returns the size currently used by this context.
     ///
     /// This may change over time.
@@ -591,120 +834,7 @@ impl<'a> CCtx<'a> {
     ///
     /// Some of these parameters need to be set during de-compression as well.
     pub fn set_parameter(&mut self, param: CParameter) -> SafeResult {
-        // TODO: Until bindgen properly generates a binding for this, we'll need to do it here.
-
-        #[cfg(feature = "experimental")]
-        use zstd_sys::ZSTD_cParameter::{
-            ZSTD_c_experimentalParam1 as ZSTD_c_rsyncable,
-            ZSTD_c_experimentalParam10 as ZSTD_c_stableOutBuffer,
-            ZSTD_c_experimentalParam11 as ZSTD_c_blockDelimiters,
-            ZSTD_c_experimentalParam12 as ZSTD_c_validateSequences,
-            ZSTD_c_experimentalParam13 as ZSTD_c_useBlockSplitter,
-            ZSTD_c_experimentalParam14 as ZSTD_c_useRowMatchFinder,
-            ZSTD_c_experimentalParam15 as ZSTD_c_deterministicRefPrefix,
-            ZSTD_c_experimentalParam16 as ZSTD_c_prefetchCDictTables,
-            ZSTD_c_experimentalParam17 as ZSTD_c_enableSeqProducerFallback,
-            ZSTD_c_experimentalParam18 as ZSTD_c_maxBlockSize,
-            ZSTD_c_experimentalParam19 as ZSTD_c_searchForExternalRepcodes,
-            ZSTD_c_experimentalParam2 as ZSTD_c_format,
-            ZSTD_c_experimentalParam3 as ZSTD_c_forceMaxWindow,
-            ZSTD_c_experimentalParam4 as ZSTD_c_forceAttachDict,
-            ZSTD_c_experimentalParam5 as ZSTD_c_literalCompressionMode,
-            ZSTD_c_experimentalParam7 as ZSTD_c_srcSizeHint,
-            ZSTD_c_experimentalParam8 as ZSTD_c_enableDedicatedDictSearch,
-            ZSTD_c_experimentalParam9 as ZSTD_c_stableInBuffer,
-        };
-
-        use zstd_sys::ZSTD_cParameter::*;
-        use CParameter::*;
-
-        let (param, value) = match param {
-            #[cfg(feature = "experimental")]
-            RSyncable(rsyncable) => (ZSTD_c_rsyncable, rsyncable as c_int),
-            #[cfg(feature = "experimental")]
-            Format(format) => (ZSTD_c_format, format as c_int),
-            #[cfg(feature = "experimental")]
-            ForceMaxWindow(force) => (ZSTD_c_forceMaxWindow, force as c_int),
-            #[cfg(feature = "experimental")]
-            ForceAttachDict(force) => (ZSTD_c_forceAttachDict, force as c_int),
-            #[cfg(feature = "experimental")]
-            LiteralCompressionMode(mode) => {
-                (ZSTD_c_literalCompressionMode, mode as c_int)
-            }
-            #[cfg(feature = "experimental")]
-            SrcSizeHint(value) => (ZSTD_c_srcSizeHint, value as c_int),
-            #[cfg(feature = "experimental")]
-            EnableDedicatedDictSearch(enable) => {
-                (ZSTD_c_enableDedicatedDictSearch, enable as c_int)
-            }
-            #[cfg(feature = "experimental")]
-            StableInBuffer(stable) => (ZSTD_c_stableInBuffer, stable as c_int),
-            #[cfg(feature = "experimental")]
-            StableOutBuffer(stable) => {
-                (ZSTD_c_stableOutBuffer, stable as c_int)
-            }
-            #[cfg(feature = "experimental")]
-            BlockDelimiters(value) => (ZSTD_c_blockDelimiters, value as c_int),
-            #[cfg(feature = "experimental")]
-            ValidateSequences(validate) => {
-                (ZSTD_c_validateSequences, validate as c_int)
-            }
-            #[cfg(feature = "experimental")]
-            UseBlockSplitter(split) => {
-                (ZSTD_c_useBlockSplitter, split as c_int)
-            }
-            #[cfg(feature = "experimental")]
-            UseRowMatchFinder(mode) => {
-                (ZSTD_c_useRowMatchFinder, mode as c_int)
-            }
-            #[cfg(feature = "experimental")]
-            DeterministicRefPrefix(deterministic) => {
-                (ZSTD_c_deterministicRefPrefix, deterministic as c_int)
-            }
-            #[cfg(feature = "experimental")]
-            PrefetchCDictTables(prefetch) => {
-                (ZSTD_c_prefetchCDictTables, prefetch as c_int)
-            }
-            #[cfg(feature = "experimental")]
-            EnableSeqProducerFallback(enable) => {
-                (ZSTD_c_enableSeqProducerFallback, enable as c_int)
-            }
-            #[cfg(feature = "experimental")]
-            MaxBlockSize(value) => (ZSTD_c_maxBlockSize, value as c_int),
-            #[cfg(feature = "experimental")]
-            SearchForExternalRepcodes(value) => {
-                (ZSTD_c_searchForExternalRepcodes, value as c_int)
-            }
-            TargetCBlockSize(value) => {
-                (ZSTD_c_targetCBlockSize, value as c_int)
-            }
-            CompressionLevel(level) => (ZSTD_c_compressionLevel, level),
-            WindowLog(value) => (ZSTD_c_windowLog, value as c_int),
-            HashLog(value) => (ZSTD_c_hashLog, value as c_int),
-            ChainLog(value) => (ZSTD_c_chainLog, value as c_int),
-            SearchLog(value) => (ZSTD_c_searchLog, value as c_int),
-            MinMatch(value) => (ZSTD_c_minMatch, value as c_int),
-            TargetLength(value) => (ZSTD_c_targetLength, value as c_int),
-            Strategy(strategy) => (ZSTD_c_strategy, strategy as c_int),
-            EnableLongDistanceMatching(flag) => {
-                (ZSTD_c_enableLongDistanceMatching, flag as c_int)
-            }
-            LdmHashLog(value) => (ZSTD_c_ldmHashLog, value as c_int),
-            LdmMinMatch(value) => (ZSTD_c_ldmMinMatch, value as c_int),
-            LdmBucketSizeLog(value) => {
-                (ZSTD_c_ldmBucketSizeLog, value as c_int)
-            }
-            LdmHashRateLog(value) => (ZSTD_c_ldmHashRateLog, value as c_int),
-            ContentSizeFlag(flag) => (ZSTD_c_contentSizeFlag, flag as c_int),
-            ChecksumFlag(flag) => (ZSTD_c_checksumFlag, flag as c_int),
-            DictIdFlag(flag) => (ZSTD_c_dictIDFlag, flag as c_int),
-
-            NbWorkers(value) => (ZSTD_c_nbWorkers, value as c_int),
-
-            JobSize(value) => (ZSTD_c_jobSize, value as c_int),
-
-            OverlapSizeLog(value) => (ZSTD_c_overlapLog, value as c_int),
-        };
+        let (param, value) = param.to_raw();
 
         // Safety: Just FFI
         parse_code(unsafe {
@@ -712,6 +842,29 @@ impl<'a> CCtx<'a> {
         })
     }
 
+    /// Wraps the `ZSTD_CCtx_getParameter()` function.
+    ///
+    /// Returns the raw value of the given parameter. Only the kind of
+    /// `param` is used: the value it holds is ignored.
+    ///
+    /// Parameters left to their default value usually return `0`.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn get_parameter(&self, param: CParameter) -> Result<i32, ErrorCode> {
+        let (param, _) = param.to_raw();
+        let mut value = 0;
+
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_CCtx_getParameter(
+                self.0.as_ptr(),
+                param,
+                &mut value,
+            )
+        })?;
+        Ok(value)
+    }
+
     /// Guarantee that the input size will be this value.
     ///
     /// If given `None`, assumes the size is unknown.
@@ -733,18 +886,81 @@ impl<'a> CCtx<'a> {
         })
     }
 
-    /// Creates a copy of this context.
+    /// Wraps the `ZSTD_CCtx_setCParams()` function.
     ///
-    /// This only works before any data has been compressed. An error will be
-    /// returned otherwise.
+    /// Sets all compression parameters at once. They are checked first, and
+    /// nothing is changed if any is invalid.
     #[cfg(feature = "experimental")]
     #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
-    pub fn try_clone(
-        &self,
-        pledged_src_size: Option<u64>,
-    ) -> Result<Self, ErrorCode> {
+    pub fn set_compression_parameters(
+        &mut self,
+        params: &CompressionParameters,
+    ) -> SafeResult {
         // Safety: Just FFI
-        let context = NonNull::new(unsafe { zstd_sys::ZSTD_createCCtx() })
+        parse_code(unsafe {
+            zstd_sys::ZSTD_CCtx_setCParams(self.0.as_ptr(), (*params).into())
+        })
+    }
+
+    /// Wraps the `ZSTD_CCtx_setFParams()` function.
+    ///
+    /// Sets all frame parameters at once.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn set_frame_parameters(
+        &mut self,
+        params: &FrameParameters,
+    ) -> SafeResult {
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_CCtx_setFParams(self.0.as_ptr(), (*params).into())
+        })
+    }
+
+    /// Wraps the `ZSTD_CCtx_setParametersUsingCCtxParams()` function.
+    ///
+    /// Applies all parameters from `params` to this context.
+    ///
+    /// This is only possible before compression starts.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn set_parameters_using_cctx_params(
+        &mut self,
+        params: &CCtxParams,
+    ) -> SafeResult {
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_CCtx_setParametersUsingCCtxParams(
+                self.0.as_ptr(),
+                params.0.as_ptr(),
+            )
+        })
+    }
+
+    /// Wraps the `ZSTD_CCtx_setParams()` function.
+    ///
+    /// Sets both compression and frame parameters at once.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn set_parameters(&mut self, params: &Parameters) -> SafeResult {
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_CCtx_setParams(self.0.as_ptr(), (*params).into())
+        })
+    }
+
+    /// Creates a copy of this context.
+    ///
+    /// This only works before any data has been compressed. An error will be
+    /// returned otherwise.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn try_clone(
+        &self,
+        pledged_src_size: Option<u64>,
+    ) -> Result<Self, ErrorCode> {
+        // Safety: Just FFI
+        let context = NonNull::new(unsafe { zstd_sys::ZSTD_createCCtx() })
             .ok_or(0usize)?;
 
         // Safety: Just FFI
@@ -756,7 +972,7 @@ impl<'a> CCtx<'a> {
             )
         })?;
 
-        Ok(CCtx(context, self.1))
+        Ok(CCtx(context, self.1, self.2.clone()))
     }
 
     /// Wraps the `ZSTD_getBlockSize()` function.
@@ -789,6 +1005,144 @@ impl<'a> CCtx<'a> {
         }
     }
 
+    /// Wraps the `ZSTD_generateSequences()` function.
+    ///
+    /// Compresses `src` using the current parameters, and writes the
+    /// resulting sequences to `sequences` instead of the compressed data.
+    ///
+    /// `sequences` should hold at least `sequence_bound(src.len())` elements.
+    /// The output always includes block delimiters: they can be removed with
+    /// `merge_block_delimiters`.
+    ///
+    /// Returns the number of sequences written.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn generate_sequences(
+        &mut self,
+        sequences: &mut [Sequence],
+        src: &[u8],
+    ) -> SafeResult {
+        // Safety: `Sequence` has the same layout as `ZSTD_Sequence`.
+        parse_code(unsafe {
+            zstd_sys::ZSTD_generateSequences(
+                self.0.as_ptr(),
+                sequences.as_mut_ptr().cast(),
+                sequences.len(),
+                ptr_void(src),
+                src.len(),
+            )
+        })
+    }
+
+    /// Wraps the `ZSTD_compressSequences()` function.
+    ///
+    /// Compresses `src` into a single frame, using the given `sequences`
+    /// instead of searching for matches. `src` must contain the entire
+    /// input, not just the literals.
+    ///
+    /// If `CParameter::BlockDelimiters` is enabled, `sequences` must include
+    /// block delimiters.
+    ///
+    /// Sequences covering more than `src`, or with matches shorter than 3
+    /// bytes, are always rejected with an `externalSequences_invalid` error:
+    /// zstd would otherwise read out of bounds. Other checks, such as
+    /// offsets pointing before the start of the data, are only done if
+    /// `CParameter::ValidateSequences` is enabled. Without them, invalid
+    /// sequences produce a corrupted frame.
+    ///
+    /// Returns the number of bytes written to `dst`.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn compress_sequences<C: WriteBuf + ?Sized>(
+        &mut self,
+        dst: &mut C,
+        sequences: &[Sequence],
+        src: &[u8],
+    ) -> SafeResult {
+        if !sequences_fit(sequences, src.len()) {
+            return Err(error_code(
+                zstd_sys::ZSTD_ErrorCode::ZSTD_error_externalSequences_invalid,
+            ));
+        }
+
+        // Safety: ZSTD_compressSequences returns the number of bytes written,
+        // and `Sequence` has the same layout as `ZSTD_Sequence`. Sequences
+        // don't cover more than `src`, and offsets are never dereferenced.
+        unsafe {
+            dst.write_from(|buffer, capacity| {
+                parse_code(zstd_sys::ZSTD_compressSequences(
+                    self.0.as_ptr(),
+                    buffer,
+                    capacity,
+                    sequences.as_ptr().cast(),
+                    sequences.len(),
+                    ptr_void(src),
+                    src.len(),
+                ))
+            })
+        }
+    }
+
+    /// Wraps the `ZSTD_registerSequenceProducer()` function.
+    ///
+    /// Uses `producer` to find matches instead of the built-in match finder.
+    ///
+    /// This also enables `CParameter::EnableSeqProducerFallback`, so blocks
+    /// for which the producer fails (or panics) are compressed with the
+    /// built-in match finder, and `CParameter::ValidateSequences`, so
+    /// sequences with invalid offsets result in an error rather than a
+    /// corrupted frame.
+    ///
+    /// Sequences longer than the block or with too short matches are always
+    /// treated as a failure of the producer, even if
+    /// `CParameter::ValidateSequences` is disabled later: zstd would
+    /// otherwise read out of bounds. Offsets are never dereferenced when
+    /// compressing, so they don't need this check.
+    ///
+    /// The producer is only used by `compress2` and the streaming functions.
+    /// It is not compatible with multi-threading or long-distance matching.
+    #[cfg(all(feature = "experimental", feature = "std"))]
+    #[cfg_attr(
+        feature = "doc-cfg",
+        doc(cfg(all(feature = "experimental", feature = "std")))
+    )]
+    pub fn register_sequence_producer<'b, P>(
+        &mut self,
+        producer: &'b mut P,
+    ) -> SafeResult
+    where
+        P: SequenceProducer + Send,
+        'b: 'a,
+    {
+        // Safety: `producer` outlives the context, and is only used through
+        // the matching callback.
+        unsafe {
+            zstd_sys::ZSTD_registerSequenceProducer(
+                self.0.as_ptr(),
+                (producer as *mut P).cast(),
+                Some(produce_sequences::<P>),
+            );
+        }
+        self.set_parameter(CParameter::EnableSeqProducerFallback(true))?;
+        self.set_parameter(CParameter::ValidateSequences(true))
+    }
+
+    /// Stops using any previously registered sequence producer.
+    #[cfg(all(feature = "experimental", feature = "std"))]
+    #[cfg_attr(
+        feature = "doc-cfg",
+        doc(cfg(all(feature = "experimental", feature = "std")))
+    )]
+    pub fn clear_sequence_producer(&mut self) {
+        unsafe {
+            zstd_sys::ZSTD_registerSequenceProducer(
+                self.0.as_ptr(),
+                core::ptr::null_mut(),
+                None,
+            );
+        }
+    }
+
     /// Returns the recommended input buffer size.
     ///
     /// Using this size may result in minor performance boost.
@@ -822,6 +1176,39 @@ impl<'a> CCtx<'a> {
         })
     }
 
+    /// Use a shared thread pool for this context.
+    ///
+    /// The context keeps `pool` alive until it is dropped. Once it has
+    /// compressed with a pool, zstd keeps using its threads, so this fails
+    /// if a different shared pool was given before.
+    #[cfg(all(feature = "experimental", feature = "zstdmt", feature = "std"))]
+    #[cfg_attr(
+        feature = "doc-cfg",
+        doc(cfg(all(
+            feature = "experimental",
+            feature = "zstdmt",
+            feature = "std"
+        )))
+    )]
+    pub fn ref_shared_thread_pool(
+        &mut self,
+        pool: std::sync::Arc<ThreadPool>,
+    ) -> SafeResult {
+        let shared = &mut (self.2).0;
+        if let Some(current) = shared {
+            if !std::sync::Arc::ptr_eq(current, &pool) {
+                return Err(error_code(
+                    zstd_sys::ZSTD_ErrorCode::ZSTD_error_stage_wrong,
+                ));
+            }
+        }
+        let pool = shared.get_or_insert(pool);
+        // Safety: `self` keeps the pool alive.
+        parse_code(unsafe {
+            zstd_sys::ZSTD_CCtx_refThreadPool(self.0.as_ptr(), pool.0.as_ptr())
+        })
+    }
+
     /// Return to using a private thread pool for this context.
     #[cfg(all(feature = "experimental", feature = "zstdmt"))]
     #[cfg_attr(
@@ -851,114 +1238,534 @@ unsafe impl Send for CCtx<'_> {}
 // Non thread-safe methods already take `&mut self`, so it's fine to implement Sync here.
 unsafe impl Sync for CCtx<'_> {}
 
-unsafe fn c_char_to_str(text: *const c_char) -> &'static str {
-    core::ffi::CStr::from_ptr(text)
-        .to_str()
-        .expect("bad error message from zstd")
-}
-
-/// Returns the error string associated with an error code.
-pub fn get_error_name(code: usize) -> &'static str {
-    unsafe {
-        // Safety: assumes ZSTD returns a well-formed utf8 string.
-        let name = zstd_sys::ZSTD_getErrorName(code);
-        c_char_to_str(name)
-    }
-}
-
-/// A Decompression Context.
-///
-/// The lifetime references the potential dictionary used for this context.
+/// A set of compression parameters, which can be applied to many contexts.
 ///
-/// If no dictionary was used, it will most likely be `'static`.
-///
-/// Same as `DStream`.
-pub struct DCtx<'a>(NonNull<zstd_sys::ZSTD_DCtx>, PhantomData<&'a ()>);
+/// This wraps a `ZSTD_CCtx_params` object.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub struct CCtxParams(NonNull<zstd_sys::ZSTD_CCtx_params>);
 
-impl Default for DCtx<'_> {
+#[cfg(feature = "experimental")]
+impl Default for CCtxParams {
     fn default() -> Self {
-        DCtx::create()
+        CCtxParams::create()
     }
 }
 
-impl<'a> DCtx<'a> {
-    /// Try to create a new decompression context.
+#[cfg(feature = "experimental")]
+impl CCtxParams {
+    /// Tries to create a new parameter set, with default values.
     ///
-    /// Returns `None` if the operation failed (for example, not enough memory).
+    /// Returns `None` if zstd returns a NULL pointer - may happen if allocation fails.
     pub fn try_create() -> Option<Self> {
-        Some(DCtx(
-            NonNull::new(unsafe { zstd_sys::ZSTD_createDCtx() })?,
-            PhantomData,
-        ))
+        // Safety: Just FFI
+        Some(CCtxParams(NonNull::new(unsafe {
+            zstd_sys::ZSTD_createCCtxParams()
+        })?))
     }
 
-    /// Creates a new decoding context.
+    /// Wrap `ZSTD_createCCtxParams`
     ///
     /// # Panics
     ///
-    /// If the context creation fails.
+    /// If zstd returns a NULL pointer.
     pub fn create() -> Self {
         Self::try_create()
-            .expect("zstd returned null pointer when creating new context")
+            .expect("zstd returned null pointer when creating new parameters")
     }
 
-    /// Fully decompress the given frame.
+    /// Wraps the `ZSTD_CCtxParams_reset()` function.
     ///
-    /// This decompress an entire frame in-memory. If you can have enough memory to store both the
-    /// input and output buffer, then it may be faster that streaming decompression.
+    /// Resets all parameters to their default values.
+    pub fn reset(&mut self) -> SafeResult {
+        // Safety: Just FFI
+        parse_code(unsafe { zstd_sys::ZSTD_CCtxParams_reset(self.0.as_ptr()) })
+    }
+
+    /// Wraps the `ZSTD_CCtxParams_init()` function.
     ///
-    /// Wraps the `ZSTD_decompressDCtx()` function.
-    pub fn decompress<C: WriteBuf + ?Sized>(
-        &mut self,
-        dst: &mut C,
-        src: &[u8],
-    ) -> SafeResult {
-        unsafe {
-            dst.write_from(|buffer, capacity| {
-                parse_code(zstd_sys::ZSTD_decompressDCtx(
-                    self.0.as_ptr(),
-                    buffer,
-                    capacity,
-                    ptr_void(src),
-                    src.len(),
-                ))
-            })
-        }
+    /// Resets all parameters to their default values, then sets the
+    /// compression level.
+    pub fn init(&mut self, compression_level: CompressionLevel) -> SafeResult {
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_CCtxParams_init(self.0.as_ptr(), compression_level)
+        })
     }
 
-    /// Fully decompress the given frame using a dictionary.
+    /// Wraps the `ZSTD_CCtxParams_init_advanced()` function.
     ///
-    /// Dictionary must be identical to the one used during compression.
+    /// Resets all parameters to their default values, then sets the given
+    /// compression and frame parameters.
+    pub fn init_advanced(&mut self, params: &Parameters) -> SafeResult {
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_CCtxParams_init_advanced(
+                self.0.as_ptr(),
+                (*params).into(),
+            )
+        })
+    }
+
+    /// Wraps the `ZSTD_CCtxParams_setParameter()` function.
+    pub fn set_parameter(&mut self, param: CParameter) -> SafeResult {
+        let (param, value) = param.to_raw();
+
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_CCtxParams_setParameter(
+                self.0.as_ptr(),
+                param,
+                value,
+            )
+        })
+    }
+
+    /// Wraps the `ZSTD_CCtxParams_getParameter()` function.
     ///
-    /// If you plan on using the same dictionary multiple times, it is faster to create a `DDict`
-    /// first and use `decompress_using_ddict`.
+    /// Returns the raw value of the given parameter. Only the kind of
+    /// `param` is used: the value it holds is ignored.
     ///
-    /// Wraps `ZSTD_decompress_usingDict`
-    pub fn decompress_using_dict<C: WriteBuf + ?Sized>(
-        &mut self,
-        dst: &mut C,
-        src: &[u8],
-        dict: &[u8],
-    ) -> SafeResult {
-        unsafe {
-            dst.write_from(|buffer, capacity| {
-                parse_code(zstd_sys::ZSTD_decompress_usingDict(
+    /// Parameters left to their default value usually return `0`.
+    pub fn get_parameter(&self, param: CParameter) -> Result<i32, ErrorCode> {
+        let (param, _) = param.to_raw();
+        let mut value = 0;
+
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_CCtxParams_getParameter(
+                self.0.as_ptr(),
+                param,
+                &mut value,
+            )
+        })?;
+        Ok(value)
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl CCtxParams {
+    /// Parameters copied by `clone`.
+    const CLONED_PARAMETERS: &'static [CParameter] = {
+        use CParameter::*;
+        &[
+            CompressionLevel(0),
+            WindowLog(0),
+            HashLog(0),
+            ChainLog(0),
+            SearchLog(0),
+            MinMatch(0),
+            TargetLength(0),
+            Strategy(zstd_sys::ZSTD_strategy::ZSTD_fast),
+            TargetCBlockSize(0),
+            EnableLongDistanceMatching(false),
+            LdmHashLog(0),
+            LdmMinMatch(0),
+            LdmBucketSizeLog(0),
+            LdmHashRateLog(0),
+            ContentSizeFlag(false),
+            ChecksumFlag(false),
+            DictIdFlag(false),
+            NbWorkers(0),
+            JobSize(0),
+            OverlapSizeLog(0),
+            RSyncable(false),
+            Format(FrameFormat::One),
+            ForceMaxWindow(false),
+            ForceAttachDict(DictAttachPref::DefaultAttach),
+            LiteralCompressionMode(ParamSwitch::Auto),
+            SrcSizeHint(0),
+            EnableDedicatedDictSearch(false),
+            StableInBuffer(false),
+            StableOutBuffer(false),
+            BlockDelimiters(false),
+            ValidateSequences(false),
+            UseBlockSplitter(ParamSwitch::Auto),
+            UseRowMatchFinder(ParamSwitch::Auto),
+            DeterministicRefPrefix(false),
+            PrefetchCDictTables(ParamSwitch::Auto),
+            EnableSeqProducerFallback(false),
+            MaxBlockSize(0),
+            SearchForExternalRepcodes(ParamSwitch::Auto),
+        ]
+    };
+}
+
+/// There is no copy function for `ZSTD_CCtx_params`, so this copies each
+/// parameter with a `CParameter` variant instead.
+///
+/// Any other state is not copied: parameters only known to a newer zstd
+/// keep their default value in the clone.
+#[cfg(feature = "experimental")]
+impl Clone for CCtxParams {
+    fn clone(&self) -> Self {
+        let copy = CCtxParams::create();
+        for &param in Self::CLONED_PARAMETERS {
+            let (param, _) = param.to_raw();
+            let mut value = 0;
+
+            // Safety: Just FFI
+            unsafe {
+                let code = zstd_sys::ZSTD_CCtxParams_getParameter(
                     self.0.as_ptr(),
-                    buffer,
-                    capacity,
-                    ptr_void(src),
-                    src.len(),
-                    ptr_void(dict),
-                    dict.len(),
-                ))
-            })
+                    param,
+                    &mut value,
+                );
+                // Some parameters are not supported by every build.
+                if parse_code(code).is_err() {
+                    continue;
+                }
+                let code = zstd_sys::ZSTD_CCtxParams_setParameter(
+                    copy.0.as_ptr(),
+                    param,
+                    value,
+                );
+                debug_assert!(parse_code(code).is_ok());
+            }
         }
+        copy
     }
+}
 
-    /// Fully decompress the given frame using a dictionary.
-    ///
-    /// Dictionary must be identical to the one used during compression.
-    ///
+#[cfg(feature = "experimental")]
+impl Drop for CCtxParams {
+    fn drop(&mut self) {
+        // Safety: Just FFI
+        unsafe {
+            zstd_sys::ZSTD_freeCCtxParams(self.0.as_ptr());
+        }
+    }
+}
+
+#[cfg(feature = "experimental")]
+unsafe impl Send for CCtxParams {}
+// Mutating methods take `&mut self`, so it's fine to implement Sync here.
+#[cfg(feature = "experimental")]
+unsafe impl Sync for CCtxParams {}
+
+unsafe fn c_char_to_str(text: *const c_char) -> &'static str {
+    core::ffi::CStr::from_ptr(text)
+        .to_str()
+        .expect("bad error message from zstd")
+}
+
+/// The kind of error behind an `ErrorCode`.
+///
+/// See `ErrorKind::from_code`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Generic error.
+    Generic,
+
+    /// The data does not start with a known frame magic number.
+    PrefixUnknown,
+
+    /// The frame was written by an unsupported version of zstd.
+    VersionUnsupported,
+
+    /// The frame uses an unsupported parameter.
+    FrameParameterUnsupported,
+
+    /// The frame requires a window larger than allowed.
+    FrameParameterWindowTooLarge,
+
+    /// The compressed data is corrupted.
+    CorruptionDetected,
+
+    /// The content checksum does not match.
+    ChecksumWrong,
+
+    /// A literals header is invalid.
+    LiteralsHeaderWrong,
+
+    /// The dictionary is corrupted.
+    DictionaryCorrupted,
+
+    /// The frame requires a different dictionary.
+    DictionaryWrong,
+
+    /// The dictionary could not be created.
+    DictionaryCreationFailed,
+
+    /// The parameter is not supported.
+    ParameterUnsupported,
+
+    /// The combination of parameters is not supported.
+    ParameterCombinationUnsupported,
+
+    /// The parameter value is out of bounds.
+    ParameterOutOfBound,
+
+    /// An entropy table log is too large.
+    TableLogTooLarge,
+
+    /// A maximum symbol value is too large.
+    MaxSymbolValueTooLarge,
+
+    /// A maximum symbol value is too small.
+    MaxSymbolValueTooSmall,
+
+    /// A stable buffer was modified between calls.
+    StabilityConditionNotRespected,
+
+    /// The operation is not allowed at this stage.
+    StageWrong,
+
+    /// The context was not initialized.
+    InitMissing,
+
+    /// Memory allocation failed.
+    MemoryAllocation,
+
+    /// The workspace is too small.
+    WorkSpaceTooSmall,
+
+    /// The destination buffer is too small.
+    DstSizeTooSmall,
+
+    /// The source size is not the expected one.
+    SrcSizeWrong,
+
+    /// The destination buffer is null.
+    DstBufferNull,
+
+    /// No progress could be made: the output buffer is full.
+    NoForwardProgressDestFull,
+
+    /// No progress could be made: the input buffer is empty.
+    NoForwardProgressInputEmpty,
+
+    /// The frame index is too large.
+    FrameIndexTooLarge,
+
+    /// An I/O error happened in the seekable format.
+    SeekableIo,
+
+    /// The destination buffer is invalid.
+    DstBufferWrong,
+
+    /// The source buffer is invalid.
+    SrcBufferWrong,
+
+    /// The external sequence producer failed.
+    SequenceProducerFailed,
+
+    /// The external sequences are invalid.
+    ExternalSequencesInvalid,
+
+    /// An error code unknown to this version of the bindings.
+    Unknown,
+}
+
+impl ErrorKind {
+    /// Returns the kind of the given error code.
+    ///
+    /// Wraps the `ZSTD_getErrorCode()` function, whose values are stable
+    /// across zstd versions. Values unknown to this crate, for example from
+    /// a newer system library, are reported as `ErrorKind::Unknown`.
+    ///
+    /// Returns `None` if `code` is not an error.
+    pub fn from_code(code: ErrorCode) -> Option<Self> {
+        if !is_error(code) {
+            return None;
+        }
+
+        // Safety: Just FFI
+        Some(match unsafe { ZSTD_getErrorCode(code) } {
+            1 => ErrorKind::Generic,
+            10 => ErrorKind::PrefixUnknown,
+            12 => ErrorKind::VersionUnsupported,
+            14 => ErrorKind::FrameParameterUnsupported,
+            16 => ErrorKind::FrameParameterWindowTooLarge,
+            20 => ErrorKind::CorruptionDetected,
+            22 => ErrorKind::ChecksumWrong,
+            24 => ErrorKind::LiteralsHeaderWrong,
+            30 => ErrorKind::DictionaryCorrupted,
+            32 => ErrorKind::DictionaryWrong,
+            34 => ErrorKind::DictionaryCreationFailed,
+            40 => ErrorKind::ParameterUnsupported,
+            41 => ErrorKind::ParameterCombinationUnsupported,
+            42 => ErrorKind::ParameterOutOfBound,
+            44 => ErrorKind::TableLogTooLarge,
+            46 => ErrorKind::MaxSymbolValueTooLarge,
+            48 => ErrorKind::MaxSymbolValueTooSmall,
+            50 => ErrorKind::StabilityConditionNotRespected,
+            60 => ErrorKind::StageWrong,
+            62 => ErrorKind::InitMissing,
+            64 => ErrorKind::MemoryAllocation,
+            66 => ErrorKind::WorkSpaceTooSmall,
+            70 => ErrorKind::DstSizeTooSmall,
+            72 => ErrorKind::SrcSizeWrong,
+            74 => ErrorKind::DstBufferNull,
+            80 => ErrorKind::NoForwardProgressDestFull,
+            82 => ErrorKind::NoForwardProgressInputEmpty,
+            100 => ErrorKind::FrameIndexTooLarge,
+            102 => ErrorKind::SeekableIo,
+            104 => ErrorKind::DstBufferWrong,
+            105 => ErrorKind::SrcBufferWrong,
+            106 => ErrorKind::SequenceProducerFailed,
+            107 => ErrorKind::ExternalSequencesInvalid,
+            _ => ErrorKind::Unknown,
+        })
+    }
+}
+
+extern "C" {
+    // Declared here rather than taken from `zstd_sys`, where it returns a
+    // Rust enum that can't hold values added by newer zstd versions.
+    fn ZSTD_getErrorCode(function_result: usize) -> c_uint;
+}
+
+/// Returns the error string associated with an error code.
+pub fn get_error_name(code: usize) -> &'static str {
+    unsafe {
+        // Safety: assumes ZSTD returns a well-formed utf8 string.
+        let name = zstd_sys::ZSTD_getErrorName(code);
+        c_char_to_str(name)
+    }
+}
+
+/// A Decompression Context.
+///
+/// The lifetime references the potential dictionary used for this context.
+///
+/// If no dictionary was used, it will most likely be `'static`.
+///
+/// Same as `DStream`.
+pub struct DCtx<'a>(NonNull<zstd_sys::ZSTD_DCtx>, PhantomData<&'a ()>);
+
+impl Default for DCtx<'_> {
+    fn default() -> Self {
+        DCtx::create()
+    }
+}
+
+impl<'a> DCtx<'a> {
+    /// Try to create a new decompression context.
+    ///
+    /// Returns `None` if the operation failed (for example, not enough memory).
+    pub fn try_create() -> Option<Self> {
+        Some(DCtx(
+            NonNull::new(unsafe { zstd_sys::ZSTD_createDCtx() })?,
+            PhantomData,
+        ))
+    }
+
+    /// Creates a new decoding context.
+    ///
+    /// # Panics
+    ///
+    /// If the context creation fails.
+    pub fn create() -> Self {
+        Self::try_create()
+            .expect("zstd returned null pointer when creating new context")
+    }
+
+    /// Wraps the `ZSTD_createDCtx_advanced()` function.
+    ///
+    /// Creates a new context using `allocator` for all its memory.
+    ///
+    /// Returns `None` if the allocation failed. A panic in `allocator`
+    /// aborts the process.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn try_create_with_allocator<A: GlobalAlloc + Sync>(
+        allocator: &'a A,
+    ) -> Option<Self> {
+        // Safety: `allocator` outlives the context.
+        Some(DCtx(
+            NonNull::new(unsafe {
+                zstd_sys::ZSTD_createDCtx_advanced(custom_mem(allocator))
+            })?,
+            PhantomData,
+        ))
+    }
+
+    /// Wraps the `ZSTD_initStaticDCtx()` function.
+    ///
+    /// Creates a context living in `workspace`, which will never allocate
+    /// memory. Use `estimate_dctx_size` to find out how large `workspace`
+    /// should be.
+    ///
+    /// Such a context cannot load dictionaries with `load_dictionary`, and
+    /// does not support legacy formats.
+    ///
+    /// Returns `None` if `workspace` is too small.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn init_static(workspace: &'a mut [u8]) -> Option<Self> {
+        let workspace = align_workspace(workspace);
+        // Safety: zstd only uses `workspace`, which outlives the context.
+        // Freeing a static context is a no-op.
+        Some(DCtx(
+            NonNull::new(unsafe {
+                zstd_sys::ZSTD_initStaticDCtx(
+                    ptr_mut_void(workspace),
+                    workspace.len(),
+                )
+            })?,
+            PhantomData,
+        ))
+    }
+
+    /// Fully decompress the given frame.
+    ///
+    /// This decompress an entire frame in-memory. If you can have enough memory to store both the
+    /// input and output buffer, then it may be faster that streaming decompression.
+    ///
+    /// Wraps the `ZSTD_decompressDCtx()` function.
+    pub fn decompress<C: WriteBuf + ?Sized>(
+        &mut self,
+        dst: &mut C,
+        src: &[u8],
+    ) -> SafeResult {
+        unsafe {
+            dst.write_from(|buffer, capacity| {
+                parse_code(zstd_sys::ZSTD_decompressDCtx(
+                    self.0.as_ptr(),
+                    buffer,
+                    capacity,
+                    ptr_void(src),
+                    src.len(),
+                ))
+            })
+        }
+    }
+
+    /// Fully decompress the given frame using a dictionary.
+    ///
+    /// Dictionary must be identical to the one used during compression.
+    ///
+    /// If you plan on using the same dictionary multiple times, it is faster to create a `DDict`
+    /// first and use `decompress_using_ddict`.
+    ///
+    /// Wraps `ZSTD_decompress_usingDict`
+    pub fn decompress_using_dict<C: WriteBuf + ?Sized>(
+        &mut self,
+        dst: &mut C,
+        src: &[u8],
+        dict: &[u8],
+    ) -> SafeResult {
+        unsafe {
+            dst.write_from(|buffer, capacity| {
+                parse_code(zstd_sys::ZSTD_decompress_usingDict(
+                    self.0.as_ptr(),
+                    buffer,
+                    capacity,
+                    ptr_void(src),
+                    src.len(),
+                    ptr_void(dict),
+                    dict.len(),
+                ))
+            })
+        }
+    }
+
+    /// Fully decompress the given frame using a dictionary.
+    ///
+    /// Dictionary must be identical to the one used during compression.
+    ///
     /// Wraps the `ZSTD_decompress_usingDDict()` function.
     pub fn decompress_using_ddict<C: WriteBuf + ?Sized>(
         &mut self,
@@ -1105,50 +1912,149 @@ impl<'a> DCtx<'a> {
         })
     }
 
-    /// Sets a decompression parameter.
-    pub fn set_parameter(&mut self, param: DParameter) -> SafeResult {
-        #[cfg(feature = "experimental")]
-        use zstd_sys::ZSTD_dParameter::{
-            ZSTD_d_experimentalParam1 as ZSTD_d_format,
-            ZSTD_d_experimentalParam2 as ZSTD_d_stableOutBuffer,
-            ZSTD_d_experimentalParam3 as ZSTD_d_forceIgnoreChecksum,
-            ZSTD_d_experimentalParam4 as ZSTD_d_refMultipleDDicts,
-        };
-
-        use zstd_sys::ZSTD_dParameter::*;
-        use DParameter::*;
+    /// Wraps the `ZSTD_DCtx_loadDictionary_advanced()` function.
+    ///
+    /// Same as `load_dictionary`, with control over how the dictionary is
+    /// loaded and interpreted.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn load_dictionary_advanced<'b>(
+        &mut self,
+        dict: &'b [u8],
+        load_method: DictLoadMethod,
+        content_type: DictContentType,
+    ) -> SafeResult
+    where
+        'b: 'a,
+    {
+        parse_code(unsafe {
+            zstd_sys::ZSTD_DCtx_loadDictionary_advanced(
+                self.0.as_ptr(),
+                ptr_void(dict),
+                dict.len(),
+                load_method.as_sys(),
+                content_type.as_sys(),
+            )
+        })
+    }
 
-        let (param, value) = match param {
-            #[cfg(feature = "experimental")]
-            Format(format) => (ZSTD_d_format, format as c_int),
-            #[cfg(feature = "experimental")]
-            StableOutBuffer(stable) => {
-                (ZSTD_d_stableOutBuffer, stable as c_int)
-            }
-            #[cfg(feature = "experimental")]
-            ForceIgnoreChecksum(force) => {
-                (ZSTD_d_forceIgnoreChecksum, force as c_int)
-            }
-            #[cfg(feature = "experimental")]
-            RefMultipleDDicts(value) => {
-                (ZSTD_d_refMultipleDDicts, value as c_int)
-            }
+    /// Wraps the `ZSTD_DCtx_refPrefix_advanced()` function.
+    ///
+    /// Same as `ref_prefix`, with control over how the prefix is
+    /// interpreted. `ref_prefix` always uses `DictContentType::RawContent`.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn ref_prefix_advanced<'b>(
+        &mut self,
+        prefix: &'b [u8],
+        content_type: DictContentType,
+    ) -> SafeResult
+    where
+        'b: 'a,
+    {
+        parse_code(unsafe {
+            zstd_sys::ZSTD_DCtx_refPrefix_advanced(
+                self.0.as_ptr(),
+                ptr_void(prefix),
+                prefix.len(),
+                content_type.as_sys(),
+            )
+        })
+    }
 
-            WindowLogMax(value) => (ZSTD_d_windowLogMax, value as c_int),
-        };
+    /// Sets a decompression parameter.
+    pub fn set_parameter(&mut self, param: DParameter) -> SafeResult {
+        let (param, value) = param.to_raw();
 
         parse_code(unsafe {
             zstd_sys::ZSTD_DCtx_setParameter(self.0.as_ptr(), param, value)
         })
     }
 
-    /// Performs a step of a streaming decompression operation.
-    ///
-    /// This will read some data from `input` and/or write some data to `output`.
-    ///
-    /// # Returns
+    /// Wraps the `ZSTD_DCtx_getParameter()` function.
     ///
-    /// * `Ok(0)` if the current frame just finished decompressing successfully.
+    /// Returns the raw value of the given parameter. Only the kind of
+    /// `param` is used: the value it holds is ignored.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn get_parameter(&self, param: DParameter) -> Result<i32, ErrorCode> {
+        let (param, _) = param.to_raw();
+        let mut value = 0;
+
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_DCtx_getParameter(
+                self.0.as_ptr(),
+                param,
+                &mut value,
+            )
+        })?;
+        Ok(value)
+    }
+
+    /// Wraps the `ZSTD_decompressBegin()` function.
+    ///
+    /// Starts decompressing a new frame with the buffer-less API.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn decompress_begin<'s>(
+        &'s mut self,
+    ) -> Result<BufferlessDecompressor<'s, 'a>, ErrorCode> {
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_decompressBegin(self.0.as_ptr())
+        })?;
+        Ok(BufferlessDecompressor::new(self))
+    }
+
+    /// Wraps the `ZSTD_decompressBegin_usingDict()` function.
+    ///
+    /// Starts decompressing a new frame with the buffer-less API, using the
+    /// given dictionary.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn decompress_begin_using_dict<'s>(
+        &'s mut self,
+        dictionary: &'s [u8],
+    ) -> Result<BufferlessDecompressor<'s, 'a>, ErrorCode> {
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_decompressBegin_usingDict(
+                self.0.as_ptr(),
+                ptr_void(dictionary),
+                dictionary.len(),
+            )
+        })?;
+        Ok(BufferlessDecompressor::new(self))
+    }
+
+    /// Wraps the `ZSTD_decompressBegin_usingDDict()` function.
+    ///
+    /// Starts decompressing a new frame with the buffer-less API, using the
+    /// given prepared dictionary.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn decompress_begin_using_ddict<'s>(
+        &'s mut self,
+        ddict: &'s DDict<'_>,
+    ) -> Result<BufferlessDecompressor<'s, 'a>, ErrorCode> {
+        // Safety: Just FFI
+        parse_code(unsafe {
+            zstd_sys::ZSTD_decompressBegin_usingDDict(
+                self.0.as_ptr(),
+                ddict.0.as_ptr(),
+            )
+        })?;
+        Ok(BufferlessDecompressor::new(self))
+    }
+
+    /// Performs a step of a streaming decompression operation.
+    ///
+    /// This will read some data from `input` and/or write some data to `output`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(0)` if the current frame just finished decompressing successfully.
     /// * `Ok(hint)` with a hint for the "ideal" amount of input data to provide in the next call.
     ///     Can be safely ignored.
     ///
@@ -1251,6 +2157,212 @@ unsafe impl Send for DCtx<'_> {}
 // Non thread-safe methods already take `&mut self`, so it's fine to implement Sync here.
 unsafe impl Sync for DCtx<'_> {}
 
+/// A frame being compressed with the buffer-less API.
+///
+/// Created by one of the `CCtx::compress_begin*` methods.
+///
+/// zstd keeps referencing the data given to previous calls, so it is borrowed
+/// for as long as the frame is being compressed. If dropped before
+/// `compress_end`, the frame is ended anyway, so the context stops using
+/// that data.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub struct BufferlessCompressor<'s, 'a> {
+    context: &'s mut CCtx<'a>,
+}
+
+#[cfg(feature = "experimental")]
+impl<'s, 'a> BufferlessCompressor<'s, 'a> {
+    fn new(context: &'s mut CCtx<'a>) -> Self {
+        BufferlessCompressor { context }
+    }
+
+    /// Wraps the `ZSTD_compressContinue()` function.
+    ///
+    /// Compresses `src` as the next part of the frame, writing the result in
+    /// `dst`. Any amount of data may be given, but `dst` must be large enough
+    /// for the output (see `compress_bound`).
+    ///
+    /// Returns the number of bytes written to `dst`, which may be 0.
+    pub fn compress_continue<C: WriteBuf + ?Sized>(
+        &mut self,
+        dst: &mut C,
+        src: &'s [u8],
+    ) -> SafeResult {
+        // Safety: Just FFI
+        unsafe {
+            dst.write_from(|buffer, capacity| {
+                parse_code(zstd_sys::ZSTD_compressContinue(
+                    self.context.0.as_ptr(),
+                    buffer,
+                    capacity,
+                    ptr_void(src),
+                    src.len(),
+                ))
+            })
+        }
+    }
+
+    /// Wraps the `ZSTD_compressEnd()` function.
+    ///
+    /// Compresses `src` (which may be empty) as the last part of the frame,
+    /// and ends it.
+    ///
+    /// Returns the number of bytes written to `dst`.
+    pub fn compress_end<C: WriteBuf + ?Sized>(
+        self,
+        dst: &mut C,
+        src: &[u8],
+    ) -> SafeResult {
+        // Safety: Just FFI
+        unsafe {
+            dst.write_from(|buffer, capacity| {
+                parse_code(zstd_sys::ZSTD_compressEnd(
+                    self.context.0.as_ptr(),
+                    buffer,
+                    capacity,
+                    ptr_void(src),
+                    src.len(),
+                ))
+            })
+        }
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl Drop for BufferlessCompressor<'_, '_> {
+    fn drop(&mut self) {
+        // Large enough for a frame header, an empty last block and a
+        // checksum.
+        let mut epilogue = [0u8; 32];
+
+        // Ending the frame prevents `CCtx::compress_block` from using the
+        // borrowed data. This fails harmlessly if it was already ended.
+        // Safety: Just FFI
+        unsafe {
+            zstd_sys::ZSTD_compressEnd(
+                self.context.0.as_ptr(),
+                ptr_mut_void(&mut epilogue[..]),
+                epilogue.len(),
+                core::ptr::null(),
+                0,
+            );
+        }
+    }
+}
+
+/// Type of the next input expected by a `BufferlessDecompressor`.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NextInputType {
+    /// The header of a frame.
+    FrameHeader,
+
+    /// The header of a block.
+    BlockHeader,
+
+    /// The content of a block.
+    Block,
+
+    /// The content of the last block of a frame.
+    LastBlock,
+
+    /// The checksum at the end of a frame.
+    Checksum,
+
+    /// The content of a skippable frame.
+    SkippableFrame,
+}
+
+/// A frame being decompressed with the buffer-less API.
+///
+/// Created by one of the `DCtx::decompress_begin*` methods.
+///
+/// zstd keeps referencing the data it decompressed previously, so output
+/// buffers stay borrowed for as long as the frame is being decompressed.
+/// When dropped, the context is reset so it stops using them.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub struct BufferlessDecompressor<'s, 'a> {
+    context: &'s mut DCtx<'a>,
+}
+
+#[cfg(feature = "experimental")]
+impl<'s, 'a> BufferlessDecompressor<'s, 'a> {
+    fn new(context: &'s mut DCtx<'a>) -> Self {
+        BufferlessDecompressor { context }
+    }
+
+    /// Wraps the `ZSTD_nextSrcSizeToDecompress()` function.
+    ///
+    /// Returns the exact number of bytes to give to the next call to
+    /// `decompress_continue`, or `0` once the frame is complete.
+    pub fn next_src_size(&self) -> usize {
+        // Safety: Just FFI
+        unsafe {
+            zstd_sys::ZSTD_nextSrcSizeToDecompress(self.context.0.as_ptr())
+        }
+    }
+
+    /// Wraps the `ZSTD_nextInputType()` function.
+    ///
+    /// Returns the type of the input expected by the next call to
+    /// `decompress_continue`.
+    pub fn next_input_type(&self) -> NextInputType {
+        use zstd_sys::ZSTD_nextInputType_e::*;
+
+        // Safety: Just FFI
+        match unsafe { zstd_sys::ZSTD_nextInputType(self.context.0.as_ptr()) }
+        {
+            ZSTDnit_frameHeader => NextInputType::FrameHeader,
+            ZSTDnit_blockHeader => NextInputType::BlockHeader,
+            ZSTDnit_block => NextInputType::Block,
+            ZSTDnit_lastBlock => NextInputType::LastBlock,
+            ZSTDnit_checksum => NextInputType::Checksum,
+            ZSTDnit_skippableFrame => NextInputType::SkippableFrame,
+        }
+    }
+
+    /// Wraps the `ZSTD_decompressContinue()` function.
+    ///
+    /// `src` must be exactly `next_src_size()` bytes long.
+    ///
+    /// Returns the part of `dst` that was written to, which may be empty, and
+    /// the rest of `dst`, which can be given to the next call. The written
+    /// part stays borrowed until the frame is complete.
+    pub fn decompress_continue(
+        &mut self,
+        dst: &'s mut [u8],
+        src: &[u8],
+    ) -> Result<(&'s [u8], &'s mut [u8]), ErrorCode> {
+        // Safety: Just FFI
+        let written = parse_code(unsafe {
+            zstd_sys::ZSTD_decompressContinue(
+                self.context.0.as_ptr(),
+                ptr_mut_void(dst),
+                dst.len(),
+                ptr_void(src),
+                src.len(),
+            )
+        })?;
+        let (written, rest) = dst.split_at_mut(written);
+        Ok((written, rest))
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl Drop for BufferlessDecompressor<'_, '_> {
+    fn drop(&mut self) {
+        // Forgets about previous output, so `DCtx::decompress_block` and
+        // `DCtx::insert_block` can't reference it.
+        // Safety: Just FFI
+        unsafe {
+            zstd_sys::ZSTD_decompressBegin(self.context.0.as_ptr());
+        }
+    }
+}
+
 /// Compression dictionary.
 pub struct CDict<'a>(NonNull<zstd_sys::ZSTD_CDict>, PhantomData<&'a ()>);
 
@@ -1314,6 +2426,70 @@ impl<'a> CDict<'a> {
         )
     }
 
+    /// Wraps the `ZSTD_createCDict_advanced()` function.
+    ///
+    /// Prepares a dictionary, with control over how it is loaded and
+    /// interpreted.
+    ///
+    /// Returns `None` if the dictionary could not be created, for example
+    /// if `content_type` is `DictContentType::FullDict` but `dict_buffer`
+    /// is not a valid full dictionary.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn try_create_advanced(
+        dict_buffer: &'a [u8],
+        load_method: DictLoadMethod,
+        content_type: DictContentType,
+        compression_level: CompressionLevel,
+    ) -> Option<Self> {
+        // Safety: Just FFI
+        Some(CDict(
+            NonNull::new(unsafe {
+                zstd_sys::ZSTD_createCDict_advanced(
+                    ptr_void(dict_buffer),
+                    dict_buffer.len(),
+                    load_method.as_sys(),
+                    content_type.as_sys(),
+                    cdict_params(dict_buffer.len(), compression_level),
+                    default_custom_mem(),
+                )
+            })?,
+            PhantomData,
+        ))
+    }
+
+    /// Wraps the `ZSTD_createCDict_advanced()` function.
+    ///
+    /// Prepares a dictionary using `allocator` for all its memory.
+    ///
+    /// The dictionary content will be copied internally, and does not need
+    /// to be kept around.
+    ///
+    /// Returns `None` if the allocation failed. A panic in `allocator`
+    /// aborts the process.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn try_create_with_allocator<A: GlobalAlloc + Sync>(
+        dict_buffer: &[u8],
+        compression_level: CompressionLevel,
+        allocator: &'a A,
+    ) -> Option<Self> {
+        // Safety: `allocator` outlives the dictionary.
+        Some(CDict(
+            NonNull::new(unsafe {
+                zstd_sys::ZSTD_createCDict_advanced(
+                    ptr_void(dict_buffer),
+                    dict_buffer.len(),
+                    zstd_sys::ZSTD_dictLoadMethod_e::ZSTD_dlm_byCopy,
+                    zstd_sys::ZSTD_dictContentType_e::ZSTD_dct_auto,
+                    cdict_params(dict_buffer.len(), compression_level),
+                    custom_mem(allocator),
+                )
+            })?,
+            PhantomData,
+        ))
+    }
+
     /// Returns the _current_ memory usage of this dictionary.
     ///
     /// Note that this may change over time.
@@ -1350,6 +2526,56 @@ impl<'a> Drop for CDict<'a> {
 unsafe impl<'a> Send for CDict<'a> {}
 unsafe impl<'a> Sync for CDict<'a> {}
 
+/// A compression dictionary living in a caller-provided workspace.
+///
+/// Created with `StaticCDict::init`, and usable as a `CDict`.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub struct StaticCDict<'a>(ManuallyDrop<CDict<'a>>);
+
+#[cfg(feature = "experimental")]
+impl<'a> StaticCDict<'a> {
+    /// Wraps the `ZSTD_initStaticCDict()` function.
+    ///
+    /// Prepares a dictionary in `workspace`, without allocating memory. Use
+    /// `estimate_cdict_size` to find out how large `workspace` should be.
+    ///
+    /// The dictionary content is copied into `workspace`.
+    ///
+    /// Returns `None` if `workspace` is too small.
+    pub fn init(
+        workspace: &'a mut [u8],
+        dict_buffer: &[u8],
+        compression_level: CompressionLevel,
+    ) -> Option<Self> {
+        let workspace = align_workspace(workspace);
+        // Safety: zstd only uses `workspace`, which outlives the dictionary.
+        let c_params = cdict_params(dict_buffer.len(), compression_level);
+        let cdict = NonNull::new(unsafe {
+            zstd_sys::ZSTD_initStaticCDict(
+                ptr_mut_void(workspace),
+                workspace.len(),
+                ptr_void(dict_buffer),
+                dict_buffer.len(),
+                zstd_sys::ZSTD_dictLoadMethod_e::ZSTD_dlm_byCopy,
+                zstd_sys::ZSTD_dictContentType_e::ZSTD_dct_auto,
+                c_params,
+            ) as *mut _
+        })?;
+        // The dictionary must not be freed: its memory belongs to the caller.
+        Some(StaticCDict(ManuallyDrop::new(CDict(cdict, PhantomData))))
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl<'a> Deref for StaticCDict<'a> {
+    type Target = CDict<'a>;
+
+    fn deref(&self) -> &CDict<'a> {
+        &self.0
+    }
+}
+
 /// Wraps the `ZSTD_compress_usingCDict()` function.
 pub fn compress_using_cdict(
     cctx: &mut CCtx<'_>,
@@ -1405,6 +2631,66 @@ impl<'a> DDict<'a> {
         )
     }
 
+    /// Wraps the `ZSTD_createDDict_advanced()` function.
+    ///
+    /// Prepares a dictionary, with control over how it is loaded and
+    /// interpreted.
+    ///
+    /// Returns `None` if the dictionary could not be created, for example
+    /// if `content_type` is `DictContentType::FullDict` but `dict_buffer`
+    /// is not a valid full dictionary.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn try_create_advanced(
+        dict_buffer: &'a [u8],
+        load_method: DictLoadMethod,
+        content_type: DictContentType,
+    ) -> Option<Self> {
+        // Safety: Just FFI
+        Some(DDict(
+            NonNull::new(unsafe {
+                zstd_sys::ZSTD_createDDict_advanced(
+                    ptr_void(dict_buffer),
+                    dict_buffer.len(),
+                    load_method.as_sys(),
+                    content_type.as_sys(),
+                    default_custom_mem(),
+                )
+            })?,
+            PhantomData,
+        ))
+    }
+
+    /// Wraps the `ZSTD_createDDict_advanced()` function.
+    ///
+    /// Prepares a dictionary using `allocator` for all its memory.
+    ///
+    /// The dictionary content will be copied internally, and does not need
+    /// to be kept around.
+    ///
+    /// Returns `None` if the allocation failed. A panic in `allocator`
+    /// aborts the process.
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    pub fn try_create_with_allocator<A: GlobalAlloc + Sync>(
+        dict_buffer: &[u8],
+        allocator: &'a A,
+    ) -> Option<Self> {
+        // Safety: `allocator` outlives the dictionary.
+        Some(DDict(
+            NonNull::new(unsafe {
+                zstd_sys::ZSTD_createDDict_advanced(
+                    ptr_void(dict_buffer),
+                    dict_buffer.len(),
+                    zstd_sys::ZSTD_dictLoadMethod_e::ZSTD_dlm_byCopy,
+                    zstd_sys::ZSTD_dictContentType_e::ZSTD_dct_auto,
+                    custom_mem(allocator),
+                )
+            })?,
+            PhantomData,
+        ))
+    }
+
     /// Returns the dictionary ID for this dict.
     ///
     /// Returns `None` if this dictionary is empty or invalid.
@@ -1433,18 +2719,169 @@ impl<'a> Drop for DDict<'a> {
 unsafe impl<'a> Send for DDict<'a> {}
 unsafe impl<'a> Sync for DDict<'a> {}
 
-/// A shared thread pool for one or more compression contexts
-#[cfg(all(feature = "experimental", feature = "zstdmt"))]
-#[cfg_attr(
-    feature = "doc-cfg",
-    doc(cfg(all(feature = "experimental", feature = "zstdmt")))
-)]
-pub struct ThreadPool(NonNull<zstd_sys::ZSTD_threadPool>);
+/// A decompression dictionary living in a caller-provided workspace.
+///
+/// Created with `StaticDDict::init`, and usable as a `DDict`.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub struct StaticDDict<'a>(ManuallyDrop<DDict<'a>>);
 
-#[cfg(all(feature = "experimental", feature = "zstdmt"))]
-#[cfg_attr(
-    feature = "doc-cfg",
-    doc(cfg(all(feature = "experimental", feature = "zstdmt")))
+#[cfg(feature = "experimental")]
+impl<'a> StaticDDict<'a> {
+    /// Wraps the `ZSTD_initStaticDDict()` function.
+    ///
+    /// Prepares a dictionary in `workspace`, without allocating memory. Use
+    /// `estimate_ddict_size` to find out how large `workspace` should be.
+    ///
+    /// The dictionary content is copied into `workspace`.
+    ///
+    /// Returns `None` if `workspace` is too small.
+    pub fn init(workspace: &'a mut [u8], dict_buffer: &[u8]) -> Option<Self> {
+        let workspace = align_workspace(workspace);
+        // Safety: zstd only uses `workspace`, which outlives the dictionary.
+        let ddict = NonNull::new(unsafe {
+            zstd_sys::ZSTD_initStaticDDict(
+                ptr_mut_void(workspace),
+                workspace.len(),
+                ptr_void(dict_buffer),
+                dict_buffer.len(),
+                zstd_sys::ZSTD_dictLoadMethod_e::ZSTD_dlm_byCopy,
+                zstd_sys::ZSTD_dictContentType_e::ZSTD_dct_auto,
+            ) as *mut _
+        })?;
+        // The dictionary must not be freed: its memory belongs to the caller.
+        Some(StaticDDict(ManuallyDrop::new(DDict(ddict, PhantomData))))
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl<'a> Deref for StaticDDict<'a> {
+    type Target = DDict<'a>;
+
+    fn deref(&self) -> &DDict<'a> {
+        &self.0
+    }
+}
+
+/// Skips the beginning of `workspace` to make it 8-bytes aligned.
+///
+/// This is required by the `ZSTD_initStatic*()` functions.
+#[cfg(feature = "experimental")]
+fn align_workspace(workspace: &mut [u8]) -> &mut [u8] {
+    let offset = workspace.as_ptr().align_offset(8).min(workspace.len());
+    &mut workspace[offset..]
+}
+
+/// Size of the header storing the size of each custom allocation.
+///
+/// zstd does not give the size of the memory it frees, but `GlobalAlloc`
+/// needs it. This is also the alignment of allocations.
+#[cfg(feature = "experimental")]
+const ALLOC_HEADER_SIZE: usize = 16;
+
+/// Builds a `ZSTD_customMem` forwarding to `allocator`.
+///
+/// `allocator` must outlive any object created with the result.
+#[cfg(feature = "experimental")]
+fn custom_mem<A: GlobalAlloc + Sync>(
+    allocator: &A,
+) -> zstd_sys::ZSTD_customMem {
+    zstd_sys::ZSTD_customMem {
+        customAlloc: Some(custom_alloc::<A>),
+        customFree: Some(custom_free::<A>),
+        opaque: allocator as *const A as *mut c_void,
+    }
+}
+
+/// Builds a `ZSTD_customMem` using zstd's default allocator.
+#[cfg(feature = "experimental")]
+fn default_custom_mem() -> zstd_sys::ZSTD_customMem {
+    zstd_sys::ZSTD_customMem {
+        customAlloc: None,
+        customFree: None,
+        opaque: core::ptr::null_mut(),
+    }
+}
+
+#[cfg(feature = "experimental")]
+unsafe extern "C" fn custom_alloc<A: GlobalAlloc>(
+    opaque: *mut c_void,
+    size: usize,
+) -> *mut c_void {
+    let allocator = &*(opaque as *const A);
+
+    // memory layout: [size] [allocation]
+    let layout =
+        match size.checked_add(ALLOC_HEADER_SIZE).and_then(|full_size| {
+            Layout::from_size_align(full_size, ALLOC_HEADER_SIZE).ok()
+        }) {
+            Some(layout) => layout,
+            None => return core::ptr::null_mut(),
+        };
+
+    let guard = AbortOnUnwind;
+    let ptr = allocator.alloc(layout);
+    core::mem::forget(guard);
+    if ptr.is_null() {
+        return core::ptr::null_mut();
+    }
+
+    // Safety: `ptr` is aligned and large enough for the header.
+    ptr.cast::<usize>().write(layout.size());
+    ptr.add(ALLOC_HEADER_SIZE).cast()
+}
+
+#[cfg(feature = "experimental")]
+unsafe extern "C" fn custom_free<A: GlobalAlloc>(
+    opaque: *mut c_void,
+    address: *mut c_void,
+) {
+    if address.is_null() {
+        return;
+    }
+    let allocator = &*(opaque as *const A);
+
+    // Safety: `address` was returned by `custom_alloc`, right after a header.
+    let ptr = address.cast::<u8>().sub(ALLOC_HEADER_SIZE);
+    let full_size = ptr.cast::<usize>().read();
+    let guard = AbortOnUnwind;
+    allocator.dealloc(
+        ptr,
+        Layout::from_size_align_unchecked(full_size, ALLOC_HEADER_SIZE),
+    );
+    core::mem::forget(guard);
+}
+
+/// Aborts the process if dropped, which only happens when unwinding.
+///
+/// Allocators must not unwind, and a panic cannot go through zstd's C code.
+#[cfg(feature = "experimental")]
+struct AbortOnUnwind;
+
+#[cfg(feature = "experimental")]
+impl Drop for AbortOnUnwind {
+    fn drop(&mut self) {
+        #[cfg(feature = "std")]
+        std::process::abort();
+
+        // Panicking while unwinding aborts.
+        #[cfg(not(feature = "std"))]
+        panic!("custom allocator panicked");
+    }
+}
+
+/// A shared thread pool for one or more compression contexts
+#[cfg(all(feature = "experimental", feature = "zstdmt"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "experimental", feature = "zstdmt")))
+)]
+pub struct ThreadPool(NonNull<zstd_sys::ZSTD_threadPool>);
+
+#[cfg(all(feature = "experimental", feature = "zstdmt"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "experimental", feature = "zstdmt")))
 )]
 impl ThreadPool {
     /// Create a thread pool with the specified number of threads.
@@ -2015,6 +3452,281 @@ pub fn get_dict_id_from_frame(src: &[u8]) -> Option<NonZeroU32> {
     })
 }
 
+/// Wraps the `ZSTD_frameHeaderSize()` function.
+///
+/// `src` should contain at least the first 5 bytes of the frame.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub fn frame_header_size(src: &[u8]) -> SafeResult {
+    parse_code(unsafe {
+        zstd_sys::ZSTD_frameHeaderSize(ptr_void(src), src.len())
+    })
+}
+
+/// The kind of frame described by a `FrameHeader`.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrameType {
+    /// A regular zstd frame.
+    Frame,
+
+    /// A skippable frame, holding user data.
+    Skippable,
+}
+
+/// Information parsed from a frame header.
+///
+/// See `get_frame_header`.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FrameHeader {
+    /// Decompressed size of the frame, if it was included in the header.
+    ///
+    /// For skippable frames, this is the size of the user data.
+    pub content_size: Option<u64>,
+
+    /// Size of the window needed to decompress this frame.
+    ///
+    /// This is `0` for skippable frames.
+    pub window_size: u64,
+
+    /// Maximum size of a block in this frame.
+    pub block_size_max: u32,
+
+    /// Whether this is a regular or a skippable frame.
+    pub frame_type: FrameType,
+
+    /// Size of the header, in bytes.
+    pub header_size: u32,
+
+    /// Dictionary ID required to decompress this frame, if any.
+    ///
+    /// For skippable frames, this is the magic variant (between 0 and 15),
+    /// and `None` only for variant 0.
+    pub dict_id: Option<NonZeroU32>,
+
+    /// Whether a checksum follows the last block of this frame.
+    pub checksum_flag: bool,
+
+    /// Whether the frame is decompressed as a single segment.
+    ///
+    /// In this case the window size is the content size.
+    pub single_segment: bool,
+}
+
+/// Progress of the compression of a frame.
+///
+/// See `CCtx::frame_progression`.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FrameProgression {
+    /// Number of input bytes given to the context so far.
+    pub ingested: u64,
+
+    /// Number of input bytes actually compressed so far.
+    pub consumed: u64,
+
+    /// Number of compressed bytes generated so far.
+    pub produced: u64,
+
+    /// Number of compressed bytes written out of the context so far.
+    pub flushed: u64,
+
+    /// ID of the job currently being compressed (multithreading only).
+    pub current_job_id: u32,
+
+    /// Number of workers currently busy (multithreading only).
+    pub nb_active_workers: u32,
+}
+
+#[cfg(feature = "experimental")]
+impl From<zstd_sys::ZSTD_frameProgression> for FrameProgression {
+    fn from(progression: zstd_sys::ZSTD_frameProgression) -> Self {
+        FrameProgression {
+            ingested: progression.ingested,
+            consumed: progression.consumed,
+            produced: progression.produced,
+            flushed: progression.flushed,
+            current_job_id: progression.currentJobID,
+            nb_active_workers: progression.nbActiveWorkers,
+        }
+    }
+}
+
+/// Indicates an error happened when parsing a frame header.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrameHeaderError {
+    /// The given prefix is too small to hold the entire header.
+    ///
+    /// Contains the size of the prefix needed.
+    Incomplete(usize),
+
+    /// The given data is not a valid frame header.
+    Invalid(ErrorCode),
+}
+
+#[cfg(feature = "experimental")]
+impl core::fmt::Display for FrameHeaderError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            FrameHeaderError::Incomplete(size) => {
+                write!(f, "Frame header needs at least {} bytes", size)
+            }
+            FrameHeaderError::Invalid(code) => {
+                f.write_str(get_error_name(code))
+            }
+        }
+    }
+}
+
+/// Wraps the `ZSTD_getFrameHeader()` function.
+///
+/// `src` should be a prefix of a frame, including at least its header.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub fn get_frame_header(src: &[u8]) -> Result<FrameHeader, FrameHeaderError> {
+    get_frame_header_advanced(src, FrameFormat::One)
+}
+
+/// Wraps the `ZSTD_getFrameHeader_advanced()` function.
+///
+/// Same as `get_frame_header`, but can parse frames without magic number
+/// when given `FrameFormat::Magicless`.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub fn get_frame_header_advanced(
+    src: &[u8],
+    format: FrameFormat,
+) -> Result<FrameHeader, FrameHeaderError> {
+    let mut header = core::mem::MaybeUninit::uninit();
+    let sys_format = match format {
+        FrameFormat::One => zstd_sys::ZSTD_format_e::ZSTD_f_zstd1,
+        FrameFormat::Magicless => {
+            zstd_sys::ZSTD_format_e::ZSTD_f_zstd1_magicless
+        }
+    };
+
+    let code = unsafe {
+        zstd_sys::ZSTD_getFrameHeader_advanced(
+            header.as_mut_ptr(),
+            ptr_void(src),
+            src.len(),
+            sys_format,
+        )
+    };
+    match parse_code(code) {
+        Ok(0) => (),
+        Ok(needed) => return Err(FrameHeaderError::Incomplete(needed)),
+        Err(code) => return Err(FrameHeaderError::Invalid(code)),
+    }
+
+    // Safety: `ZSTD_getFrameHeader_advanced` filled the header on success.
+    let header = unsafe { header.assume_init() };
+
+    let frame_type = match header.frameType {
+        zstd_sys::ZSTD_frameType_e::ZSTD_frame => FrameType::Frame,
+        zstd_sys::ZSTD_frameType_e::ZSTD_skippableFrame => {
+            FrameType::Skippable
+        }
+    };
+
+    // zstd does not report the single-segment flag, so read it from the
+    // frame header descriptor, right after the magic number.
+    let descriptor_pos = match format {
+        FrameFormat::One => 4,
+        FrameFormat::Magicless => 0,
+    };
+    let single_segment =
+        frame_type == FrameType::Frame && src[descriptor_pos] & 0x20 != 0;
+
+    Ok(FrameHeader {
+        content_size: parse_content_size(header.frameContentSize)
+            .ok()
+            .flatten(),
+        window_size: header.windowSize,
+        block_size_max: header.blockSizeMax,
+        frame_type,
+        header_size: header.headerSize,
+        dict_id: NonZeroU32::new(header.dictID),
+        checksum_flag: header.checksumFlag != 0,
+        single_segment,
+    })
+}
+
+/// Wraps the `ZSTD_writeSkippableFrame()` function.
+///
+/// Writes a skippable frame holding `src` as user data to `dst`.
+///
+/// `magic_variant` selects the magic number used (`MAGIC_SKIPPABLE_START +
+/// magic_variant`), and must be in the `0..=15` range.
+///
+/// Returns the number of bytes written.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub fn write_skippable_frame<C: WriteBuf + ?Sized>(
+    dst: &mut C,
+    src: &[u8],
+    magic_variant: u32,
+) -> SafeResult {
+    // Safety: ZSTD_writeSkippableFrame indeed returns how many bytes have been written.
+    unsafe {
+        dst.write_from(|buffer, capacity| {
+            parse_code(zstd_sys::ZSTD_writeSkippableFrame(
+                buffer,
+                capacity,
+                ptr_void(src),
+                src.len(),
+                magic_variant,
+            ))
+        })
+    }
+}
+
+/// Wraps the `ZSTD_readSkippableFrame()` function.
+///
+/// Reads the user data of the skippable frame at the beginning of `src`
+/// into `dst`.
+///
+/// Returns the number of bytes written, and the magic variant of the frame.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub fn read_skippable_frame<C: WriteBuf + ?Sized>(
+    dst: &mut C,
+    src: &[u8],
+) -> Result<(usize, u32), ErrorCode> {
+    let mut magic_variant = 0;
+    // Safety: ZSTD_readSkippableFrame indeed returns how many bytes have been written.
+    let written = unsafe {
+        dst.write_from(|buffer, capacity| {
+            parse_code(zstd_sys::ZSTD_readSkippableFrame(
+                buffer,
+                capacity,
+                &mut magic_variant,
+                ptr_void(src),
+                src.len(),
+            ))
+        })
+    }?;
+    Ok((written, magic_variant))
+}
+
+/// Wraps the `ZSTD_isSkippableFrame()` function.
+///
+/// Returns `true` if `buffer` starts with the magic number of a skippable
+/// frame.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub fn is_skippable_frame(buffer: &[u8]) -> bool {
+    unsafe {
+        zstd_sys::ZSTD_isSkippableFrame(ptr_void(buffer), buffer.len()) > 0
+    }
+}
+
 /// What kind of context reset should be applied.
 pub enum ResetDirective {
     /// Only the session will be reset.
@@ -2073,6 +3785,59 @@ pub enum DictAttachPref {
     ForceLoad = zstd_sys::ZSTD_dictAttachPref_e::ZSTD_dictForceLoad as u32,
 }
 
+/// How the content of a dictionary is interpreted.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum DictContentType {
+    /// A full dictionary if it starts with `MAGIC_DICTIONARY`, raw content
+    /// otherwise.
+    Auto = zstd_sys::ZSTD_dictContentType_e::ZSTD_dct_auto as u32,
+
+    /// Always raw content, even if it starts with `MAGIC_DICTIONARY`.
+    RawContent = zstd_sys::ZSTD_dictContentType_e::ZSTD_dct_rawContent as u32,
+
+    /// Always a full dictionary. Anything else is an error.
+    FullDict = zstd_sys::ZSTD_dictContentType_e::ZSTD_dct_fullDict as u32,
+}
+
+#[cfg(feature = "experimental")]
+impl DictContentType {
+    fn as_sys(self) -> zstd_sys::ZSTD_dictContentType_e {
+        use zstd_sys::ZSTD_dictContentType_e::*;
+        match self {
+            DictContentType::Auto => ZSTD_dct_auto,
+            DictContentType::RawContent => ZSTD_dct_rawContent,
+            DictContentType::FullDict => ZSTD_dct_fullDict,
+        }
+    }
+}
+
+/// How a dictionary is loaded.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum DictLoadMethod {
+    /// The content is copied internally.
+    ByCopy = zstd_sys::ZSTD_dictLoadMethod_e::ZSTD_dlm_byCopy as u32,
+
+    /// The content is referenced, and must outlive its users.
+    ByRef = zstd_sys::ZSTD_dictLoadMethod_e::ZSTD_dlm_byRef as u32,
+}
+
+#[cfg(feature = "experimental")]
+impl DictLoadMethod {
+    fn as_sys(self) -> zstd_sys::ZSTD_dictLoadMethod_e {
+        use zstd_sys::ZSTD_dictLoadMethod_e::*;
+        match self {
+            DictLoadMethod::ByCopy => ZSTD_dlm_byCopy,
+            DictLoadMethod::ByRef => ZSTD_dlm_byRef,
+        }
+    }
+}
+
 #[cfg(feature = "experimental")]
 #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -2083,6 +3848,14 @@ pub enum ParamSwitch {
     Disable = zstd_sys::ZSTD_paramSwitch_e::ZSTD_ps_disable as u32,
 }
 
+/// Converts a `ZSTD_bounds` into an inclusive range.
+fn parse_bounds(
+    bounds: zstd_sys::ZSTD_bounds,
+) -> Result<RangeInclusive<i32>, ErrorCode> {
+    parse_code(bounds.error)?;
+    Ok(bounds.lowerBound..=bounds.upperBound)
+}
+
 /// A compression parameter.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[non_exhaustive]
@@ -2238,60 +4011,930 @@ pub enum CParameter {
     OverlapSizeLog(u32),
 }
 
-/// A decompression parameter.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-#[non_exhaustive]
-pub enum DParameter {
-    WindowLogMax(u32),
+impl CParameter {
+    /// Wraps the `ZSTD_cParam_getBounds()` function.
+    ///
+    /// Returns the range of values accepted by this parameter. The value it
+    /// holds is ignored.
+    pub fn bounds(&self) -> Result<RangeInclusive<i32>, ErrorCode> {
+        let (param, _) = self.to_raw();
 
-    #[cfg(feature = "experimental")]
-    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
-    /// See `FrameFormat`.
-    Format(FrameFormat),
+        // Safety: Just FFI
+        parse_bounds(unsafe { zstd_sys::ZSTD_cParam_getBounds(param) })
+    }
 
-    #[cfg(feature = "experimental")]
-    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
-    StableOutBuffer(bool),
+    /// Returns the raw parameter and value to give to zstd.
+    fn to_raw(self) -> (zstd_sys::ZSTD_cParameter, c_int) {
+        // TODO: Until bindgen properly generates a binding for this, we'll need to do it here.
 
-    #[cfg(feature = "experimental")]
-    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
-    ForceIgnoreChecksum(bool),
+        #[cfg(feature = "experimental")]
+        use zstd_sys::ZSTD_cParameter::{
+            ZSTD_c_experimentalParam1 as ZSTD_c_rsyncable,
+            ZSTD_c_experimentalParam10 as ZSTD_c_stableOutBuffer,
+            ZSTD_c_experimentalParam11 as ZSTD_c_blockDelimiters,
+            ZSTD_c_experimentalParam12 as ZSTD_c_validateSequences,
+            ZSTD_c_experimentalParam13 as ZSTD_c_useBlockSplitter,
+            ZSTD_c_experimentalParam14 as ZSTD_c_useRowMatchFinder,
+            ZSTD_c_experimentalParam15 as ZSTD_c_deterministicRefPrefix,
+            ZSTD_c_experimentalParam16 as ZSTD_c_prefetchCDictTables,
+            ZSTD_c_experimentalParam17 as ZSTD_c_enableSeqProducerFallback,
+            ZSTD_c_experimentalParam18 as ZSTD_c_maxBlockSize,
+            ZSTD_c_experimentalParam19 as ZSTD_c_searchForExternalRepcodes,
+            ZSTD_c_experimentalParam2 as ZSTD_c_format,
+            ZSTD_c_experimentalParam3 as ZSTD_c_forceMaxWindow,
+            ZSTD_c_experimentalParam4 as ZSTD_c_forceAttachDict,
+            ZSTD_c_experimentalParam5 as ZSTD_c_literalCompressionMode,
+            ZSTD_c_experimentalParam7 as ZSTD_c_srcSizeHint,
+            ZSTD_c_experimentalParam8 as ZSTD_c_enableDedicatedDictSearch,
+            ZSTD_c_experimentalParam9 as ZSTD_c_stableInBuffer,
+        };
 
-    #[cfg(feature = "experimental")]
-    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+        use zstd_sys::ZSTD_cParameter::*;
+        use CParameter::*;
+
+        match self {
+            #[cfg(feature = "experimental")]
+            RSyncable(rsyncable) => (ZSTD_c_rsyncable, rsyncable as c_int),
+            #[cfg(feature = "experimental")]
+            Format(format) => (ZSTD_c_format, format as c_int),
+            #[cfg(feature = "experimental")]
+            ForceMaxWindow(force) => (ZSTD_c_forceMaxWindow, force as c_int),
+            #[cfg(feature = "experimental")]
+            ForceAttachDict(force) => (ZSTD_c_forceAttachDict, force as c_int),
+            #[cfg(feature = "experimental")]
+            LiteralCompressionMode(mode) => {
+                (ZSTD_c_literalCompressionMode, mode as c_int)
+            }
+            #[cfg(feature = "experimental")]
+            SrcSizeHint(value) => (ZSTD_c_srcSizeHint, value as c_int),
+            #[cfg(feature = "experimental")]
+            EnableDedicatedDictSearch(enable) => {
+                (ZSTD_c_enableDedicatedDictSearch, enable as c_int)
+            }
+            #[cfg(feature = "experimental")]
+            StableInBuffer(stable) => (ZSTD_c_stableInBuffer, stable as c_int),
+            #[cfg(feature = "experimental")]
+            StableOutBuffer(stable) => {
+                (ZSTD_c_stableOutBuffer, stable as c_int)
+            }
+            #[cfg(feature = "experimental")]
+            BlockDelimiters(value) => (ZSTD_c_blockDelimiters, value as c_int),
+            #[cfg(feature = "experimental")]
+            ValidateSequences(validate) => {
+                (ZSTD_c_validateSequences, validate as c_int)
+            }
+            #[cfg(feature = "experimental")]
+            UseBlockSplitter(split) => {
+                (ZSTD_c_useBlockSplitter, split as c_int)
+            }
+            #[cfg(feature = "experimental")]
+            UseRowMatchFinder(mode) => {
+                (ZSTD_c_useRowMatchFinder, mode as c_int)
+            }
+            #[cfg(feature = "experimental")]
+            DeterministicRefPrefix(deterministic) => {
+                (ZSTD_c_deterministicRefPrefix, deterministic as c_int)
+            }
+            #[cfg(feature = "experimental")]
+            PrefetchCDictTables(prefetch) => {
+                (ZSTD_c_prefetchCDictTables, prefetch as c_int)
+            }
+            #[cfg(feature = "experimental")]
+            EnableSeqProducerFallback(enable) => {
+                (ZSTD_c_enableSeqProducerFallback, enable as c_int)
+            }
+            #[cfg(feature = "experimental")]
+            MaxBlockSize(value) => (ZSTD_c_maxBlockSize, value as c_int),
+            #[cfg(feature = "experimental")]
+            SearchForExternalRepcodes(value) => {
+                (ZSTD_c_searchForExternalRepcodes, value as c_int)
+            }
+            TargetCBlockSize(value) => {
+                (ZSTD_c_targetCBlockSize, value as c_int)
+            }
+            CompressionLevel(level) => (ZSTD_c_compressionLevel, level),
+            WindowLog(value) => (ZSTD_c_windowLog, value as c_int),
+            HashLog(value) => (ZSTD_c_hashLog, value as c_int),
+            ChainLog(value) => (ZSTD_c_chainLog, value as c_int),
+            SearchLog(value) => (ZSTD_c_searchLog, value as c_int),
+            MinMatch(value) => (ZSTD_c_minMatch, value as c_int),
+            TargetLength(value) => (ZSTD_c_targetLength, value as c_int),
+            Strategy(strategy) => (ZSTD_c_strategy, strategy as c_int),
+            EnableLongDistanceMatching(flag) => {
+                (ZSTD_c_enableLongDistanceMatching, flag as c_int)
+            }
+            LdmHashLog(value) => (ZSTD_c_ldmHashLog, value as c_int),
+            LdmMinMatch(value) => (ZSTD_c_ldmMinMatch, value as c_int),
+            LdmBucketSizeLog(value) => {
+                (ZSTD_c_ldmBucketSizeLog, value as c_int)
+            }
+            LdmHashRateLog(value) => (ZSTD_c_ldmHashRateLog, value as c_int),
+            ContentSizeFlag(flag) => (ZSTD_c_contentSizeFlag, flag as c_int),
+            ChecksumFlag(flag) => (ZSTD_c_checksumFlag, flag as c_int),
+            DictIdFlag(flag) => (ZSTD_c_dictIDFlag, flag as c_int),
+
+            NbWorkers(value) => (ZSTD_c_nbWorkers, value as c_int),
+
+            JobSize(value) => (ZSTD_c_jobSize, value as c_int),
+
+            OverlapSizeLog(value) => (ZSTD_c_overlapLog, value as c_int),
+        }
+    }
+}
+
+/// The parameters controlling the compression algorithm.
+///
+/// Unlike a compression level, this describes exactly how data will be
+/// compressed. Use `CompressionParameters::new` to see which parameters a
+/// level translates into, then tweak individual fields.
+///
+/// See the `CParameter` variants of the same name for the meaning of each
+/// field.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CompressionParameters {
+    /// Maximum allowed back-reference distance, as a power of 2.
+    pub window_log: u32,
+
+    /// Size of the multi-probe search table, as a power of 2.
+    pub chain_log: u32,
+
+    /// Size of the initial probe table, as a power of 2.
+    pub hash_log: u32,
+
+    /// Number of search attempts, as a power of 2.
+    pub search_log: u32,
+
+    /// Minimum size of searched matches.
+    pub min_match: u32,
+
+    /// Impact depends on the strategy: see `CParameter::TargetLength`.
+    pub target_length: u32,
+
+    /// Match finder strategy.
+    pub strategy: Strategy,
+}
+
+#[cfg(feature = "experimental")]
+impl CompressionParameters {
+    /// Wraps the `ZSTD_getCParams()` function.
+    ///
+    /// Returns the parameters used by `compression_level` for an input of
+    /// about `src_size_hint` bytes (`None` if unknown), compressed with a
+    /// dictionary of `dict_size` bytes (`0` if none).
+    pub fn new(
+        compression_level: CompressionLevel,
+        src_size_hint: Option<u64>,
+        dict_size: usize,
+    ) -> Self {
+        // Safety: Just FFI
+        unsafe {
+            zstd_sys::ZSTD_getCParams(
+                compression_level,
+                src_size_hint.unwrap_or(CONTENTSIZE_UNKNOWN),
+                dict_size,
+            )
+        }
+        .into()
+    }
+
+    /// Checks that each field is within the bounds of its `CParameter`.
+    ///
+    /// This is what `ZSTD_checkCParams()` does, except the first field out
+    /// of range is returned as an error, as the `CParameter` holding its
+    /// value.
+    pub fn check(&self) -> Result<(), CParameter> {
+        let fields = [
+            CParameter::WindowLog(self.window_log),
+            CParameter::ChainLog(self.chain_log),
+            CParameter::HashLog(self.hash_log),
+            CParameter::SearchLog(self.search_log),
+            CParameter::MinMatch(self.min_match),
+            CParameter::TargetLength(self.target_length),
+            CParameter::Strategy(self.strategy),
+        ];
+        for &field in &fields {
+            let (_, value) = field.to_raw();
+            match field.bounds() {
+                Ok(bounds) if bounds.contains(&value) => (),
+                _ => return Err(field),
+            }
+        }
+        Ok(())
+    }
+
+    /// Wraps the `ZSTD_adjustCParams()` function.
+    ///
+    /// Returns parameters optimized for an input of `src_size` bytes (`None`
+    /// if unknown) and a dictionary of `dict_size` bytes (`0` if none).
+    ///
+    /// Out-of-range fields are clamped to their valid range.
+    #[must_use]
+    pub fn adjust(self, src_size: Option<u64>, dict_size: usize) -> Self {
+        // Safety: Just FFI
+        unsafe {
+            zstd_sys::ZSTD_adjustCParams(
+                self.into(),
+                src_size.unwrap_or(CONTENTSIZE_UNKNOWN),
+                dict_size,
+            )
+        }
+        .into()
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl From<zstd_sys::ZSTD_compressionParameters> for CompressionParameters {
+    fn from(params: zstd_sys::ZSTD_compressionParameters) -> Self {
+        CompressionParameters {
+            window_log: params.windowLog,
+            chain_log: params.chainLog,
+            hash_log: params.hashLog,
+            search_log: params.searchLog,
+            min_match: params.minMatch,
+            target_length: params.targetLength,
+            strategy: params.strategy,
+        }
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl From<CompressionParameters> for zstd_sys::ZSTD_compressionParameters {
+    fn from(params: CompressionParameters) -> Self {
+        zstd_sys::ZSTD_compressionParameters {
+            windowLog: params.window_log,
+            chainLog: params.chain_log,
+            hashLog: params.hash_log,
+            searchLog: params.search_log,
+            minMatch: params.min_match,
+            targetLength: params.target_length,
+            strategy: params.strategy,
+        }
+    }
+}
+
+/// The parameters controlling the frame format.
+///
+/// The default values match the ones of a new `CCtx`.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FrameParameters {
+    /// Whether the content size is written in the frame header, when known.
+    pub content_size_flag: bool,
+
+    /// Whether a checksum of the content is written at the end of the frame.
+    pub checksum_flag: bool,
+
+    /// Whether the dictionary ID is written in the frame header.
+    pub dict_id_flag: bool,
+}
+
+#[cfg(feature = "experimental")]
+impl Default for FrameParameters {
+    fn default() -> Self {
+        FrameParameters {
+            content_size_flag: true,
+            checksum_flag: false,
+            dict_id_flag: true,
+        }
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl From<zstd_sys::ZSTD_frameParameters> for FrameParameters {
+    fn from(params: zstd_sys::ZSTD_frameParameters) -> Self {
+        FrameParameters {
+            content_size_flag: params.contentSizeFlag != 0,
+            checksum_flag: params.checksumFlag != 0,
+            dict_id_flag: params.noDictIDFlag == 0,
+        }
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl From<FrameParameters> for zstd_sys::ZSTD_frameParameters {
+    fn from(params: FrameParameters) -> Self {
+        zstd_sys::ZSTD_frameParameters {
+            contentSizeFlag: params.content_size_flag as c_int,
+            checksumFlag: params.checksum_flag as c_int,
+            noDictIDFlag: (!params.dict_id_flag) as c_int,
+        }
+    }
+}
+
+/// Both compression and frame parameters.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Parameters {
+    /// Parameters controlling the compression algorithm.
+    pub compression: CompressionParameters,
+
+    /// Parameters controlling the frame format.
+    pub frame: FrameParameters,
+}
+
+#[cfg(feature = "experimental")]
+impl Parameters {
+    /// Wraps the `ZSTD_getParams()` function.
+    ///
+    /// Same as `CompressionParameters::new`, with default frame parameters.
+    pub fn new(
+        compression_level: CompressionLevel,
+        src_size_hint: Option<u64>,
+        dict_size: usize,
+    ) -> Self {
+        // Safety: Just FFI
+        unsafe {
+            zstd_sys::ZSTD_getParams(
+                compression_level,
+                src_size_hint.unwrap_or(CONTENTSIZE_UNKNOWN),
+                dict_size,
+            )
+        }
+        .into()
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl From<zstd_sys::ZSTD_parameters> for Parameters {
+    fn from(params: zstd_sys::ZSTD_parameters) -> Self {
+        Parameters {
+            compression: params.cParams.into(),
+            frame: params.fParams.into(),
+        }
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl From<Parameters> for zstd_sys::ZSTD_parameters {
+    fn from(params: Parameters) -> Self {
+        zstd_sys::ZSTD_parameters {
+            cParams: params.compression.into(),
+            fParams: params.frame.into(),
+        }
+    }
+}
+
+/// A decompression parameter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DParameter {
+    WindowLogMax(u32),
+
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    /// See `FrameFormat`.
+    Format(FrameFormat),
+
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    StableOutBuffer(bool),
+
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+    ForceIgnoreChecksum(bool),
+
+    #[cfg(feature = "experimental")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
     RefMultipleDDicts(bool),
 }
 
-/// Wraps the `ZDICT_trainFromBuffer()` function.
-#[cfg(feature = "zdict_builder")]
-#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "zdict_builder")))]
-pub fn train_from_buffer<C: WriteBuf + ?Sized>(
+impl DParameter {
+    /// Wraps the `ZSTD_dParam_getBounds()` function.
+    ///
+    /// Returns the range of values accepted by this parameter. The value it
+    /// holds is ignored.
+    pub fn bounds(&self) -> Result<RangeInclusive<i32>, ErrorCode> {
+        let (param, _) = self.to_raw();
+
+        // Safety: Just FFI
+        parse_bounds(unsafe { zstd_sys::ZSTD_dParam_getBounds(param) })
+    }
+
+    /// Returns the raw parameter and value to give to zstd.
+    fn to_raw(self) -> (zstd_sys::ZSTD_dParameter, c_int) {
+        #[cfg(feature = "experimental")]
+        use zstd_sys::ZSTD_dParameter::{
+            ZSTD_d_experimentalParam1 as ZSTD_d_format,
+            ZSTD_d_experimentalParam2 as ZSTD_d_stableOutBuffer,
+            ZSTD_d_experimentalParam3 as ZSTD_d_forceIgnoreChecksum,
+            ZSTD_d_experimentalParam4 as ZSTD_d_refMultipleDDicts,
+        };
+
+        use zstd_sys::ZSTD_dParameter::*;
+        use DParameter::*;
+
+        match self {
+            #[cfg(feature = "experimental")]
+            Format(format) => (ZSTD_d_format, format as c_int),
+            #[cfg(feature = "experimental")]
+            StableOutBuffer(stable) => {
+                (ZSTD_d_stableOutBuffer, stable as c_int)
+            }
+            #[cfg(feature = "experimental")]
+            ForceIgnoreChecksum(force) => {
+                (ZSTD_d_forceIgnoreChecksum, force as c_int)
+            }
+            #[cfg(feature = "experimental")]
+            RefMultipleDDicts(value) => {
+                (ZSTD_d_refMultipleDDicts, value as c_int)
+            }
+
+            WindowLogMax(value) => (ZSTD_d_windowLogMax, value as c_int),
+        }
+    }
+}
+
+/// Wraps the `ZDICT_trainFromBuffer()` function.
+#[cfg(feature = "zdict_builder")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "zdict_builder")))]
+pub fn train_from_buffer<C: WriteBuf + ?Sized>(
+    dict_buffer: &mut C,
+    samples_buffer: &[u8],
+    samples_sizes: &[usize],
+) -> SafeResult {
+    assert_eq!(samples_buffer.len(), samples_sizes.iter().sum());
+
+    unsafe {
+        dict_buffer.write_from(|buffer, capacity| {
+            parse_code(zstd_sys::ZDICT_trainFromBuffer(
+                buffer,
+                capacity,
+                ptr_void(samples_buffer),
+                samples_sizes.as_ptr(),
+                samples_sizes.len() as u32,
+            ))
+        })
+    }
+}
+
+/// Wraps the `ZDICT_getDictID()` function.
+#[cfg(feature = "zdict_builder")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "zdict_builder")))]
+pub fn get_dict_id(dict_buffer: &[u8]) -> Option<NonZeroU32> {
+    NonZeroU32::new(unsafe {
+        zstd_sys::ZDICT_getDictID(ptr_void(dict_buffer), dict_buffer.len())
+    })
+}
+
+/// Wraps the `ZDICT_getDictHeaderSize()` function.
+///
+/// Returns the size of the header and entropy tables of a full dictionary.
+/// Fails if `dict_buffer` is not a valid full dictionary.
+#[cfg(feature = "zdict_builder")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "zdict_builder")))]
+pub fn get_dict_header_size(dict_buffer: &[u8]) -> SafeResult {
+    // Safety: Just FFI
+    parse_code(unsafe {
+        zstd_sys::ZDICT_getDictHeaderSize(
+            ptr_void(dict_buffer),
+            dict_buffer.len(),
+        )
+    })
+}
+
+/// Common parameters for dictionary training and finalization.
+///
+/// The default values let zstd pick everything.
+#[cfg(feature = "zdict_builder")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "zdict_builder")))]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DictParameters {
+    /// Compression level the dictionary is tuned for. `0` means default.
+    pub compression_level: CompressionLevel,
+
+    /// Verbosity of the logs written to stderr. `0` means none.
+    pub notification_level: u32,
+
+    /// Forces the dictionary ID. `0` means it is derived from a hash of the
+    /// dictionary content.
+    pub dict_id: u32,
+}
+
+#[cfg(feature = "zdict_builder")]
+impl From<zstd_sys::ZDICT_params_t> for DictParameters {
+    fn from(params: zstd_sys::ZDICT_params_t) -> Self {
+        DictParameters {
+            compression_level: params.compressionLevel,
+            notification_level: params.notificationLevel,
+            dict_id: params.dictID,
+        }
+    }
+}
+
+#[cfg(feature = "zdict_builder")]
+impl From<DictParameters> for zstd_sys::ZDICT_params_t {
+    fn from(params: DictParameters) -> Self {
+        zstd_sys::ZDICT_params_t {
+            compressionLevel: params.compression_level,
+            notificationLevel: params.notification_level,
+            dictID: params.dict_id,
+        }
+    }
+}
+
+/// Wraps the `ZDICT_finalizeDictionary()` function.
+///
+/// Turns `dict_content` into a dictionary, adding a header and entropy
+/// tables computed from the samples. If everything doesn't fit in
+/// `dict_buffer`, the beginning of the content is dropped.
+#[cfg(feature = "zdict_builder")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "zdict_builder")))]
+pub fn finalize_dictionary<C: WriteBuf + ?Sized>(
+    dict_buffer: &mut C,
+    dict_content: &[u8],
+    samples_buffer: &[u8],
+    samples_sizes: &[usize],
+    parameters: &DictParameters,
+) -> SafeResult {
+    assert_eq!(samples_buffer.len(), samples_sizes.iter().sum());
+
+    unsafe {
+        dict_buffer.write_from(|buffer, capacity| {
+            parse_code(zstd_sys::ZDICT_finalizeDictionary(
+                buffer,
+                capacity,
+                ptr_void(dict_content),
+                dict_content.len(),
+                ptr_void(samples_buffer),
+                samples_sizes.as_ptr(),
+                samples_sizes.len() as u32,
+                (*parameters).into(),
+            ))
+        })
+    }
+}
+
+/// Wraps the `ZDICT_addEntropyTablesFromBuffer()` function.
+///
+/// The dictionary content must be in the last `dict_content_size` bytes of
+/// `dict_buffer`. On success, the complete dictionary is written at the
+/// beginning of `dict_buffer`, and its size is returned.
+///
+/// Returns a `dstSize_tooSmall` error if `dict_buffer` is shorter than
+/// `ZDICT_DICTSIZE_MIN` bytes.
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "experimental", feature = "zdict_builder")))
+)]
+pub fn add_entropy_tables_from_buffer(
+    dict_buffer: &mut [u8],
+    dict_content_size: usize,
+    samples_buffer: &[u8],
+    samples_sizes: &[usize],
+) -> SafeResult {
+    assert!(dict_content_size <= dict_buffer.len());
+    assert_eq!(samples_buffer.len(), samples_sizes.iter().sum());
+
+    // zstd writes the header without checking the buffer size.
+    if dict_buffer.len() < zstd_sys::ZDICT_DICTSIZE_MIN as usize {
+        return Err(error_code(
+            zstd_sys::ZSTD_ErrorCode::ZSTD_error_dstSize_tooSmall,
+        ));
+    }
+
+    // Safety: Just FFI
+    parse_code(unsafe {
+        zstd_sys::ZDICT_addEntropyTablesFromBuffer(
+            dict_buffer.as_mut_ptr().cast(),
+            dict_content_size,
+            dict_buffer.len(),
+            ptr_void(samples_buffer),
+            samples_sizes.as_ptr(),
+            samples_sizes.len() as u32,
+        )
+    })
+}
+
+/// Parameters for the COVER dictionary training algorithm.
+///
+/// For every field, `0` means default, except for `k` and `d` which are
+/// required for training without optimization.
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "experimental", feature = "zdict_builder")))
+)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct CoverParameters {
+    /// Segment size.
+    pub k: u32,
+
+    /// Dmer size.
+    pub d: u32,
+
+    /// Number of steps tried for `k` when optimizing.
+    pub steps: u32,
+
+    /// Number of threads used when optimizing.
+    pub nb_threads: u32,
+
+    /// Fraction of the samples used for training when optimizing, the rest
+    /// being used for testing. `0.0` means default (`1.0`).
+    pub split_point: f64,
+
+    /// Whether to try smaller dictionaries when optimizing.
+    pub shrink_dict: bool,
+
+    /// Maximum regression, in percent, accepted for a smaller dictionary.
+    pub shrink_dict_max_regression: u32,
+
+    /// Common dictionary parameters.
+    pub dict: DictParameters,
+}
+
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+impl From<zstd_sys::ZDICT_cover_params_t> for CoverParameters {
+    fn from(params: zstd_sys::ZDICT_cover_params_t) -> Self {
+        CoverParameters {
+            k: params.k,
+            d: params.d,
+            steps: params.steps,
+            nb_threads: params.nbThreads,
+            split_point: params.splitPoint,
+            shrink_dict: params.shrinkDict != 0,
+            shrink_dict_max_regression: params.shrinkDictMaxRegression,
+            dict: params.zParams.into(),
+        }
+    }
+}
+
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+impl From<CoverParameters> for zstd_sys::ZDICT_cover_params_t {
+    fn from(params: CoverParameters) -> Self {
+        zstd_sys::ZDICT_cover_params_t {
+            k: params.k,
+            d: params.d,
+            steps: params.steps,
+            nbThreads: params.nb_threads,
+            splitPoint: params.split_point,
+            shrinkDict: params.shrink_dict as u32,
+            shrinkDictMaxRegression: params.shrink_dict_max_regression,
+            zParams: params.dict.into(),
+        }
+    }
+}
+
+/// Parameters for the FastCover dictionary training algorithm.
+///
+/// See `CoverParameters` for the fields shared with COVER.
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "experimental", feature = "zdict_builder")))
+)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FastCoverParameters {
+    /// Segment size.
+    pub k: u32,
+
+    /// Dmer size.
+    pub d: u32,
+
+    /// Log of the size of the frequency array. `0` means default (`20`).
+    pub f: u32,
+
+    /// Number of steps tried for `k` when optimizing.
+    pub steps: u32,
+
+    /// Number of threads used when optimizing.
+    pub nb_threads: u32,
+
+    /// Fraction of the samples used for training when optimizing.
+    pub split_point: f64,
+
+    /// Acceleration level, from 1 to 10. `0` means default (`1`).
+    pub accel: u32,
+
+    /// Whether to try smaller dictionaries when optimizing.
+    pub shrink_dict: bool,
+
+    /// Maximum regression, in percent, accepted for a smaller dictionary.
+    pub shrink_dict_max_regression: u32,
+
+    /// Common dictionary parameters.
+    pub dict: DictParameters,
+}
+
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+impl From<zstd_sys::ZDICT_fastCover_params_t> for FastCoverParameters {
+    fn from(params: zstd_sys::ZDICT_fastCover_params_t) -> Self {
+        FastCoverParameters {
+            k: params.k,
+            d: params.d,
+            f: params.f,
+            steps: params.steps,
+            nb_threads: params.nbThreads,
+            split_point: params.splitPoint,
+            accel: params.accel,
+            shrink_dict: params.shrinkDict != 0,
+            shrink_dict_max_regression: params.shrinkDictMaxRegression,
+            dict: params.zParams.into(),
+        }
+    }
+}
+
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+impl From<FastCoverParameters> for zstd_sys::ZDICT_fastCover_params_t {
+    fn from(params: FastCoverParameters) -> Self {
+        zstd_sys::ZDICT_fastCover_params_t {
+            k: params.k,
+            d: params.d,
+            f: params.f,
+            steps: params.steps,
+            nbThreads: params.nb_threads,
+            splitPoint: params.split_point,
+            accel: params.accel,
+            shrinkDict: params.shrink_dict as u32,
+            shrinkDictMaxRegression: params.shrink_dict_max_regression,
+            zParams: params.dict.into(),
+        }
+    }
+}
+
+/// Parameters for the legacy dictionary training algorithm.
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "experimental", feature = "zdict_builder")))
+)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LegacyParameters {
+    /// Selectivity level. `0` means default.
+    pub selectivity_level: u32,
+
+    /// Common dictionary parameters.
+    pub dict: DictParameters,
+}
+
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+impl From<LegacyParameters> for zstd_sys::ZDICT_legacy_params_t {
+    fn from(params: LegacyParameters) -> Self {
+        zstd_sys::ZDICT_legacy_params_t {
+            selectivityLevel: params.selectivity_level,
+            zParams: params.dict.into(),
+        }
+    }
+}
+
+/// Wraps the `ZDICT_trainFromBuffer_cover()` function.
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "experimental", feature = "zdict_builder")))
+)]
+pub fn train_from_buffer_cover<C: WriteBuf + ?Sized>(
     dict_buffer: &mut C,
     samples_buffer: &[u8],
     samples_sizes: &[usize],
+    parameters: &CoverParameters,
 ) -> SafeResult {
     assert_eq!(samples_buffer.len(), samples_sizes.iter().sum());
 
     unsafe {
         dict_buffer.write_from(|buffer, capacity| {
-            parse_code(zstd_sys::ZDICT_trainFromBuffer(
+            parse_code(zstd_sys::ZDICT_trainFromBuffer_cover(
                 buffer,
                 capacity,
                 ptr_void(samples_buffer),
                 samples_sizes.as_ptr(),
                 samples_sizes.len() as u32,
+                (*parameters).into(),
             ))
         })
     }
 }
 
-/// Wraps the `ZDICT_getDictID()` function.
-#[cfg(feature = "zdict_builder")]
-#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "zdict_builder")))]
-pub fn get_dict_id(dict_buffer: &[u8]) -> Option<NonZeroU32> {
-    NonZeroU32::new(unsafe {
-        zstd_sys::ZDICT_getDictID(ptr_void(dict_buffer), dict_buffer.len())
-    })
+/// Wraps the `ZDICT_optimizeTrainFromBuffer_cover()` function.
+///
+/// Fields of `parameters` left to `0` are searched for. On success,
+/// `parameters` holds the values that were picked.
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "experimental", feature = "zdict_builder")))
+)]
+pub fn optimize_train_from_buffer_cover<C: WriteBuf + ?Sized>(
+    dict_buffer: &mut C,
+    samples_buffer: &[u8],
+    samples_sizes: &[usize],
+    parameters: &mut CoverParameters,
+) -> SafeResult {
+    assert_eq!(samples_buffer.len(), samples_sizes.iter().sum());
+
+    let mut raw_parameters = (*parameters).into();
+    let result = unsafe {
+        dict_buffer.write_from(|buffer, capacity| {
+            parse_code(zstd_sys::ZDICT_optimizeTrainFromBuffer_cover(
+                buffer,
+                capacity,
+                ptr_void(samples_buffer),
+                samples_sizes.as_ptr(),
+                samples_sizes.len() as u32,
+                &mut raw_parameters,
+            ))
+        })
+    };
+    if result.is_ok() {
+        *parameters = raw_parameters.into();
+    }
+    result
+}
+
+/// Wraps the `ZDICT_trainFromBuffer_fastCover()` function.
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "experimental", feature = "zdict_builder")))
+)]
+pub fn train_from_buffer_fast_cover<C: WriteBuf + ?Sized>(
+    dict_buffer: &mut C,
+    samples_buffer: &[u8],
+    samples_sizes: &[usize],
+    parameters: &FastCoverParameters,
+) -> SafeResult {
+    assert_eq!(samples_buffer.len(), samples_sizes.iter().sum());
+
+    unsafe {
+        dict_buffer.write_from(|buffer, capacity| {
+            parse_code(zstd_sys::ZDICT_trainFromBuffer_fastCover(
+                buffer,
+                capacity,
+                ptr_void(samples_buffer),
+                samples_sizes.as_ptr(),
+                samples_sizes.len() as u32,
+                (*parameters).into(),
+            ))
+        })
+    }
+}
+
+/// Wraps the `ZDICT_optimizeTrainFromBuffer_fastCover()` function.
+///
+/// Fields of `parameters` left to `0` are searched for. On success,
+/// `parameters` holds the values that were picked.
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "experimental", feature = "zdict_builder")))
+)]
+pub fn optimize_train_from_buffer_fast_cover<C: WriteBuf + ?Sized>(
+    dict_buffer: &mut C,
+    samples_buffer: &[u8],
+    samples_sizes: &[usize],
+    parameters: &mut FastCoverParameters,
+) -> SafeResult {
+    assert_eq!(samples_buffer.len(), samples_sizes.iter().sum());
+
+    let mut raw_parameters = (*parameters).into();
+    let result = unsafe {
+        dict_buffer.write_from(|buffer, capacity| {
+            parse_code(zstd_sys::ZDICT_optimizeTrainFromBuffer_fastCover(
+                buffer,
+                capacity,
+                ptr_void(samples_buffer),
+                samples_sizes.as_ptr(),
+                samples_sizes.len() as u32,
+                &mut raw_parameters,
+            ))
+        })
+    };
+    if result.is_ok() {
+        *parameters = raw_parameters.into();
+    }
+    result
+}
+
+/// Wraps the `ZDICT_trainFromBuffer_legacy()` function.
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "experimental", feature = "zdict_builder")))
+)]
+pub fn train_from_buffer_legacy<C: WriteBuf + ?Sized>(
+    dict_buffer: &mut C,
+    samples_buffer: &[u8],
+    samples_sizes: &[usize],
+    parameters: &LegacyParameters,
+) -> SafeResult {
+    assert_eq!(samples_buffer.len(), samples_sizes.iter().sum());
+
+    unsafe {
+        dict_buffer.write_from(|buffer, capacity| {
+            parse_code(zstd_sys::ZDICT_trainFromBuffer_legacy(
+                buffer,
+                capacity,
+                ptr_void(samples_buffer),
+                samples_sizes.as_ptr(),
+                samples_sizes.len() as u32,
+                (*parameters).into(),
+            ))
+        })
+    }
 }
 
 /// Wraps the `ZSTD_getBlockSize()` function.
@@ -2323,6 +4966,350 @@ pub fn sequence_bound(src_size: usize) -> usize {
     unsafe { zstd_sys::ZSTD_sequenceBound(src_size) }
 }
 
+/// Wraps the `ZSTD_estimateCCtxSize()` function.
+///
+/// Returns the memory needed by a context for single-shot compression (for
+/// example with `CCtx::compress2`), using any level up to
+/// `max_compression_level`.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub fn estimate_cctx_size(max_compression_level: CompressionLevel) -> usize {
+    // Safety: Just FFI
+    unsafe { zstd_sys::ZSTD_estimateCCtxSize(max_compression_level) }
+}
+
+/// Wraps the `ZSTD_estimateDCtxSize()` function.
+///
+/// Returns the memory needed by a context for single-shot decompression.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub fn estimate_dctx_size() -> usize {
+    // Safety: Just FFI
+    unsafe { zstd_sys::ZSTD_estimateDCtxSize() }
+}
+
+/// Wraps the `ZSTD_estimateCCtxSize_usingCParams()` function.
+///
+/// Same as [`estimate_cctx_size`], but for a single compression level, and
+/// with an optional window log override and input size.
+///
+/// Knowing the input size can significantly lower the estimation.
+///
+/// Returns an error if `window_log` is out of bounds.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub fn estimate_cctx_size_for(
+    compression_level: CompressionLevel,
+    window_log: Option<u32>,
+    pledged_src_size: Option<u64>,
+) -> SafeResult {
+    let params =
+        estimate_params(compression_level, window_log, pledged_src_size)?;
+    // Safety: Just FFI
+    Ok(unsafe { zstd_sys::ZSTD_estimateCCtxSize_usingCParams(params) })
+}
+
+/// Wraps the `ZSTD_estimateCStreamSize()` function.
+///
+/// Returns the memory needed by a context for streaming compression, using
+/// any level up to `max_compression_level`.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub fn estimate_cstream_size(
+    max_compression_level: CompressionLevel,
+) -> usize {
+    // Safety: Just FFI
+    unsafe { zstd_sys::ZSTD_estimateCStreamSize(max_compression_level) }
+}
+
+/// Wraps the `ZSTD_estimateCStreamSize_usingCParams()` function.
+///
+/// Same as [`estimate_cstream_size`], but for a single compression level,
+/// and with an optional window log override and input size.
+///
+/// Returns an error if `window_log` is out of bounds.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub fn estimate_cstream_size_for(
+    compression_level: CompressionLevel,
+    window_log: Option<u32>,
+    pledged_src_size: Option<u64>,
+) -> SafeResult {
+    let params =
+        estimate_params(compression_level, window_log, pledged_src_size)?;
+    // Safety: Just FFI
+    Ok(unsafe { zstd_sys::ZSTD_estimateCStreamSize_usingCParams(params) })
+}
+
+/// Compression parameters a context would use for the given settings.
+#[cfg(feature = "experimental")]
+fn estimate_params(
+    compression_level: CompressionLevel,
+    window_log: Option<u32>,
+    pledged_src_size: Option<u64>,
+) -> Result<zstd_sys::ZSTD_compressionParameters, ErrorCode> {
+    let mut params =
+        CompressionParameters::new(compression_level, pledged_src_size, 0);
+    if let Some(window_log) = window_log {
+        params.window_log = window_log;
+    }
+    let params = params.into();
+    // zstd doesn't check the parameters given to estimate functions.
+    // Safety: Just FFI
+    parse_code(unsafe { zstd_sys::ZSTD_checkCParams(params) })?;
+    Ok(params)
+}
+
+/// Wraps the `ZSTD_estimateDStreamSize()` function.
+///
+/// Returns the memory needed by a context for streaming decompression of
+/// frames with a window of at most `max_window_size` bytes.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub fn estimate_dstream_size(max_window_size: usize) -> usize {
+    // Safety: Just FFI
+    unsafe { zstd_sys::ZSTD_estimateDStreamSize(max_window_size) }
+}
+
+/// Wraps the `ZSTD_estimateDStreamSize_fromFrame()` function.
+///
+/// Returns the memory needed by a context for streaming decompression of
+/// the frame starting at the beginning of `src`.
+///
+/// `src` only needs to contain the frame header.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub fn estimate_dstream_size_from_frame(src: &[u8]) -> SafeResult {
+    // Safety: Just FFI
+    let code = unsafe {
+        zstd_sys::ZSTD_estimateDStreamSize_fromFrame(ptr_void(src), src.len())
+    };
+    parse_code(code)
+}
+
+/// Wraps the `ZSTD_estimateCDictSize_advanced()` function.
+///
+/// Returns the memory needed by a `StaticCDict` of `dict_size` bytes.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub fn estimate_cdict_size(
+    dict_size: usize,
+    compression_level: CompressionLevel,
+) -> usize {
+    // Safety: Just FFI
+    unsafe {
+        zstd_sys::ZSTD_estimateCDictSize_advanced(
+            dict_size,
+            cdict_params(dict_size, compression_level),
+            zstd_sys::ZSTD_dictLoadMethod_e::ZSTD_dlm_byCopy,
+        )
+    }
+}
+
+/// Compression parameters used for dictionaries created with a level.
+#[cfg(feature = "experimental")]
+fn cdict_params(
+    dict_size: usize,
+    compression_level: CompressionLevel,
+) -> zstd_sys::ZSTD_compressionParameters {
+    // Safety: Just FFI
+    unsafe { zstd_sys::ZSTD_getCParams(compression_level, 0, dict_size) }
+}
+
+/// Wraps the `ZSTD_estimateDDictSize()` function.
+///
+/// Returns the memory needed by a decompression dictionary of `dict_size`
+/// bytes, copied internally.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub fn estimate_ddict_size(dict_size: usize) -> usize {
+    // Safety: Just FFI
+    unsafe {
+        zstd_sys::ZSTD_estimateDDictSize(
+            dict_size,
+            zstd_sys::ZSTD_dictLoadMethod_e::ZSTD_dlm_byCopy,
+        )
+    }
+}
+
+/// A match found while compressing, as used by the sequence API.
+///
+/// Each sequence copies `literal_length` literal bytes, then `match_length`
+/// bytes from `offset` bytes before the current position.
+///
+/// A sequence with both `offset` and `match_length` set to 0 is a block
+/// delimiter: `literal_length` then counts the last literals of the block.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Sequence {
+    /// Distance to the start of the match.
+    pub offset: u32,
+
+    /// Number of literal bytes preceding the match.
+    pub literal_length: u32,
+
+    /// Length of the match.
+    pub match_length: u32,
+
+    /// Repeat offset code used, if any.
+    ///
+    /// Ignored by `CCtx::compress_sequences`.
+    pub rep: u32,
+}
+
+// `Sequence` is given to zstd in place of `ZSTD_Sequence`.
+#[cfg(feature = "experimental")]
+const _: () = assert!(
+    core::mem::size_of::<Sequence>()
+        == core::mem::size_of::<zstd_sys::ZSTD_Sequence>()
+);
+
+#[cfg(feature = "experimental")]
+impl Sequence {
+    /// Returns `true` if this sequence marks the end of a block.
+    pub fn is_block_delimiter(&self) -> bool {
+        self.offset == 0 && self.match_length == 0
+    }
+}
+
+/// Wraps the `ZSTD_mergeBlockDelimiters()` function.
+///
+/// Removes block delimiters from `sequences`, merging their literals into the
+/// next sequence. The result can be given to `CCtx::compress_sequences` when
+/// `CParameter::BlockDelimiters` is disabled.
+///
+/// Returns the number of sequences left, at the beginning of `sequences`.
+#[cfg(feature = "experimental")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "experimental")))]
+pub fn merge_block_delimiters(sequences: &mut [Sequence]) -> usize {
+    // Safety: `Sequence` has the same layout as `ZSTD_Sequence`.
+    unsafe {
+        zstd_sys::ZSTD_mergeBlockDelimiters(
+            sequences.as_mut_ptr().cast(),
+            sequences.len(),
+        )
+    }
+}
+
+/// A block-level match finder, used in place of the built-in one.
+///
+/// See `CCtx::register_sequence_producer`.
+#[cfg(all(feature = "experimental", feature = "std"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "experimental", feature = "std")))
+)]
+pub trait SequenceProducer {
+    /// Finds the sequences describing a single block.
+    ///
+    /// * `sequences`: where to write the sequences.
+    /// * `src`: the content of the block, at most 128KB.
+    /// * `dict`: currently always empty.
+    /// * `compression_level`: the compression level in use.
+    /// * `window_size`: the maximum offset allowed for matches.
+    ///
+    /// The sequences must cover all of `src`, and end with a block delimiter.
+    ///
+    /// Returns the number of sequences written, or `None` to let the built-in
+    /// match finder handle this block.
+    fn produce_sequences(
+        &mut self,
+        sequences: &mut [Sequence],
+        src: &[u8],
+        dict: &[u8],
+        compression_level: CompressionLevel,
+        window_size: usize,
+    ) -> Option<usize>;
+}
+
+/// Same as `ZSTD_SEQUENCE_PRODUCER_ERROR`.
+#[cfg(all(feature = "experimental", feature = "std"))]
+const SEQUENCE_PRODUCER_ERROR: usize = usize::MAX;
+
+/// Builds a slice from a pointer given by zstd, which may be null if empty.
+///
+/// Safety: `ptr` must be valid for `len` bytes if `len > 0`.
+#[cfg(all(feature = "experimental", feature = "std"))]
+unsafe fn slice_from_raw<'b>(ptr: *const c_void, len: usize) -> &'b [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        core::slice::from_raw_parts(ptr.cast(), len)
+    }
+}
+
+/// Callback given to `ZSTD_registerSequenceProducer()`.
+///
+/// Safety: `state` must point to a valid `P`.
+#[cfg(all(feature = "experimental", feature = "std"))]
+#[allow(clippy::too_many_arguments)]
+unsafe extern "C" fn produce_sequences<P: SequenceProducer>(
+    state: *mut c_void,
+    out_seqs: *mut zstd_sys::ZSTD_Sequence,
+    out_seqs_capacity: usize,
+    src: *const c_void,
+    src_size: usize,
+    dict: *const c_void,
+    dict_size: usize,
+    compression_level: c_int,
+    window_size: usize,
+) -> usize {
+    let producer = &mut *state.cast::<P>();
+    let sequences = if out_seqs_capacity == 0 {
+        &mut []
+    } else {
+        // The buffer given by zstd is not initialized.
+        core::ptr::write_bytes(out_seqs, 0, out_seqs_capacity);
+        core::slice::from_raw_parts_mut(
+            out_seqs.cast::<Sequence>(),
+            out_seqs_capacity,
+        )
+    };
+    let src = slice_from_raw(src, src_size);
+    let dict = slice_from_raw(dict, dict_size);
+
+    // Unwinding into C code is not allowed: report panics as errors.
+    let result =
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            producer.produce_sequences(
+                sequences,
+                src,
+                dict,
+                compression_level,
+                window_size,
+            )
+        }));
+
+    match result {
+        Ok(Some(n))
+            if n <= out_seqs_capacity
+                && sequences_fit(&sequences[..n], src_size) =>
+        {
+            n
+        }
+        _ => SEQUENCE_PRODUCER_ERROR,
+    }
+}
+
+/// Checks that `sequences` cover at most `src_size` bytes, and that every
+/// match is at least 3 bytes long.
+///
+/// zstd only checks the match length if `CParameter::ValidateSequences` is
+/// enabled, and shorter matches corrupt its internal state.
+#[cfg(feature = "experimental")]
+fn sequences_fit(sequences: &[Sequence], src_size: usize) -> bool {
+    let mut covered = 0u64;
+    for seq in sequences {
+        if !seq.is_block_delimiter() && seq.match_length < 3 {
+            return false;
+        }
+        covered += u64::from(seq.literal_length) + u64::from(seq.match_length);
+    }
+    covered <= src_size as u64
+}
+
 /// Returns the minimum extra space when output and input buffer overlap.
 ///
 /// When using in-place decompression, the output buffer must be at least this much bigger (in
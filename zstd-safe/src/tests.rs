@@ -173,3 +173,718 @@ fn test_upper_bound() {
         Ok(INPUT.len() as u64)
     );
 }
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_frame_header() {
+    let mut buffer = std::vec![0u8; 256];
+    let mut cctx = zstd_safe::CCtx::default();
+    cctx.set_parameter(zstd_safe::CParameter::ChecksumFlag(true))
+        .unwrap();
+    let written = cctx.compress2(&mut buffer[..], INPUT).unwrap();
+    let compressed = &buffer[..written];
+
+    let header = zstd_safe::get_frame_header(compressed).unwrap();
+    assert_eq!(header.frame_type, zstd_safe::FrameType::Frame);
+    assert_eq!(header.content_size, Some(INPUT.len() as u64));
+    assert!(header.checksum_flag);
+    assert!(header.single_segment);
+    assert_eq!(header.dict_id, None);
+    assert_eq!(
+        zstd_safe::frame_header_size(compressed),
+        Ok(header.header_size as usize)
+    );
+
+    // A truncated header asks for more data.
+    assert!(matches!(
+        zstd_safe::get_frame_header(&compressed[..2]),
+        Err(zstd_safe::FrameHeaderError::Incomplete(n)) if n > 2
+    ));
+
+    // Without magic number, the header starts right away.
+    cctx.set_parameter(zstd_safe::CParameter::Format(
+        zstd_safe::FrameFormat::Magicless,
+    ))
+    .unwrap();
+    let written = cctx.compress2(&mut buffer[..], INPUT).unwrap();
+    let magicless = &buffer[..written];
+    assert!(zstd_safe::get_frame_header(magicless).is_err());
+    let header = zstd_safe::get_frame_header_advanced(
+        magicless,
+        zstd_safe::FrameFormat::Magicless,
+    )
+    .unwrap();
+    assert_eq!(header.content_size, Some(INPUT.len() as u64));
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_skippable_frame() {
+    let mut buffer = std::vec![0u8; 256];
+    let written =
+        zstd_safe::write_skippable_frame(&mut buffer[..], b"metadata", 3)
+            .unwrap();
+    let frame = &buffer[..written];
+    assert_eq!(written, zstd_safe::SKIPPABLEHEADERSIZE as usize + 8);
+    assert!(zstd_safe::is_skippable_frame(frame));
+    assert!(!zstd_safe::is_frame(b"foo"));
+
+    let mut payload = std::vec![0u8; 16];
+    let (read, magic_variant) =
+        zstd_safe::read_skippable_frame(&mut payload[..], frame).unwrap();
+    assert_eq!(&payload[..read], b"metadata");
+    assert_eq!(magic_variant, 3);
+
+    // A regular frame is not skippable.
+    let compressed = &mut buffer[..];
+    let written = zstd_safe::compress(compressed, INPUT, 1).unwrap();
+    assert!(!zstd_safe::is_skippable_frame(&buffer[..written]));
+    assert!(zstd_safe::read_skippable_frame(
+        &mut payload[..],
+        &buffer[..written]
+    )
+    .is_err());
+
+    // Invalid magic variant
+    assert!(
+        zstd_safe::write_skippable_frame(&mut buffer[..], b"", 16).is_err()
+    );
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_sequences() {
+    let mut cctx = zstd_safe::CCtx::default();
+    let mut sequences = std::vec![
+        zstd_safe::Sequence::default();
+        zstd_safe::sequence_bound(INPUT.len())
+    ];
+    let n = cctx.generate_sequences(&mut sequences, INPUT).unwrap();
+    sequences.truncate(n);
+    assert!(sequences.last().unwrap().is_block_delimiter());
+
+    let total: usize = sequences
+        .iter()
+        .map(|s| (s.literal_length + s.match_length) as usize)
+        .sum();
+    assert_eq!(total, INPUT.len());
+
+    let mut compressed =
+        std::vec![0u8; zstd_safe::compress_bound(INPUT.len())];
+    let mut decompressed = std::vec![0u8; INPUT.len()];
+
+    // With explicit block delimiters.
+    let mut cctx = zstd_safe::CCtx::default();
+    cctx.set_parameter(zstd_safe::CParameter::BlockDelimiters(true))
+        .unwrap();
+    cctx.set_parameter(zstd_safe::CParameter::ValidateSequences(true))
+        .unwrap();
+    let written = cctx
+        .compress_sequences(&mut compressed[..], &sequences, INPUT)
+        .unwrap();
+    let read =
+        zstd_safe::decompress(&mut decompressed[..], &compressed[..written])
+            .unwrap();
+    assert_eq!(&decompressed[..read], INPUT);
+
+    // Without block delimiters.
+    let n = zstd_safe::merge_block_delimiters(&mut sequences);
+    sequences.truncate(n);
+    assert!(!sequences.iter().any(|s| s.is_block_delimiter()));
+
+    let mut cctx = zstd_safe::CCtx::default();
+    let written = cctx
+        .compress_sequences(&mut compressed[..], &sequences, INPUT)
+        .unwrap();
+    let read =
+        zstd_safe::decompress(&mut decompressed[..], &compressed[..written])
+            .unwrap();
+    assert_eq!(&decompressed[..read], INPUT);
+
+    // Sequences reading out of bounds are rejected, even without
+    // `ValidateSequences`.
+    let mut long = sequences.clone();
+    long[0].literal_length += 1024;
+    assert!(cctx
+        .compress_sequences(&mut compressed[..], &long, INPUT)
+        .is_err());
+
+    let mut short = sequences.clone();
+    short[0].match_length = 2;
+    assert!(cctx
+        .compress_sequences(&mut compressed[..], &short, INPUT)
+        .is_err());
+    assert_eq!(
+        cctx.get_parameter(zstd_safe::CParameter::ValidateSequences(false)),
+        Ok(0)
+    );
+
+    // Offsets are only checked with `ValidateSequences`.
+    let mut far = sequences.clone();
+    far[0].offset = 1 << 24;
+    far[0].match_length = 16;
+    let mut cctx = zstd_safe::CCtx::default();
+    cctx.set_parameter(zstd_safe::CParameter::ValidateSequences(true))
+        .unwrap();
+    assert!(cctx
+        .compress_sequences(&mut compressed[..], &far, INPUT)
+        .is_err());
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_static_contexts() {
+    let dict = &INPUT[..INPUT.len() / 2];
+
+    let mut cdict_workspace =
+        std::vec![0u8; zstd_safe::estimate_cdict_size(dict.len(), 1)];
+    let cdict =
+        zstd_safe::StaticCDict::init(&mut cdict_workspace, dict, 1).unwrap();
+    let mut cctx_workspace = std::vec![0u8; zstd_safe::estimate_cctx_size(1)];
+    let mut cctx = zstd_safe::CCtx::init_static(&mut cctx_workspace).unwrap();
+    cctx.ref_cdict(&cdict).unwrap();
+
+    let mut compressed =
+        std::vec![0u8; zstd_safe::compress_bound(INPUT.len())];
+    let written = cctx.compress2(&mut compressed[..], INPUT).unwrap();
+
+    let mut ddict_workspace =
+        std::vec![0u8; zstd_safe::estimate_ddict_size(dict.len())];
+    let ddict =
+        zstd_safe::StaticDDict::init(&mut ddict_workspace, dict).unwrap();
+    let mut dctx_workspace = std::vec![0u8; zstd_safe::estimate_dctx_size()];
+    let mut dctx = zstd_safe::DCtx::init_static(&mut dctx_workspace).unwrap();
+    dctx.ref_ddict(&ddict).unwrap();
+
+    let mut decompressed = std::vec![0u8; INPUT.len()];
+    let read = dctx
+        .decompress(&mut decompressed[..], &compressed[..written])
+        .unwrap();
+    assert_eq!(&decompressed[..read], INPUT);
+
+    // Too small for anything.
+    let mut workspace = [0u8; 16];
+    assert!(zstd_safe::CCtx::init_static(&mut workspace).is_none());
+    assert!(zstd_safe::DCtx::init_static(&mut workspace).is_none());
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_custom_allocator() {
+    use core::alloc::{GlobalAlloc, Layout};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct Counting {
+        allocated: AtomicUsize,
+        live: AtomicUsize,
+    }
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            self.allocated.fetch_add(layout.size(), Ordering::Relaxed);
+            self.live.fetch_add(1, Ordering::Relaxed);
+            std::alloc::System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            self.live.fetch_sub(1, Ordering::Relaxed);
+            std::alloc::System.dealloc(ptr, layout)
+        }
+    }
+
+    let allocator = Counting::default();
+    let dict = &INPUT[..INPUT.len() / 2];
+    let mut compressed =
+        std::vec![0u8; zstd_safe::compress_bound(INPUT.len())];
+    let mut decompressed = std::vec![0u8; INPUT.len()];
+
+    {
+        let cdict =
+            zstd_safe::CDict::try_create_with_allocator(dict, 1, &allocator)
+                .unwrap();
+        let mut cctx =
+            zstd_safe::CCtx::try_create_with_allocator(&allocator).unwrap();
+        cctx.ref_cdict(&cdict).unwrap();
+        let written = cctx.compress2(&mut compressed[..], INPUT).unwrap();
+
+        let ddict =
+            zstd_safe::DDict::try_create_with_allocator(dict, &allocator)
+                .unwrap();
+        let mut dctx =
+            zstd_safe::DCtx::try_create_with_allocator(&allocator).unwrap();
+        dctx.ref_ddict(&ddict).unwrap();
+        let read = dctx
+            .decompress(&mut decompressed[..], &compressed[..written])
+            .unwrap();
+        assert_eq!(&decompressed[..read], INPUT);
+
+        assert!(allocator.live.load(Ordering::Relaxed) > 0);
+    }
+
+    assert!(allocator.allocated.load(Ordering::Relaxed) > 0);
+    // Everything was freed through the allocator.
+    assert_eq!(allocator.live.load(Ordering::Relaxed), 0);
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_estimate_sizes() {
+    assert!(
+        zstd_safe::estimate_cstream_size(3) > zstd_safe::estimate_cctx_size(3)
+    );
+    assert!(
+        zstd_safe::estimate_cctx_size_for(3, None, Some(1000)).unwrap()
+            < zstd_safe::estimate_cctx_size(3)
+    );
+    assert!(
+        zstd_safe::estimate_cstream_size_for(3, Some(10), None).unwrap()
+            < zstd_safe::estimate_cstream_size_for(3, Some(20), None).unwrap()
+    );
+    assert!(zstd_safe::estimate_cctx_size_for(3, Some(64), None).is_err());
+    assert!(zstd_safe::estimate_cstream_size_for(3, Some(1), None).is_err());
+
+    let mut compressed =
+        std::vec![0u8; zstd_safe::compress_bound(INPUT.len())];
+    let written = zstd_safe::compress(&mut compressed[..], INPUT, 1).unwrap();
+    let from_frame =
+        zstd_safe::estimate_dstream_size_from_frame(&compressed[..written])
+            .unwrap();
+    assert!(from_frame >= zstd_safe::estimate_dstream_size(0));
+    assert!(zstd_safe::estimate_dstream_size_from_frame(b"garbage").is_err());
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_compression_parameters() {
+    let params = zstd_safe::CompressionParameters::new(3, None, 0);
+    params.check().unwrap();
+
+    // Small inputs get smaller windows.
+    let small = zstd_safe::CompressionParameters::new(3, Some(1000), 0);
+    assert!(small.window_log < params.window_log);
+    assert_eq!(params.adjust(Some(1000), 0).window_log, small.window_log);
+
+    let mut invalid = params;
+    invalid.window_log = 100;
+    assert_eq!(invalid.check(), Err(zstd_safe::CParameter::WindowLog(100)));
+    invalid.adjust(None, 0).check().unwrap();
+
+    // Each field is checked against the bounds of its parameter.
+    fn with(
+        mut params: zstd_safe::CompressionParameters,
+        field: zstd_safe::CParameter,
+    ) -> zstd_safe::CompressionParameters {
+        use zstd_safe::CParameter::*;
+        match field {
+            WindowLog(value) => params.window_log = value,
+            ChainLog(value) => params.chain_log = value,
+            HashLog(value) => params.hash_log = value,
+            SearchLog(value) => params.search_log = value,
+            MinMatch(value) => params.min_match = value,
+            TargetLength(value) => params.target_length = value,
+            _ => unreachable!(),
+        }
+        params
+    }
+
+    let fields: [fn(u32) -> zstd_safe::CParameter; 6] = [
+        zstd_safe::CParameter::WindowLog,
+        zstd_safe::CParameter::ChainLog,
+        zstd_safe::CParameter::HashLog,
+        zstd_safe::CParameter::SearchLog,
+        zstd_safe::CParameter::MinMatch,
+        zstd_safe::CParameter::TargetLength,
+    ];
+    for field in &fields {
+        let bounds = field(0).bounds().unwrap();
+        let (min, max) = (*bounds.start() as u32, *bounds.end() as u32);
+
+        with(params, field(min)).check().unwrap();
+        with(params, field(max)).check().unwrap();
+        if min > 0 {
+            let below = field(min - 1);
+            assert_eq!(with(params, below).check(), Err(below));
+        }
+        let above = field(max + 1);
+        assert_eq!(with(params, above).check(), Err(above));
+    }
+
+    let mut cctx = zstd_safe::CCtx::create();
+    assert!(cctx.set_compression_parameters(&invalid).is_err());
+
+    let mut params =
+        zstd_safe::Parameters::new(19, Some(INPUT.len() as u64), 0);
+    assert_eq!(params.frame, zstd_safe::FrameParameters::default());
+    params.frame.checksum_flag = true;
+    cctx.set_parameters(&params).unwrap();
+
+    let mut compressed =
+        std::vec![0u8; zstd_safe::compress_bound(INPUT.len())];
+    let written = cctx.compress2(&mut compressed[..], INPUT).unwrap();
+    let header = zstd_safe::get_frame_header(&compressed[..written]).unwrap();
+    assert!(header.checksum_flag);
+
+    let mut decompressed = std::vec![0u8; INPUT.len()];
+    let read =
+        zstd_safe::decompress(&mut decompressed[..], &compressed[..written])
+            .unwrap();
+    assert_eq!(&decompressed[..read], INPUT);
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_cctx_params() {
+    use zstd_safe::{CCtxParams, CParameter};
+
+    let mut params = CCtxParams::create();
+    params.init(19).unwrap();
+    params
+        .set_parameter(CParameter::ChecksumFlag(true))
+        .unwrap();
+    params.set_parameter(CParameter::WindowLog(20)).unwrap();
+    assert!(params.set_parameter(CParameter::WindowLog(100)).is_err());
+    assert_eq!(
+        params.get_parameter(CParameter::CompressionLevel(0)),
+        Ok(19)
+    );
+    assert_eq!(params.get_parameter(CParameter::WindowLog(0)), Ok(20));
+
+    let copy = params.clone();
+    params.reset().unwrap();
+    assert_eq!(params.get_parameter(CParameter::WindowLog(0)), Ok(0));
+    assert_eq!(copy.get_parameter(CParameter::WindowLog(0)), Ok(20));
+    assert_eq!(copy.get_parameter(CParameter::ChecksumFlag(false)), Ok(1));
+
+    let mut cctx = zstd_safe::CCtx::create();
+    cctx.set_parameters_using_cctx_params(&copy).unwrap();
+    let mut compressed =
+        std::vec![0u8; zstd_safe::compress_bound(INPUT.len())];
+    let written = cctx.compress2(&mut compressed[..], INPUT).unwrap();
+    let header = zstd_safe::get_frame_header(&compressed[..written]).unwrap();
+    assert!(header.checksum_flag);
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_cctx_params_clone() {
+    use zstd_safe::{CCtxParams, CParameter};
+
+    // Set every cloned parameter to its maximum value, when supported.
+    let params = CCtxParams::create();
+    for &param in CCtxParams::CLONED_PARAMETERS {
+        let max = *param.bounds().unwrap().end();
+        let (param, _) = param.to_raw();
+        unsafe {
+            zstd_sys::ZSTD_CCtxParams_setParameter(
+                params.0.as_ptr(),
+                param,
+                max,
+            );
+        }
+    }
+    let max = *CParameter::WindowLog(0).bounds().unwrap().end();
+    assert_eq!(params.get_parameter(CParameter::WindowLog(0)), Ok(max));
+
+    let copy = params.clone();
+    for &param in CCtxParams::CLONED_PARAMETERS {
+        assert_eq!(
+            copy.get_parameter(param),
+            params.get_parameter(param),
+            "{:?}",
+            param
+        );
+    }
+}
+
+#[test]
+fn test_parameter_bounds() {
+    use zstd_safe::{CParameter, DParameter};
+
+    let levels = CParameter::CompressionLevel(0).bounds().unwrap();
+    assert_eq!(*levels.end(), zstd_safe::max_c_level());
+    assert!(CParameter::WindowLog(0).bounds().unwrap().contains(&20));
+    assert!(DParameter::WindowLogMax(0).bounds().unwrap().contains(&27));
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_get_parameter() {
+    use zstd_safe::{CParameter, DParameter};
+
+    let mut cctx = zstd_safe::CCtx::create();
+    cctx.set_parameter(CParameter::CompressionLevel(7)).unwrap();
+    cctx.set_parameter(CParameter::ChecksumFlag(true)).unwrap();
+    assert_eq!(cctx.get_parameter(CParameter::CompressionLevel(0)), Ok(7));
+    assert_eq!(cctx.get_parameter(CParameter::ChecksumFlag(false)), Ok(1));
+
+    let mut dctx = zstd_safe::DCtx::create();
+    dctx.set_parameter(DParameter::WindowLogMax(24)).unwrap();
+    assert_eq!(dctx.get_parameter(DParameter::WindowLogMax(0)), Ok(24));
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_bufferless() {
+    use zstd_safe::NextInputType;
+
+    let mut cctx = zstd_safe::CCtx::create();
+    let mut compressed =
+        std::vec![0u8; zstd_safe::compress_bound(INPUT.len())];
+    let mut written = 0;
+    {
+        let (first, last) = INPUT.split_at(INPUT.len() / 2);
+        let mut session = cctx.compress_begin(3).unwrap();
+        written += session
+            .compress_continue(&mut compressed[written..], first)
+            .unwrap();
+        written += session
+            .compress_end(&mut compressed[written..], last)
+            .unwrap();
+    }
+    compressed.truncate(written);
+    assert_eq!(
+        zstd_safe::get_frame_content_size(&compressed).unwrap(),
+        None
+    );
+
+    let mut dctx = zstd_safe::DCtx::create();
+    let mut decompressed = std::vec![0u8; INPUT.len()];
+    let mut decompressed_size = 0;
+    {
+        let mut session = dctx.decompress_begin().unwrap();
+        assert_eq!(session.next_input_type(), NextInputType::FrameHeader);
+
+        let mut src = &compressed[..];
+        let mut dst = &mut decompressed[..];
+        loop {
+            let size = session.next_src_size();
+            if size == 0 {
+                break;
+            }
+            let (written, rest) =
+                session.decompress_continue(dst, &src[..size]).unwrap();
+            decompressed_size += written.len();
+            dst = rest;
+            src = &src[size..];
+        }
+        assert!(src.is_empty());
+    }
+    assert_eq!(&decompressed[..decompressed_size], INPUT);
+
+    // Dropping an unfinished session ends the frame, so the block API can't
+    // use the borrowed input anymore.
+    let mut block = std::vec![0u8; zstd_safe::compress_bound(INPUT.len())];
+    {
+        let input = INPUT.to_vec();
+        let mut session = cctx.compress_begin(3).unwrap();
+        session.compress_continue(&mut block[..], &input).unwrap();
+    }
+    assert!(cctx.compress_block(&mut block[..], INPUT).is_err());
+}
+
+#[test]
+fn test_error_kind() {
+    use zstd_safe::ErrorKind;
+
+    let mut buffer = [0u8; 4];
+    let code = zstd_safe::compress(&mut buffer[..], INPUT, 1).unwrap_err();
+    assert_eq!(ErrorKind::from_code(code), Some(ErrorKind::DstSizeTooSmall));
+    assert_eq!(ErrorKind::from_code(0), None);
+
+    // Error codes are negated `ZSTD_ErrorCode` values.
+    let error = |value: usize| 0usize.wrapping_sub(value);
+    assert_eq!(unsafe { zstd_safe::ZSTD_getErrorCode(error(72)) }, 72);
+    assert_eq!(
+        ErrorKind::from_code(error(72)),
+        Some(ErrorKind::SrcSizeWrong)
+    );
+    assert_eq!(
+        ErrorKind::from_code(error(107)),
+        Some(ErrorKind::ExternalSequencesInvalid)
+    );
+
+    // Values zstd doesn't use (yet) are unknown errors.
+    assert_eq!(ErrorKind::from_code(error(2)), Some(ErrorKind::Unknown));
+    assert_eq!(ErrorKind::from_code(error(119)), Some(ErrorKind::Unknown));
+}
+
+#[cfg(all(feature = "experimental", feature = "zdict_builder"))]
+#[test]
+fn test_cover_training() {
+    let samples: Vec<&str> = LONG_CONTENT.lines().collect();
+    let sizes: Vec<usize> = samples.iter().map(|s| s.len()).collect();
+    let data = samples.concat();
+    let mut dict = std::vec![0u8; 4096];
+
+    let parameters = zstd_safe::CoverParameters {
+        k: 200,
+        d: 8,
+        ..Default::default()
+    };
+    let size = zstd_safe::train_from_buffer_cover(
+        &mut dict[..],
+        data.as_bytes(),
+        &sizes,
+        &parameters,
+    )
+    .unwrap();
+    assert!(zstd_safe::get_dict_id(&dict[..size]).is_some());
+
+    let mut parameters = zstd_safe::FastCoverParameters {
+        d: 8,
+        steps: 4,
+        ..Default::default()
+    };
+    let size = zstd_safe::optimize_train_from_buffer_fast_cover(
+        &mut dict[..],
+        data.as_bytes(),
+        &sizes,
+        &mut parameters,
+    )
+    .unwrap();
+    assert!(size > 0);
+    assert_eq!(parameters.d, 8);
+    assert_ne!(parameters.k, 0);
+
+    let size = zstd_safe::train_from_buffer_legacy(
+        &mut dict[..],
+        data.as_bytes(),
+        &sizes,
+        &Default::default(),
+    )
+    .unwrap();
+    assert!(size > 0);
+}
+
+#[cfg(feature = "zdict_builder")]
+#[test]
+fn test_finalize_dictionary() {
+    let samples: Vec<&str> = LONG_CONTENT.lines().collect();
+    let sizes: Vec<usize> = samples.iter().map(|s| s.len()).collect();
+    let data = samples.concat();
+    let content = &LONG_CONTENT.as_bytes()[..2048];
+
+    let mut dict = std::vec![0u8; 4096];
+    let parameters = zstd_safe::DictParameters {
+        dict_id: 1234,
+        ..Default::default()
+    };
+    let size = zstd_safe::finalize_dictionary(
+        &mut dict[..],
+        content,
+        data.as_bytes(),
+        &sizes,
+        &parameters,
+    )
+    .unwrap();
+    assert!(size > content.len());
+    assert!(dict[..size].ends_with(content));
+    assert_eq!(
+        zstd_safe::get_dict_id(&dict[..size]).map(|id| id.get()),
+        Some(1234)
+    );
+    let header_size = zstd_safe::get_dict_header_size(&dict[..size]).unwrap();
+    assert_eq!(header_size, size - content.len());
+    assert!(zstd_safe::get_dict_header_size(content).is_err());
+
+    #[cfg(feature = "experimental")]
+    {
+        let mut dict = std::vec![0u8; 4096];
+        let offset = dict.len() - content.len();
+        dict[offset..].copy_from_slice(content);
+        let size = zstd_safe::add_entropy_tables_from_buffer(
+            &mut dict[..],
+            content.len(),
+            data.as_bytes(),
+            &sizes,
+        )
+        .unwrap();
+        assert!(dict[..size].ends_with(content));
+        assert!(zstd_safe::get_dict_id(&dict[..size]).is_some());
+
+        // Buffers too short for a dictionary are rejected.
+        let mut dict = [0u8; 4];
+        assert!(zstd_safe::add_entropy_tables_from_buffer(
+            &mut dict[..],
+            0,
+            data.as_bytes(),
+            &sizes,
+        )
+        .is_err());
+    }
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn test_dict_content_type() {
+    use zstd_safe::{DictContentType, DictLoadMethod};
+
+    // Raw content that happens to start with the dictionary magic number.
+    let mut dict = zstd_safe::MAGIC_DICTIONARY.to_le_bytes().to_vec();
+    dict.extend_from_slice(INPUT);
+
+    let mut cctx = zstd_safe::CCtx::create();
+    cctx.load_dictionary_advanced(
+        &dict,
+        DictLoadMethod::ByRef,
+        DictContentType::FullDict,
+    )
+    .unwrap();
+    let mut compressed =
+        std::vec![0u8; zstd_safe::compress_bound(INPUT.len())];
+    assert!(cctx.compress2(&mut compressed[..], INPUT).is_err());
+
+    cctx.load_dictionary_advanced(
+        &dict,
+        DictLoadMethod::ByCopy,
+        DictContentType::RawContent,
+    )
+    .unwrap();
+    let written = cctx.compress2(&mut compressed[..], INPUT).unwrap();
+
+    let mut dctx = zstd_safe::DCtx::create();
+    dctx.load_dictionary_advanced(
+        &dict,
+        DictLoadMethod::ByRef,
+        DictContentType::RawContent,
+    )
+    .unwrap();
+    let mut decompressed = std::vec![0u8; INPUT.len()];
+    let size = dctx
+        .decompress(&mut decompressed[..], &compressed[..written])
+        .unwrap();
+    assert_eq!(&decompressed[..size], INPUT);
+
+    assert!(zstd_safe::CDict::try_create_advanced(
+        &dict,
+        DictLoadMethod::ByRef,
+        DictContentType::FullDict,
+        3
+    )
+    .is_none());
+    let cdict = zstd_safe::CDict::try_create_advanced(
+        &dict,
+        DictLoadMethod::ByRef,
+        DictContentType::RawContent,
+        3,
+    )
+    .unwrap();
+    assert_eq!(cdict.get_dict_id(), None);
+    assert!(zstd_safe::DDict::try_create_advanced(
+        &dict,
+        DictLoadMethod::ByCopy,
+        DictContentType::RawContent,
+    )
+    .is_some());
+
+    cctx.ref_prefix_advanced(&dict, DictContentType::RawContent)
+        .unwrap();
+    let written = cctx.compress2(&mut compressed[..], INPUT).unwrap();
+    dctx.ref_prefix_advanced(&dict, DictContentType::RawContent)
+        .unwrap();
+    let size = dctx
+        .decompress(&mut decompressed[..], &compressed[..written])
+        .unwrap();
+    assert_eq!(&decompressed[..size], INPUT);
+}